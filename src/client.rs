@@ -140,11 +140,18 @@ use crate::ext::Protocol;
 #[cfg(feature = "unstable")]
 use crate::frame::ExperimentalSettings;
 use crate::frame::{
-    Headers, Priorities, Pseudo, PseudoOrder, Reason, Settings, SettingsOrder, StreamDependency,
-    StreamId,
+    self, AltSvc, ExtensionFrame, HeaderOrder, Headers, PaddingPolicy, Priorities, Priority,
+    Pseudo, PseudoId, PseudoOrder, Reason, SettingId, Settings, SettingsOrder, StreamDependency,
+    StreamId, WindowUpdate, DEFAULT_INITIAL_WINDOW_SIZE,
+};
+use crate::hpack::{HuffmanPolicy, IndexingPolicy, SharedIndexingPolicy};
+use crate::observer::{FrameObserver, SharedFrameObserver};
+use crate::proto::{self, Error, SchedulingStrategy, WindowSize};
+use crate::stats::{ConnectionStats, StreamStats};
+use crate::{
+    tracing, ExtensionFrames, Fingerprint, FlowControl, InvalidFingerprint, PingPong, RecvStream,
+    SendStream,
 };
-use crate::proto::{self, Error};
-use crate::{tracing, FlowControl, PingPong, RecvStream, SendStream};
 
 #[cfg(feature = "tracing")]
 use ::tracing::Instrument;
@@ -327,6 +334,21 @@ pub struct Builder {
     /// Initial target window size for new connections.
     initial_target_connection_window_size: Option<u32>,
 
+    /// Whether receive windows grow with the bandwidth-delay product.
+    adaptive_window: bool,
+
+    /// The largest size adaptive receive windows grow to.
+    max_adaptive_window_size: u32,
+
+    /// How long to wait without receiving a frame before sending a PING.
+    keep_alive_interval: Option<Duration>,
+
+    /// How long to wait for a keep-alive PING to be acknowledged.
+    keep_alive_timeout: Duration,
+
+    /// Whether keep-alive PINGs are sent while there are no streams.
+    keep_alive_while_idle: bool,
+
     /// Maximum amount of bytes to "buffer" for writing per stream.
     max_send_buffer_size: usize,
 
@@ -353,11 +375,139 @@ pub struct Builder {
     /// The headers frame pseudo order
     headers_pseudo_order: Option<PseudoOrder>,
 
+    /// The headers frame regular field order
+    headers_order: Option<HeaderOrder>,
+
     /// The headers frame stream dependency
     headers_stream_dependency: Option<StreamDependency>,
 
     /// Priority stream list
     priorities: Option<Priorities>,
+
+    /// Decides how outgoing header fields are indexed by HPACK
+    indexing_policy: Option<SharedIndexingPolicy>,
+
+    /// Decides when outgoing header literals are Huffman encoded
+    huffman_policy: Option<HuffmanPolicy>,
+
+    /// How outgoing DATA, HEADERS and PUSH_PROMISE frames are padded
+    padding_policy: PaddingPolicy,
+
+    /// Called with every frame read and written
+    frame_observer: Option<SharedFrameObserver>,
+
+    /// How the DATA frames of concurrent streams share the connection
+    scheduling_strategy: SchedulingStrategy,
+
+    /// The frames sent after the SETTINGS frame of the connection preface
+    preface: Option<PrefaceScript>,
+}
+
+/// A curated HTTP/2 connection profile that mimics a web browser.
+///
+/// A profile covers everything a browser decides on its own when it opens an
+/// HTTP/2 connection: the SETTINGS frame parameters and their order, the
+/// connection-level WINDOW_UPDATE, and the shape of request HEADERS frames
+/// (pseudo-header order, stream dependency and regular header order).
+///
+/// Profiles are applied to a [`Builder`] with [`Builder::profile`]. Settings
+/// that a profile does not cover are left untouched, so applying a profile to
+/// a fresh `Builder` reproduces the browser's connection preface exactly.
+///
+/// # Examples
+///
+/// ```
+/// # use tokio::io::{AsyncRead, AsyncWrite};
+/// # use http2::client::*;
+/// # use bytes::Bytes;
+/// #
+/// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+/// #     -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), http2::Error>
+/// # {
+/// let client_fut = Builder::new()
+///     .profile(Profile::chrome())
+///     .handshake(my_io);
+/// # client_fut.await
+/// # }
+/// #
+/// # pub fn main() {}
+/// ```
+#[derive(Clone, Debug)]
+pub struct Profile {
+    header_table_size: Option<u32>,
+    enable_push: Option<bool>,
+    max_concurrent_streams: Option<u32>,
+    initial_window_size: Option<u32>,
+    max_frame_size: Option<u32>,
+    max_header_list_size: Option<u32>,
+    no_rfc7540_priorities: Option<bool>,
+    settings_order: SettingsOrder,
+    initial_connection_window_size: Option<u32>,
+    headers_pseudo_order: PseudoOrder,
+    headers_stream_dependency: Option<StreamDependency>,
+    headers_order: HeaderOrder,
+}
+
+/// The frames a client sends between the connection preface magic and its
+/// first request.
+///
+/// By default a client sends its SETTINGS frame, followed by a connection
+/// level WINDOW_UPDATE frame if [`Builder::initial_connection_window_size`]
+/// was set. A script replaces that sequence: the SETTINGS frame still comes
+/// first, as RFC 9113 requires, and the frames of the script follow it in the
+/// order they were pushed. No other frame is sent before the first request:
+/// the PRIORITY frames configured with [`Builder::priorities`] are not sent,
+/// and belong in the script instead.
+///
+/// Everything is written in a single batch together with the SETTINGS frame,
+/// unless the script contains [flush] points.
+///
+/// Scripts are applied to a [`Builder`] with [`Builder::preface`].
+///
+/// # Examples
+///
+/// ```
+/// # use tokio::io::{AsyncRead, AsyncWrite};
+/// # use http2::client::*;
+/// # use bytes::Bytes;
+/// #
+/// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+/// #     -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), http2::Error>
+/// # {
+/// // SETTINGS, then a PING, then a connection level WINDOW_UPDATE.
+/// let script = PrefaceScript::builder()
+///     .ping([0; 8])
+///     .window_update(12_517_377)
+///     .build();
+///
+/// let client_fut = Builder::new().preface(script).handshake(my_io);
+/// # client_fut.await
+/// # }
+/// #
+/// # pub fn main() {}
+/// ```
+///
+/// [flush]: PrefaceScriptBuilder::flush
+#[derive(Clone, Debug, Default)]
+pub struct PrefaceScript {
+    frames: Vec<PrefaceFrame>,
+}
+
+/// A builder for constructing a [`PrefaceScript`].
+///
+/// Frames that are not valid in a connection preface are ignored.
+#[derive(Debug)]
+pub struct PrefaceScriptBuilder {
+    frames: Vec<PrefaceFrame>,
+    window_size: WindowSize,
+}
+
+#[derive(Clone, Debug)]
+enum PrefaceFrame {
+    WindowUpdate(WindowUpdate),
+    Priority(Priority),
+    Ping([u8; 8]),
+    Flush,
 }
 
 #[derive(Debug)]
@@ -437,6 +587,21 @@ where
     /// If no request body or trailers are to be sent, set `end_of_stream` to
     /// `true` and drop the returned [`SendStream`] instance.
     ///
+    /// # Request extensions
+    ///
+    /// The following extensions of `request` take precedence over the
+    /// connection's configuration for this request only:
+    ///
+    /// * [`HeaderOrder`]: the order of regular header fields.
+    /// * [`PseudoOrder`]: the order of pseudo-header fields.
+    /// * [`StreamDependency`]: the stream dependency and weight of the HEADERS
+    ///   frame.
+    /// * [`Priorities`]: the PRIORITY frames sent before the HEADERS frame.
+    ///
+    /// Requests received by a server carry the order of their pseudo-header
+    /// fields as a [`PseudoOrder`] extension, so a proxy forwarding them keeps
+    /// that order.
+    ///
     /// # A note on HTTP versions
     ///
     /// The provided `Request` will be encoded differently depending on the
@@ -524,6 +689,10 @@ where
     /// [`SendStream`]: ../struct.SendStream.html
     /// [`SendStream::send_data`]: ../struct.SendStream.html#method.send_data
     /// [`SendStream::send_trailers`]: ../struct.SendStream.html#method.send_trailers
+    /// [`HeaderOrder`]: crate::ext::HeaderOrder
+    /// [`PseudoOrder`]: crate::ext::PseudoOrder
+    /// [`StreamDependency`]: crate::ext::StreamDependency
+    /// [`Priorities`]: crate::ext::Priorities
     pub fn send_request(
         &mut self,
         request: Request<()>,
@@ -563,6 +732,22 @@ where
         self.inner.is_extended_connect_protocol_enabled()
     }
 
+    /// Returns the origins the server advertised in [ORIGIN frames][1].
+    ///
+    /// Returns `None` until the server sends an ORIGIN frame, in which case
+    /// the usual connection reuse rules apply. Otherwise, the origins of
+    /// every ORIGIN frame received so far are returned, in the order they
+    /// were first advertised, as ASCII serializations such as
+    /// `https://example.com`.
+    ///
+    /// The origin the connection was established for is always part of the
+    /// origin set, but it is only known to the caller and is not included.
+    ///
+    /// [1]: https://datatracker.ietf.org/doc/html/rfc8336
+    pub fn origin_set(&self) -> Option<Vec<String>> {
+        self.inner.origin_set()
+    }
+
     /// Returns the current max send streams
     pub fn current_max_send_streams(&self) -> usize {
         self.inner.current_max_send_streams()
@@ -674,13 +859,25 @@ impl Builder {
             reset_stream_max: proto::DEFAULT_RESET_STREAM_MAX,
             pending_accept_reset_stream_max: proto::DEFAULT_REMOTE_RESET_STREAM_MAX,
             initial_target_connection_window_size: None,
+            adaptive_window: false,
+            max_adaptive_window_size: proto::DEFAULT_MAX_ADAPTIVE_WINDOW_SIZE,
+            keep_alive_interval: None,
+            keep_alive_timeout: Duration::from_secs(proto::DEFAULT_KEEP_ALIVE_TIMEOUT_SECS),
+            keep_alive_while_idle: false,
             initial_max_send_streams: usize::MAX,
             settings: Default::default(),
             stream_id: 1.into(),
             local_max_error_reset_streams: Some(proto::DEFAULT_LOCAL_RESET_COUNT_MAX),
             headers_pseudo_order: None,
+            headers_order: None,
             headers_stream_dependency: None,
             priorities: None,
+            indexing_policy: None,
+            huffman_policy: None,
+            padding_policy: PaddingPolicy::None,
+            frame_observer: None,
+            scheduling_strategy: SchedulingStrategy::Fifo,
+            preface: None,
         }
     }
 
@@ -754,6 +951,130 @@ impl Builder {
         self
     }
 
+    /// Enables adaptive flow control.
+    ///
+    /// The connection sends a PING while DATA is being received, and
+    /// estimates the bandwidth-delay product of the link from the DATA received
+    /// before the PING is acknowledged. When the estimate shows that the
+    /// receive windows limit throughput, the initial window size of streams
+    /// and the target connection window grow to twice the bytes received, up
+    /// to [`max_adaptive_window_size`]. Windows never shrink below the
+    /// configured sizes.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::client::*;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), http2::Error>
+    /// # {
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new()
+    ///     .adaptive_window(true)
+    ///     .max_adaptive_window_size(8 * 1024 * 1024)
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`max_adaptive_window_size`]: Builder::max_adaptive_window_size
+    pub fn adaptive_window(&mut self, enabled: bool) -> &mut Self {
+        self.adaptive_window = enabled;
+        self
+    }
+
+    /// Sets the largest size (in octets) receive windows grow to when
+    /// [`adaptive_window`] is enabled.
+    ///
+    /// The default value is 16 MB.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `max` is larger than 2^31-1.
+    ///
+    /// [`adaptive_window`]: Builder::adaptive_window
+    pub fn max_adaptive_window_size(&mut self, max: u32) -> &mut Self {
+        assert!(max <= proto::MAX_WINDOW_SIZE);
+        self.max_adaptive_window_size = max;
+        self
+    }
+
+    /// Sets the interval at which keep-alive PINGs are sent.
+    ///
+    /// Once `interval` passes without any frame received, the connection
+    /// sends a PING. If it is not acknowledged within the
+    /// [`keep_alive_timeout`], the connection is considered dead: it is
+    /// closed, and it and its streams fail with an error for which
+    /// [`Error::is_keep_alive_timeout`] returns true.
+    ///
+    /// The connection must be polled within a Tokio runtime that has the
+    /// time driver enabled.
+    ///
+    /// Keep-alive is disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::client::*;
+    /// # use std::time::Duration;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), http2::Error>
+    /// # {
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new()
+    ///     .keep_alive_interval(Duration::from_secs(30))
+    ///     .keep_alive_timeout(Duration::from_secs(10))
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`keep_alive_timeout`]: Builder::keep_alive_timeout
+    /// [`Error::is_keep_alive_timeout`]: crate::Error::is_keep_alive_timeout
+    pub fn keep_alive_interval(&mut self, interval: impl Into<Option<Duration>>) -> &mut Self {
+        self.keep_alive_interval = interval.into();
+        self
+    }
+
+    /// Sets how long to wait for a keep-alive PING to be acknowledged before
+    /// closing the connection.
+    ///
+    /// Only used if [`keep_alive_interval`] is set. The default value is 20
+    /// seconds.
+    ///
+    /// [`keep_alive_interval`]: Builder::keep_alive_interval
+    pub fn keep_alive_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.keep_alive_timeout = timeout;
+        self
+    }
+
+    /// Sets whether keep-alive PINGs are sent while the connection has no
+    /// open streams.
+    ///
+    /// When disabled, an idle connection is only checked once a new stream
+    /// is opened. Only used if [`keep_alive_interval`] is set. The default
+    /// value is `false`.
+    ///
+    /// [`keep_alive_interval`]: Builder::keep_alive_interval
+    pub fn keep_alive_while_idle(&mut self, enabled: bool) -> &mut Self {
+        self.keep_alive_while_idle = enabled;
+        self
+    }
+
     /// Indicates the size (in octets) of the largest HTTP/2 frame payload that the
     /// configured client is able to accept.
     ///
@@ -1219,16 +1540,251 @@ impl Builder {
     /// This determines the order in which pseudo-header fields (such as `:method`, `:scheme`, etc.)
     /// are encoded in the HEADERS frame. Customizing the order may be useful for interoperability
     /// or testing purposes.
+    ///
+    /// The order can be overridden for a single request by inserting a
+    /// [`PseudoOrder`] into the request's extensions.
+    ///
+    /// [`PseudoOrder`]: crate::ext::PseudoOrder
     pub fn headers_pseudo_order(&mut self, order: PseudoOrder) -> &mut Self {
         self.headers_pseudo_order = Some(order.into());
         self
     }
 
+    /// Sets the order of regular header fields for outgoing HEADERS frames.
+    ///
+    /// Fields named in `order` are encoded first, in the given order, and any
+    /// remaining fields follow in their `HeaderMap` iteration order. Pseudo-header
+    /// fields are not affected; see [`headers_pseudo_order`].
+    ///
+    /// The order can be overridden for a single request by inserting a
+    /// [`HeaderOrder`] into the request's extensions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::client::*;
+    /// # use http2::ext::HeaderOrder;
+    /// # use bytes::Bytes;
+    /// # use http::header;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), http2::Error>
+    /// # {
+    /// // `user-agent` is always encoded before `accept`.
+    /// let client_fut = Builder::new()
+    ///     .headers_order(
+    ///         HeaderOrder::builder()
+    ///             .push(header::USER_AGENT)
+    ///             .push(header::ACCEPT)
+    ///             .build(),
+    ///     )
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`headers_pseudo_order`]: #method.headers_pseudo_order
+    /// [`HeaderOrder`]: crate::ext::HeaderOrder
+    pub fn headers_order(&mut self, order: HeaderOrder) -> &mut Self {
+        self.headers_order = Some(order);
+        self
+    }
+
+    /// Sets the policy deciding how outgoing header fields are represented by
+    /// HPACK.
+    ///
+    /// For every field of a request's HEADERS frame, pseudo-headers included,
+    /// the policy picks between incremental indexing, no indexing and never
+    /// indexing. By default the encoder decides on its own. See
+    /// [`IndexingPolicy`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::client::*;
+    /// # use http2::ext::Indexing;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), http2::Error>
+    /// # {
+    /// // Cookies are never indexed.
+    /// let client_fut = Builder::new()
+    ///     .indexing_policy(|name: &str, _: &[u8]| match name {
+    ///         "cookie" => Indexing::NeverIndexed,
+    ///         _ => Indexing::Auto,
+    ///     })
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`IndexingPolicy`]: crate::ext::IndexingPolicy
+    pub fn indexing_policy<P: IndexingPolicy>(&mut self, policy: P) -> &mut Self {
+        self.indexing_policy = Some(SharedIndexingPolicy::new(policy));
+        self
+    }
+
+    /// Sets when string literals in outgoing header blocks are Huffman
+    /// encoded.
+    ///
+    /// By default every literal is Huffman encoded, even when that makes it
+    /// longer than the raw bytes. [`HuffmanPolicy::Shortest`] picks whichever
+    /// form is smaller, as nghttp2 does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::client::*;
+    /// # use http2::ext::HuffmanPolicy;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), http2::Error>
+    /// # {
+    /// let client_fut = Builder::new()
+    ///     .huffman_policy(HuffmanPolicy::Shortest)
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`HuffmanPolicy::Shortest`]: crate::ext::HuffmanPolicy::Shortest
+    pub fn huffman_policy(&mut self, policy: HuffmanPolicy) -> &mut Self {
+        self.huffman_policy = Some(policy);
+        self
+    }
+
+    /// Sets how outgoing HEADERS and DATA frames are padded.
+    ///
+    /// Frames are not padded by default. Padding sent in DATA frames counts
+    /// against flow control, and is reduced when the windows or the peer's
+    /// maximum frame size leave no room for it. See [`PaddingPolicy`] for
+    /// details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::client::*;
+    /// # use http2::ext::PaddingPolicy;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), http2::Error>
+    /// # {
+    /// // Pad every frame payload to a multiple of 64 bytes.
+    /// let client_fut = Builder::new()
+    ///     .padding_policy(PaddingPolicy::Block(64))
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`PaddingPolicy`]: crate::ext::PaddingPolicy
+    pub fn padding_policy(&mut self, policy: PaddingPolicy) -> &mut Self {
+        self.padding_policy = policy;
+        self
+    }
+
+    /// Sets an observer called with every frame read from and written to the
+    /// connection.
+    ///
+    /// See [`FrameObserver`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::client::*;
+    /// # use http2::ext::{FrameObserver, ObservedFrame};
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), http2::Error>
+    /// # {
+    /// struct Trace;
+    ///
+    /// impl FrameObserver for Trace {
+    ///     fn frame_sent(&self, frame: &ObservedFrame<'_>) {
+    ///         println!("sent {:?}", frame);
+    ///     }
+    /// }
+    ///
+    /// let client_fut = Builder::new()
+    ///     .frame_observer(Trace)
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`FrameObserver`]: crate::ext::FrameObserver
+    pub fn frame_observer<O: FrameObserver>(&mut self, observer: O) -> &mut Self {
+        self.frame_observer = Some(SharedFrameObserver::new(observer));
+        self
+    }
+
+    /// Sets how the DATA frames of concurrent requests share the connection.
+    ///
+    /// By default, each stream sends one frame as large as the server allows
+    /// before the next stream gets a turn. See [`SchedulingStrategy`] for the
+    /// other strategies.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the strategy has a quantum of zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::client::*;
+    /// # use http2::ext::SchedulingStrategy;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), http2::Error>
+    /// # {
+    /// // Let request bodies take turns of at most 4KB.
+    /// let client_fut = Builder::new()
+    ///     .scheduling_strategy(SchedulingStrategy::RoundRobin { quantum: 4096 })
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`SchedulingStrategy`]: crate::ext::SchedulingStrategy
+    pub fn scheduling_strategy(&mut self, strategy: SchedulingStrategy) -> &mut Self {
+        strategy.assert_valid();
+        self.scheduling_strategy = strategy;
+        self
+    }
+
     /// Sets the stream dependency and weight for the outgoing HEADERS frame.
     ///
     /// This configures the priority of the stream by specifying its dependency and weight,
     /// as defined by the HTTP/2 priority mechanism. This can be used to influence how the
     /// server allocates resources to this stream relative to others.
+    ///
+    /// The stream dependency can be overridden for a single request by
+    /// inserting a [`StreamDependency`] into the request's extensions.
+    ///
+    /// [`StreamDependency`]: crate::ext::StreamDependency
     pub fn headers_stream_dependency(&mut self, stream_dependency: StreamDependency) -> &mut Self {
         self.headers_stream_dependency = Some(stream_dependency);
         self
@@ -1243,11 +1799,191 @@ impl Builder {
     ///
     /// Each `Priority` in the list must have a valid (non-zero) stream ID. Any priority with a
     /// stream ID of zero will be ignored.
+    ///
+    /// The frames can be replaced for a single request by inserting
+    /// [`Priorities`] into the request's extensions.
+    ///
+    /// [`Priorities`]: crate::ext::Priorities
     pub fn priorities(&mut self, priorities: Priorities) -> &mut Self {
         self.priorities = Some(priorities);
         self
     }
 
+    /// Sets the frames sent between the connection preface magic and the
+    /// first request.
+    ///
+    /// The SETTINGS frame is always sent first, and the frames of the
+    /// [`PrefaceScript`] follow it in order. The connection level
+    /// WINDOW_UPDATE frame implied by [`initial_connection_window_size`] is
+    /// not sent when a script is set: the connection window is instead grown
+    /// by the WINDOW_UPDATE frames of the script, if any.
+    ///
+    /// The PRIORITY frames set with [`priorities`] are not sent when a script
+    /// is set. If the script contains PRIORITY frames, the first request is
+    /// sent on the lowest stream id above all of them, and on stream 1
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::client::*;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), http2::Error>
+    /// # {
+    /// // Send the WINDOW_UPDATE frame and a PING in a second write, after the
+    /// // SETTINGS frame.
+    /// let script = PrefaceScript::builder()
+    ///     .flush()
+    ///     .window_update(15_663_105)
+    ///     .ping([1, 2, 3, 4, 5, 6, 7, 8])
+    ///     .build();
+    ///
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new().preface(script).handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`initial_connection_window_size`]: Builder::initial_connection_window_size
+    /// [`priorities`]: Builder::priorities
+    pub fn preface(&mut self, script: PrefaceScript) -> &mut Self {
+        self.stream_id = script.first_stream_id().unwrap_or_else(|| 1.into());
+        self.preface = Some(script);
+        self
+    }
+
+    /// Applies a browser [`Profile`] to the connection.
+    ///
+    /// This configures the initial SETTINGS frame and its order, the initial
+    /// connection window and the shape of outgoing HEADERS frames in one go. Options that are not
+    /// covered by the profile keep their current value, and any option can
+    /// still be adjusted after the profile has been applied. A profile that
+    /// disables RFC 7540 priorities also clears the stream dependency of
+    /// HEADERS frames.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::client::*;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), http2::Error>
+    /// # {
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new()
+    ///     .profile(Profile::firefox())
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn profile(&mut self, profile: Profile) -> &mut Self {
+        if let Some(size) = profile.header_table_size {
+            self.header_table_size(size);
+        }
+        if let Some(enabled) = profile.enable_push {
+            self.enable_push(enabled);
+        }
+        if let Some(max) = profile.max_concurrent_streams {
+            self.max_concurrent_streams(max);
+        }
+        if let Some(size) = profile.initial_window_size {
+            self.initial_window_size(size);
+        }
+        if let Some(max) = profile.max_frame_size {
+            self.max_frame_size(max);
+        }
+        if let Some(max) = profile.max_header_list_size {
+            self.max_header_list_size(max);
+        }
+        if let Some(enabled) = profile.no_rfc7540_priorities {
+            self.no_rfc7540_priorities(enabled);
+        }
+        if let Some(size) = profile.initial_connection_window_size {
+            self.initial_connection_window_size(size);
+        }
+        self.settings_order(profile.settings_order);
+        self.headers_pseudo_order(profile.headers_pseudo_order);
+        self.headers_order(profile.headers_order);
+        if let Some(dependency) = profile.headers_stream_dependency {
+            self.headers_stream_dependency(dependency);
+        } else if profile.no_rfc7540_priorities == Some(true) {
+            // HEADERS frames carry no dependency once the peer is told RFC
+            // 7540 priorities are not used.
+            self.headers_stream_dependency = None;
+        }
+        self
+    }
+
+    /// Configures the connection to match a [`Fingerprint`].
+    ///
+    /// This replaces the initial SETTINGS frame with the parameters of the
+    /// fingerprint, in the same order, and sets the initial connection window,
+    /// the PRIORITY frames sent before the first request and the order of
+    /// pseudo-header fields in outgoing HEADERS frames. Parts the fingerprint
+    /// leaves empty fall back to their defaults. If the fingerprint contains
+    /// PRIORITY frames, the first request is sent on the lowest stream id above
+    /// all of them, and on stream 1 otherwise.
+    ///
+    /// Parameters with an unknown id are sent as experimental settings, which
+    /// requires the `unstable` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the builder unchanged, if the fingerprint
+    /// cannot be reproduced: for example if a parameter appears twice, a value
+    /// is out of range for its parameter, or a PRIORITY frame targets stream 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::client::*;
+    /// # use http2::Fingerprint;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), Box<dyn std::error::Error>>
+    /// # {
+    /// let fingerprint: Fingerprint = "1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p".parse()?;
+    ///
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new()
+    ///     .apply_fingerprint(&fingerprint)?
+    ///     .handshake(my_io);
+    /// # Ok(client_fut.await?)
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn apply_fingerprint(
+        &mut self,
+        fingerprint: &Fingerprint,
+    ) -> Result<&mut Self, InvalidFingerprint> {
+        let settings = fingerprint.settings_frame()?;
+        let window_size = fingerprint.connection_window_size()?;
+        let priorities = fingerprint.priority_frames()?;
+        let pseudo_order = fingerprint.pseudo_header_order()?;
+
+        self.settings = settings;
+        self.initial_target_connection_window_size = window_size;
+        self.priorities = priorities;
+        self.headers_pseudo_order = pseudo_order;
+        self.stream_id = fingerprint.first_stream_id().unwrap_or_else(|| 1.into());
+        Ok(self)
+    }
+
     /// Creates a new configured HTTP/2 client backed by `io`.
     ///
     /// It is expected that `io` already be in an appropriate state to commence
@@ -1327,6 +2063,280 @@ impl Default for Builder {
     }
 }
 
+// ===== impl Profile =====
+
+impl Profile {
+    /// Returns the profile of current Chrome releases (and other Chromium
+    /// based browsers).
+    ///
+    /// Akamai fingerprint: `1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p`.
+    pub fn chrome() -> Profile {
+        Profile {
+            header_table_size: Some(65_536),
+            enable_push: Some(false),
+            max_concurrent_streams: None,
+            initial_window_size: Some(6_291_456),
+            max_frame_size: None,
+            max_header_list_size: Some(262_144),
+            no_rfc7540_priorities: None,
+            settings_order: SettingsOrder::builder()
+                .extend([
+                    SettingId::HeaderTableSize,
+                    SettingId::EnablePush,
+                    SettingId::InitialWindowSize,
+                    SettingId::MaxHeaderListSize,
+                ])
+                .build(),
+            initial_connection_window_size: Some(15_728_640),
+            headers_pseudo_order: PseudoOrder::builder()
+                .extend([
+                    PseudoId::Method,
+                    PseudoId::Authority,
+                    PseudoId::Scheme,
+                    PseudoId::Path,
+                ])
+                .build(),
+            // Exclusive dependency on the root with a weight of 256.
+            headers_stream_dependency: Some(StreamDependency::new(StreamId::zero(), 255, true)),
+            headers_order: header_order(&[
+                "cache-control",
+                "sec-ch-ua",
+                "sec-ch-ua-mobile",
+                "sec-ch-ua-platform",
+                "upgrade-insecure-requests",
+                "user-agent",
+                "content-type",
+                "accept",
+                "origin",
+                "sec-fetch-site",
+                "sec-fetch-mode",
+                "sec-fetch-user",
+                "sec-fetch-dest",
+                "referer",
+                "accept-encoding",
+                "accept-language",
+                "cookie",
+                "priority",
+            ]),
+        }
+    }
+
+    /// Returns the profile of current Firefox releases.
+    ///
+    /// Akamai fingerprint: `1:65536;2:0;4:131072;5:16384|12517377|0|m,p,a,s`.
+    pub fn firefox() -> Profile {
+        Profile {
+            header_table_size: Some(65_536),
+            enable_push: Some(false),
+            max_concurrent_streams: None,
+            initial_window_size: Some(131_072),
+            max_frame_size: Some(16_384),
+            max_header_list_size: None,
+            no_rfc7540_priorities: None,
+            settings_order: SettingsOrder::builder()
+                .extend([
+                    SettingId::HeaderTableSize,
+                    SettingId::EnablePush,
+                    SettingId::InitialWindowSize,
+                    SettingId::MaxFrameSize,
+                ])
+                .build(),
+            initial_connection_window_size: Some(12_582_912),
+            headers_pseudo_order: PseudoOrder::builder()
+                .extend([
+                    PseudoId::Method,
+                    PseudoId::Path,
+                    PseudoId::Authority,
+                    PseudoId::Scheme,
+                ])
+                .build(),
+            // Non-exclusive dependency on the root with a weight of 42.
+            headers_stream_dependency: Some(StreamDependency::new(StreamId::zero(), 41, false)),
+            headers_order: header_order(&[
+                "user-agent",
+                "accept",
+                "accept-language",
+                "accept-encoding",
+                "content-type",
+                "content-length",
+                "origin",
+                "referer",
+                "cookie",
+                "upgrade-insecure-requests",
+                "sec-fetch-dest",
+                "sec-fetch-mode",
+                "sec-fetch-site",
+                "sec-fetch-user",
+                "priority",
+                "te",
+            ]),
+        }
+    }
+
+    /// Returns the profile of current Safari releases.
+    ///
+    /// Safari disables RFC 7540 stream priorities, so its HEADERS frames carry
+    /// no stream dependency.
+    ///
+    /// Akamai fingerprint: `2:0;3:100;4:2097152;9:1|10420225|0|m,s,a,p`.
+    pub fn safari() -> Profile {
+        Profile {
+            header_table_size: None,
+            enable_push: Some(false),
+            max_concurrent_streams: Some(100),
+            initial_window_size: Some(2_097_152),
+            max_frame_size: None,
+            max_header_list_size: None,
+            no_rfc7540_priorities: Some(true),
+            settings_order: SettingsOrder::builder()
+                .extend([
+                    SettingId::EnablePush,
+                    SettingId::MaxConcurrentStreams,
+                    SettingId::InitialWindowSize,
+                    SettingId::NoRfc7540Priorities,
+                ])
+                .build(),
+            initial_connection_window_size: Some(10_485_760),
+            headers_pseudo_order: PseudoOrder::builder()
+                .extend([
+                    PseudoId::Method,
+                    PseudoId::Scheme,
+                    PseudoId::Authority,
+                    PseudoId::Path,
+                ])
+                .build(),
+            headers_stream_dependency: None,
+            headers_order: header_order(&[
+                "content-type",
+                "accept",
+                "sec-fetch-site",
+                "origin",
+                "cookie",
+                "sec-fetch-dest",
+                "accept-language",
+                "sec-fetch-mode",
+                "user-agent",
+                "referer",
+                "content-length",
+                "priority",
+                "accept-encoding",
+            ]),
+        }
+    }
+}
+
+fn header_order(names: &[&'static str]) -> HeaderOrder {
+    names
+        .iter()
+        .map(|name| http::HeaderName::from_static(name))
+        .collect()
+}
+
+// ===== impl PrefaceScript =====
+
+impl PrefaceScript {
+    /// Creates a new `PrefaceScriptBuilder`.
+    pub fn builder() -> PrefaceScriptBuilder {
+        PrefaceScriptBuilder {
+            frames: Vec::new(),
+            window_size: DEFAULT_INITIAL_WINDOW_SIZE,
+        }
+    }
+
+    /// Returns the sum of the connection window increments of the script.
+    fn window_increment(&self) -> WindowSize {
+        self.frames
+            .iter()
+            .map(|frame| match frame {
+                PrefaceFrame::WindowUpdate(frame) => frame.size_increment(),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Returns the lowest client stream id above every PRIORITY frame's
+    /// stream, which is the first id a request can use.
+    fn first_stream_id(&self) -> Option<StreamId> {
+        let max = self
+            .frames
+            .iter()
+            .filter_map(|frame| match frame {
+                PrefaceFrame::Priority(frame) => Some(u32::from(frame.stream_id())),
+                _ => None,
+            })
+            .max()?;
+        Some(StreamId::from((max + 1) | 1))
+    }
+}
+
+// ===== impl PrefaceScriptBuilder =====
+
+impl PrefaceScriptBuilder {
+    /// Appends a connection level WINDOW_UPDATE frame.
+    ///
+    /// The connection window advertised to the server grows by `increment`.
+    /// The frame is ignored if `increment` is zero or would grow the window
+    /// beyond the maximum window size.
+    pub fn window_update(mut self, increment: u32) -> Self {
+        let window_size = match self.window_size.checked_add(increment) {
+            Some(size) if increment > 0 && size <= proto::MAX_WINDOW_SIZE => size,
+            _ => {
+                tracing::warn!("ignoring WINDOW_UPDATE frame with increment {}", increment);
+                return self;
+            }
+        };
+
+        self.window_size = window_size;
+        self.frames
+            .push(PrefaceFrame::WindowUpdate(WindowUpdate::new(
+                StreamId::zero(),
+                increment,
+            )));
+        self
+    }
+
+    /// Appends a PRIORITY frame.
+    ///
+    /// The frame is ignored if its stream ID is zero. Requests are sent on
+    /// stream IDs above those of every PRIORITY frame of the script.
+    pub fn priority(mut self, priority: Priority) -> Self {
+        if priority.stream_id().is_zero() {
+            tracing::warn!("ignoring priority frame with stream ID 0");
+            return self;
+        }
+
+        self.frames.push(PrefaceFrame::Priority(priority));
+        self
+    }
+
+    /// Appends a PRIORITY frame for each of `priorities`, in order.
+    pub fn priorities(self, priorities: Priorities) -> Self {
+        priorities.into_iter().fold(self, Self::priority)
+    }
+
+    /// Appends a PING frame carrying `payload`.
+    ///
+    /// The acknowledgement sent back by the server is ignored.
+    pub fn ping(mut self, payload: [u8; 8]) -> Self {
+        self.frames.push(PrefaceFrame::Ping(payload));
+        self
+    }
+
+    /// Writes every frame that precedes this point to the connection before
+    /// the following ones are buffered.
+    pub fn flush(mut self) -> Self {
+        self.frames.push(PrefaceFrame::Flush);
+        self
+    }
+
+    /// Builds the `PrefaceScript`.
+    pub fn build(self) -> PrefaceScript {
+        PrefaceScript {
+            frames: self.frames,
+        }
+    }
+}
+
 /// Creates a new configured HTTP/2 client with default configuration
 /// values backed by `io`.
 ///
@@ -1416,11 +2426,48 @@ where
             codec.set_max_recv_header_list_size(max as usize);
         }
 
+        if let Some(policy) = builder.indexing_policy.clone() {
+            codec.set_send_indexing_policy(policy);
+        }
+
+        if let Some(policy) = builder.huffman_policy {
+            codec.set_send_huffman_policy(policy);
+        }
+
+        if builder.padding_policy != PaddingPolicy::None {
+            codec.set_send_padding_policy(builder.padding_policy);
+        }
+
+        if let Some(observer) = builder.frame_observer.clone() {
+            codec.set_frame_observer(observer);
+        }
+
         // Send initial settings frame
         codec
             .buffer((builder.settings.clone()).into())
             .expect("invalid SETTINGS frame");
 
+        if let Some(ref script) = builder.preface {
+            for frame in &script.frames {
+                let frame = match *frame {
+                    PrefaceFrame::WindowUpdate(frame) => frame.into(),
+                    PrefaceFrame::Priority(ref frame) => frame.clone().into(),
+                    PrefaceFrame::Ping(payload) => frame::Ping::new(payload).into(),
+                    PrefaceFrame::Flush => {
+                        crate::poll_fn(|cx| codec.flush(cx))
+                            .await
+                            .map_err(crate::Error::from_io)?;
+                        continue;
+                    }
+                };
+
+                crate::poll_fn(|cx| codec.poll_ready(cx))
+                    .await
+                    .map_err(crate::Error::from_io)?;
+                codec.buffer(frame).expect("invalid preface frame");
+            }
+        }
+
         let inner = proto::Connection::new(
             codec,
             proto::Config {
@@ -1433,8 +2480,23 @@ where
                 local_error_reset_streams_max: builder.local_max_error_reset_streams,
                 settings: builder.settings.clone(),
                 headers_pseudo_order: builder.headers_pseudo_order,
+                headers_order: builder.headers_order,
                 headers_stream_dependency: builder.headers_stream_dependency,
-                priorities: builder.priorities,
+                // The PRIORITY frames of a script replace the connection ones.
+                priorities: if builder.preface.is_some() {
+                    None
+                } else {
+                    builder.priorities
+                },
+                padding: builder.padding_policy,
+                rfc7540_priorities: false,
+                scheduling_strategy: builder.scheduling_strategy,
+                adaptive_window: builder
+                    .adaptive_window
+                    .then_some(builder.max_adaptive_window_size),
+                keep_alive_interval: builder.keep_alive_interval,
+                keep_alive_timeout: builder.keep_alive_timeout,
+                keep_alive_while_idle: builder.keep_alive_while_idle,
             },
         );
         let send_request = SendRequest {
@@ -1443,7 +2505,12 @@ where
         };
 
         let mut connection = Connection { inner };
-        if let Some(sz) = builder.initial_target_connection_window_size {
+        if let Some(ref script) = builder.preface {
+            // The script's WINDOW_UPDATE frames were already sent.
+            connection
+                .inner
+                .inc_connection_window(script.window_increment());
+        } else if let Some(sz) = builder.initial_target_connection_window_size {
             connection.set_target_window_size(sz);
         }
 
@@ -1500,6 +2567,75 @@ where
         self.inner.take_user_pings().map(PingPong::new)
     }
 
+    /// Returns the smoothed round trip time of the connection, or `None` if
+    /// no PING was acknowledged yet.
+    ///
+    /// The round trip time is measured from the PINGs sent with a
+    /// [`PingPong`], for keep-alive, and to size adaptive windows. It is
+    /// smoothed as TCP does, see [RFC 6298][1].
+    ///
+    /// [1]: https://datatracker.ietf.org/doc/html/rfc6298#section-2
+    pub fn smoothed_rtt(&self) -> Option<Duration> {
+        self.inner.smoothed_rtt()
+    }
+
+    /// Returns the smallest round trip time measured on the connection, or
+    /// `None` if no PING was acknowledged yet.
+    pub fn min_rtt(&self) -> Option<Duration> {
+        self.inner.min_rtt()
+    }
+
+    /// Returns a snapshot of the connection statistics: the frames and bytes
+    /// sent and received, the streams opened, closed and reset, the
+    /// connection level flow control windows, and the HPACK dynamic tables.
+    ///
+    /// The snapshot is not updated afterwards, call `stats` again to see the
+    /// connection progress.
+    pub fn stats(&self) -> ConnectionStats {
+        self.inner.stats()
+    }
+
+    /// Returns the alternative services the server advertised for other
+    /// origins in [ALTSVC frames][1] sent on stream zero.
+    ///
+    /// Only the last frame received for each origin is kept, as it replaces
+    /// the alternative services previously advertised for that origin.
+    /// Frames sent on a request's stream are available through
+    /// [`ResponseFuture::alt_svc`] and the extensions of the response.
+    ///
+    /// [1]: https://datatracker.ietf.org/doc/html/rfc7838#section-4
+    pub fn alt_svc(&self) -> Vec<AltSvc> {
+        self.inner.alt_svc()
+    }
+
+    /// Takes an `ExtensionFrames` instance from the connection, to receive
+    /// the extension frames sent by the peer.
+    ///
+    /// Extension frames received before this is called are dropped.
+    ///
+    /// # Note
+    ///
+    /// This may only be called once. Calling multiple times will return `None`.
+    pub fn extension_frames(&mut self) -> Option<ExtensionFrames> {
+        self.inner.take_user_extensions().map(ExtensionFrames::new)
+    }
+
+    /// Sends an extension frame to the peer.
+    ///
+    /// The frame is written the next time the connection is polled, ahead of
+    /// any queued stream frame. Use [`SendStream::send_extension_frame`] for
+    /// frames that must follow the data already sent on a stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the type of `frame` is known to the library or if
+    /// its payload is larger than 16,384 bytes.
+    pub fn send_extension_frame(&mut self, frame: ExtensionFrame) -> Result<(), crate::Error> {
+        frame.validate()?;
+        self.inner.send_extension_frame(frame);
+        Ok(())
+    }
+
     /// Returns the maximum number of concurrent streams that may be initiated
     /// by this client.
     ///
@@ -1585,6 +2721,29 @@ impl ResponseFuture {
     pub fn stream_id(&self) -> crate::StreamId {
         crate::StreamId::from_internal(self.inner.stream_id())
     }
+
+    /// Returns the last [ALTSVC frame][1] the server sent on the stream of
+    /// this request, if any.
+    ///
+    /// An ALTSVC frame received before the response headers is also added to
+    /// the extensions of the `Response`.
+    ///
+    /// [1]: https://datatracker.ietf.org/doc/html/rfc7838#section-4
+    pub fn alt_svc(&self) -> Option<AltSvc> {
+        self.inner.alt_svc()
+    }
+
+    /// Returns a snapshot of the statistics of the request stream, such as
+    /// when the request HEADERS were flushed and when the response HEADERS
+    /// arrived.
+    ///
+    /// # Panics
+    ///
+    /// If the lock on the stream store has been poisoned.
+    pub fn stats(&self) -> StreamStats {
+        self.inner.stats()
+    }
+
     /// Returns a stream of PushPromises
     ///
     /// # Panics
@@ -1691,6 +2850,7 @@ impl Peer {
         protocol: Option<Protocol>,
         end_of_stream: bool,
         pseudo_order: Option<PseudoOrder>,
+        headers_order: Option<&HeaderOrder>,
         headers_stream_dependency: Option<StreamDependency>,
     ) -> Result<Headers, SendError> {
         use http::request::Parts;
@@ -1747,6 +2907,10 @@ impl Peer {
 
         // Create the HEADERS frame
         let mut headers_frame = Headers::new(id, pseudo, headers);
+        if let Some(order) = headers_order {
+            headers_frame.set_header_order(order);
+        }
+
         if let Some(stream_dep) = headers_stream_dependency {
             headers_frame.set_stream_dependency(stream_dep);
         }
@@ -1798,6 +2962,7 @@ impl proto::Peer for Peer {
         };
 
         *response.headers_mut() = fields;
+        response.extensions_mut().insert(pseudo.order);
 
         Ok(response)
     }
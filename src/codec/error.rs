@@ -49,6 +49,12 @@ pub enum UserError {
 
     /// Tries to send push promise to peer who has disabled server push
     PeerDisabledServerPush,
+
+    /// Tries to advertise something that is not an origin in an ORIGIN frame.
+    InvalidOrigin,
+
+    /// Tries to make a stream depend on itself.
+    InvalidStreamDependency,
 }
 
 // ===== impl SendError =====
@@ -97,6 +103,8 @@ impl fmt::Display for UserError {
             SendPingWhilePending => "send_ping before received previous pong",
             SendSettingsWhilePending => "sending SETTINGS before received previous ACK",
             PeerDisabledServerPush => "sending PUSH_PROMISE to peer who disabled server push",
+            InvalidOrigin => "invalid origin",
+            InvalidStreamDependency => "invalid stream dependency",
         })
     }
 }
@@ -2,7 +2,9 @@ use crate::frame::{self, Frame, Kind, Reason};
 use crate::frame::{
     DEFAULT_MAX_FRAME_SIZE, DEFAULT_SETTINGS_HEADER_TABLE_SIZE, MAX_MAX_FRAME_SIZE,
 };
+use crate::observer::{ObservedFrame, SharedFrameObserver};
 use crate::proto::Error;
+use crate::stats::{FrameStats, HeaderTableStats};
 
 use crate::hpack;
 use crate::tracing;
@@ -15,6 +17,7 @@ use std::io;
 
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Instant;
 use tokio::io::AsyncRead;
 use tokio_util::codec::FramedRead as InnerFramedRead;
 use tokio_util::codec::{LengthDelimitedCodec, LengthDelimitedCodecError};
@@ -34,6 +37,12 @@ pub struct FramedRead<T> {
     max_continuation_frames: usize,
 
     partial: Option<Partial>,
+
+    /// Frames read so far
+    stats: FrameStats,
+
+    /// Called with every frame read
+    observer: Option<SharedFrameObserver>,
 }
 
 /// Partially loaded headers frame
@@ -65,6 +74,8 @@ impl<T> FramedRead<T> {
             max_header_list_size,
             max_continuation_frames,
             partial: None,
+            stats: FrameStats::default(),
+            observer: None,
         }
     }
 
@@ -106,6 +117,21 @@ impl<T> FramedRead<T> {
     pub fn set_header_table_size(&mut self, val: usize) {
         self.hpack.queue_size_update(val);
     }
+
+    /// Set the observer called with every frame read.
+    pub(crate) fn set_observer(&mut self, observer: SharedFrameObserver) {
+        self.observer = Some(observer);
+    }
+
+    /// Returns the frames read so far
+    pub(crate) fn stats(&self) -> &FrameStats {
+        &self.stats
+    }
+
+    /// Returns the state of the HPACK decoder's dynamic table
+    pub(crate) fn header_table(&self) -> HeaderTableStats {
+        self.hpack.table_stats()
+    }
 }
 
 fn calc_max_continuation_frames(header_max: usize, frame_max: usize) -> usize {
@@ -360,9 +386,46 @@ fn decode_frame(
                 return Ok(None);
             }
         }
+        Kind::Origin => {
+            // ORIGIN is a non-critical extension, frames that cannot be
+            // processed are ignored (RFC 8336 section 2.1).
+            match frame::Origin::load(head, &bytes[frame::HEADER_LEN..]) {
+                Ok(frame) => frame.into(),
+                Err(_e) => {
+                    tracing::debug!("ignoring ORIGIN frame; err={:?}", _e);
+                    return Ok(None);
+                }
+            }
+        }
+        Kind::AltSvc => {
+            // ALTSVC is a non-critical extension as well.
+            bytes.advance(frame::HEADER_LEN);
+            match frame::AltSvc::load(head, bytes.freeze()) {
+                Ok(frame) => frame.into(),
+                Err(_e) => {
+                    tracing::debug!("ignoring ALTSVC frame; err={:?}", _e);
+                    return Ok(None);
+                }
+            }
+        }
+        Kind::PriorityUpdate => {
+            let res = frame::PriorityUpdate::load(head, &bytes[frame::HEADER_LEN..]);
+
+            res.map_err(|e| {
+                proto_err!(conn: "failed to load PRIORITY_UPDATE frame; err={:?}", e);
+                match e {
+                    frame::Error::BadFrameSize => Error::library_go_away(Reason::FRAME_SIZE_ERROR),
+                    _ => Error::library_go_away(Reason::PROTOCOL_ERROR),
+                }
+            })?
+            .into()
+        }
         Kind::Unknown => {
-            // Unknown frames are ignored
-            return Ok(None);
+            // Extension frames are passed on as is, the connection decides
+            // whether anyone is interested in them.
+            let kind = bytes[3];
+            bytes.advance(frame::HEADER_LEN);
+            frame::ExtensionFrame::load(head, kind, bytes.freeze()).into()
         }
     };
 
@@ -386,6 +449,14 @@ where
             };
 
             tracing::trace!(read.bytes = bytes.len());
+            self.stats.record(Kind::new(bytes[3]), bytes.len());
+
+            // The frame is observed once it is parsed, decoding consumes it.
+            let observed = self
+                .observer
+                .as_ref()
+                .map(|_| (bytes.clone().freeze(), Instant::now()));
+
             let Self {
                 ref mut hpack,
                 max_header_list_size,
@@ -393,13 +464,19 @@ where
                 max_continuation_frames,
                 ..
             } = *self;
-            if let Some(frame) = decode_frame(
+            let frame = decode_frame(
                 hpack,
                 max_header_list_size,
                 max_continuation_frames,
                 partial,
                 bytes,
-            )? {
+            )?;
+
+            if let (Some(observer), Some((bytes, at))) = (&self.observer, observed) {
+                observer.frame_received(&ObservedFrame::new(&bytes, true, at));
+            }
+
+            if let Some(frame) = frame {
                 tracing::debug!(?frame, "received");
                 return Poll::Ready(Some(Ok(frame)));
             }
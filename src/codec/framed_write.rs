@@ -1,11 +1,14 @@
 use crate::codec::UserError;
 use crate::codec::UserError::*;
 use crate::frame::{self, Frame, FrameSize};
+use crate::observer::{ObservedFrame, SharedFrameObserver};
+use crate::stats::{FrameStats, HeaderTableStats};
 use crate::{hpack, tracing};
 
 use bytes::{Buf, BufMut, BytesMut};
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Instant;
 use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use tokio_util::io::poll_write_buf;
 
@@ -33,6 +36,9 @@ struct Encoder<B> {
     /// HPACK encoder
     hpack: hpack::Encoder,
 
+    /// Pads HEADERS and PUSH_PROMISE frames
+    padder: frame::Padder,
+
     /// Write buffer
     ///
     /// TODO: Should this be a ring buffer?
@@ -52,6 +58,12 @@ struct Encoder<B> {
 
     /// Min buffer required to attempt to write a frame
     min_buffer_capacity: usize,
+
+    /// Frames written so far
+    stats: FrameStats,
+
+    /// Called with every frame buffered
+    observer: Option<SharedFrameObserver>,
 }
 
 #[derive(Debug)]
@@ -93,12 +105,15 @@ where
             final_flush_done: false,
             encoder: Encoder {
                 hpack: hpack::Encoder::default(),
+                padder: frame::Padder::default(),
                 buf: Cursor::new(BytesMut::with_capacity(DEFAULT_BUFFER_CAPACITY)),
                 next: None,
                 last_data_frame: None,
                 max_frame_size: frame::DEFAULT_MAX_FRAME_SIZE,
                 chain_threshold,
                 min_buffer_capacity: chain_threshold + frame::HEADER_LEN,
+                stats: FrameStats::default(),
+                observer: None,
             },
         }
     }
@@ -198,9 +213,19 @@ where
         // The data frame has been written, so unset it
         match self.next.take() {
             Some(Next::Data(frame)) => {
+                // The payload has been written, write the padding that
+                // follows it, if any, before moving on.
+                let padded = frame.pad_len().is_some();
+                frame.encode_padding(self.buf.get_mut());
+
                 self.last_data_frame = Some(frame);
-                debug_assert!(self.is_empty());
-                ControlFlow::Break
+
+                if padded {
+                    ControlFlow::Continue
+                } else {
+                    debug_assert!(self.is_empty());
+                    ControlFlow::Break
+                }
             }
             Some(Next::Continuation(frame)) => {
                 // Buffer the continuation frame, then try to write again
@@ -208,6 +233,9 @@ where
                 if let Some(continuation) = frame.encode(&mut buf) {
                     self.next = Some(Next::Continuation(continuation));
                 }
+                let len = self.buf.get_ref().len();
+                self.stats.record(frame::Kind::Continuation, len);
+                self.observe(0);
                 ControlFlow::Continue
             }
             None => ControlFlow::Break,
@@ -221,20 +249,25 @@ where
 
         tracing::debug!(frame = ?item, "send");
 
+        let start = self.buf.get_ref().len();
+        let is_data = matches!(item, Frame::Data(_));
+
         match item {
             Frame::Data(mut v) => {
                 // Ensure that the payload is not greater than the max frame.
                 let len = v.payload().remaining();
 
-                if len > self.max_frame_size() {
+                if len + v.padding_len() > self.max_frame_size() {
                     return Err(PayloadTooBig);
                 }
 
-                if len >= self.chain_threshold {
-                    let head = v.head();
+                // The payload may be written later, without being buffered.
+                self.stats
+                    .record(frame::Kind::Data, frame::HEADER_LEN + len + v.padding_len());
 
+                if len >= self.chain_threshold {
                     // Encode the frame head to the buffer
-                    head.encode(len, self.buf.get_mut());
+                    v.encode_head(len, self.buf.get_mut());
 
                     if self.buf.get_ref().remaining() < self.chain_threshold {
                         let extra_bytes = self.chain_threshold - self.buf.remaining();
@@ -256,13 +289,13 @@ where
             }
             Frame::Headers(v) => {
                 let mut buf = limited_write_buf!(self);
-                if let Some(continuation) = v.encode(&mut self.hpack, &mut buf) {
+                if let Some(continuation) = v.encode(&mut self.hpack, &mut self.padder, &mut buf) {
                     self.next = Some(Next::Continuation(continuation));
                 }
             }
             Frame::PushPromise(v) => {
                 let mut buf = limited_write_buf!(self);
-                if let Some(continuation) = v.encode(&mut self.hpack, &mut buf) {
+                if let Some(continuation) = v.encode(&mut self.hpack, &mut self.padder, &mut buf) {
                     self.next = Some(Next::Continuation(continuation));
                 }
             }
@@ -291,11 +324,45 @@ where
                 v.encode(self.buf.get_mut());
                 tracing::trace!(rem = self.buf.remaining(), "encoded reset");
             }
+            Frame::Origin(v) => {
+                v.encode(self.buf.get_mut());
+                tracing::trace!(rem = self.buf.remaining(), "encoded origin");
+            }
+            Frame::AltSvc(v) => {
+                v.encode(self.buf.get_mut());
+                tracing::trace!(rem = self.buf.remaining(), "encoded altsvc");
+            }
+            Frame::PriorityUpdate(v) => {
+                v.encode(self.buf.get_mut());
+                tracing::trace!(rem = self.buf.remaining(), "encoded priority_update");
+            }
+            Frame::Extension(v) => {
+                v.encode(self.buf.get_mut());
+                tracing::trace!(rem = self.buf.remaining(), "encoded extension");
+            }
         }
 
+        if !is_data {
+            let buf = self.buf.get_ref();
+            self.stats
+                .record(frame::Kind::new(buf[start + 3]), buf.len() - start);
+        }
+        self.observe(start);
+
         Ok(())
     }
 
+    /// Passes the frame encoded at `start` in the buffer to the observer.
+    fn observe(&self, start: usize) {
+        if let Some(observer) = &self.observer {
+            let buf = &self.buf.get_ref()[start..];
+            // The payload of a DATA frame may be written without being
+            // buffered.
+            let with_payload = frame::Kind::new(buf[3]) != frame::Kind::Data;
+            observer.frame_sent(&ObservedFrame::new(buf, with_payload, Instant::now()));
+        }
+    }
+
     fn has_capacity(&self) -> bool {
         self.next.is_none()
             && (self.buf.get_ref().capacity() - self.buf.get_ref().len()
@@ -333,11 +400,41 @@ impl<T, B> FramedWrite<T, B> {
         self.encoder.hpack.update_max_size(val);
     }
 
+    /// Set the policy deciding how header fields are indexed.
+    pub fn set_indexing_policy(&mut self, policy: hpack::SharedIndexingPolicy) {
+        self.encoder.hpack.set_indexing_policy(policy);
+    }
+
+    /// Set when header string literals are Huffman encoded.
+    pub fn set_huffman_policy(&mut self, huffman: hpack::HuffmanPolicy) {
+        self.encoder.hpack.set_huffman_policy(huffman);
+    }
+
+    /// Set how HEADERS and PUSH_PROMISE frames are padded.
+    pub fn set_padding_policy(&mut self, policy: frame::PaddingPolicy) {
+        self.encoder.padder = frame::Padder::new(policy);
+    }
+
+    /// Set the observer called with every frame buffered.
+    pub(crate) fn set_observer(&mut self, observer: SharedFrameObserver) {
+        self.encoder.observer = Some(observer);
+    }
+
     /// Retrieve the last data frame that has been sent
     pub fn take_last_data_frame(&mut self) -> Option<frame::Data<B>> {
         self.encoder.last_data_frame.take()
     }
 
+    /// Returns the frames buffered to be written so far
+    pub(crate) fn stats(&self) -> &FrameStats {
+        &self.encoder.stats
+    }
+
+    /// Returns the state of the HPACK encoder's dynamic table
+    pub(crate) fn header_table(&self) -> HeaderTableStats {
+        self.encoder.hpack.table_stats()
+    }
+
     pub fn get_mut(&mut self) -> &mut T {
         &mut self.inner
     }
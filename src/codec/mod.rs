@@ -8,7 +8,10 @@ use self::framed_read::FramedRead;
 use self::framed_write::FramedWrite;
 
 use crate::frame::{self, Data, Frame};
+use crate::hpack;
+use crate::observer::SharedFrameObserver;
 use crate::proto::Error;
+use crate::stats::ConnectionStats;
 
 use bytes::Buf;
 use futures_core::Stream;
@@ -95,6 +98,27 @@ impl<T, B> Codec<T, B> {
         self.framed_write().set_header_table_size(val)
     }
 
+    /// Set the policy deciding how sent header fields are indexed.
+    pub(crate) fn set_send_indexing_policy(&mut self, policy: hpack::SharedIndexingPolicy) {
+        self.framed_write().set_indexing_policy(policy)
+    }
+
+    /// Set when sent header string literals are Huffman encoded.
+    pub(crate) fn set_send_huffman_policy(&mut self, huffman: hpack::HuffmanPolicy) {
+        self.framed_write().set_huffman_policy(huffman)
+    }
+
+    /// Set how sent HEADERS and PUSH_PROMISE frames are padded.
+    pub(crate) fn set_send_padding_policy(&mut self, policy: frame::PaddingPolicy) {
+        self.framed_write().set_padding_policy(policy)
+    }
+
+    /// Set the observer called with every frame read and written.
+    pub(crate) fn set_frame_observer(&mut self, observer: SharedFrameObserver) {
+        self.framed_write().set_observer(observer.clone());
+        self.inner.set_observer(observer);
+    }
+
     /// Set the decoder header table size size.
     pub fn set_recv_header_table_size(&mut self, val: usize) {
         self.inner.set_header_table_size(val)
@@ -121,6 +145,16 @@ impl<T, B> Codec<T, B> {
         self.framed_write().take_last_data_frame()
     }
 
+    /// Copies the frames written and read so far, and the state of the HPACK
+    /// tables, into `stats`.
+    pub(crate) fn fill_stats(&self, stats: &mut ConnectionStats) {
+        let framed_write = self.inner.get_ref();
+        stats.frames_sent = framed_write.stats().clone();
+        stats.encoder_table = framed_write.header_table();
+        stats.frames_received = self.inner.stats().clone();
+        stats.decoder_table = self.inner.header_table();
+    }
+
     fn framed_write(&mut self) -> &mut FramedWrite<T, B> {
         self.inner.get_mut()
     }
@@ -40,6 +40,9 @@ enum Kind {
 
     /// An `io::Error` occurred while trying to read or write.
     Io(io::Error),
+
+    /// The peer did not acknowledge a keep-alive PING in time.
+    KeepAliveTimedOut,
 }
 
 // ===== impl Error =====
@@ -85,6 +88,12 @@ impl Error {
         }
     }
 
+    /// Returns true if the connection was closed because the peer did not
+    /// acknowledge a keep-alive PING in time.
+    pub fn is_keep_alive_timeout(&self) -> bool {
+        matches!(self.kind, Kind::KeepAliveTimedOut)
+    }
+
     /// Returns true if the error is from a `GOAWAY`.
     pub fn is_go_away(&self) -> bool {
         matches!(self.kind, Kind::GoAway(..))
@@ -129,6 +138,7 @@ impl From<proto::Error> for Error {
                 Io(kind, inner) => {
                     Kind::Io(inner.map_or_else(|| kind.into(), |inner| io::Error::new(kind, inner)))
                 }
+                KeepAliveTimedOut => Kind::KeepAliveTimedOut,
             },
         }
     }
@@ -186,6 +196,7 @@ impl fmt::Display for Error {
             Kind::Reason(reason) => return write!(fmt, "protocol error: {}", reason),
             Kind::User(ref e) => return write!(fmt, "user error: {}", e),
             Kind::Io(ref e) => return e.fmt(fmt),
+            Kind::KeepAliveTimedOut => return fmt.write_str("keep-alive timed out"),
         };
 
         if !debug_data.is_empty() {
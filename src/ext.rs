@@ -2,6 +2,20 @@
 
 use crate::hpack::BytesStr;
 
+pub use crate::hpack::{HuffmanPolicy, Indexing, IndexingPolicy};
+
+pub use crate::observer::{FrameObserver, ObservedFrame};
+
+pub use crate::proto::{Initiator, SchedulingStrategy};
+
+pub use crate::stats::{ConnectionStats, FrameStats, HeaderTableStats, StreamCounts, StreamStats};
+
+pub use crate::frame::{
+    AltSvc, ExtensiblePriority, ExtensionFrame, HeaderOrder, HeaderOrderBuilder, Kind as FrameKind,
+    PaddingPolicy, Priorities, PrioritiesBuilder, Priority, PseudoId, PseudoOrder,
+    PseudoOrderBuilder, StreamDependency,
+};
+
 use bytes::Bytes;
 use std::fmt;
 
@@ -0,0 +1,543 @@
+#[cfg(feature = "unstable")]
+use crate::frame::{ExperimentalSettings, Setting};
+use crate::frame::{
+    Priorities, Priority, PseudoId, PseudoOrder, SettingId, Settings, SettingsOrder,
+    StreamDependency, StreamId, DEFAULT_INITIAL_WINDOW_SIZE, DEFAULT_MAX_FRAME_SIZE,
+    MAX_MAX_FRAME_SIZE,
+};
+use crate::proto::MAX_WINDOW_SIZE;
+
+use std::str::FromStr;
+use std::{error, fmt};
+
+/// The HTTP/2 fingerprint of a connection.
+///
+/// A fingerprint captures the parts of an HTTP/2 connection that an
+/// implementation chooses freely and that therefore tend to identify it:
+///
+/// * the parameters of the initial SETTINGS frame, in the order they were sent,
+///   including parameters with unknown ids;
+/// * the increment of the first connection-level WINDOW_UPDATE frame;
+/// * the PRIORITY frames sent before the first request;
+/// * the order of the pseudo-header fields in the first HEADERS frame.
+///
+/// A fingerprint is rendered with `Display` and parsed with `FromStr` in the
+/// text format popularized by Akamai, e.g.
+/// `1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p`. A parsed fingerprint
+/// can be used to configure a client with
+/// [`client::Builder::apply_fingerprint`].
+///
+/// [`client::Builder::apply_fingerprint`]: crate::client::Builder::apply_fingerprint
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Fingerprint {
+    settings: Vec<(u16, u32)>,
+    window_update: Option<u32>,
+    priorities: Vec<Priority>,
+    pseudo_order: Vec<PseudoId>,
+}
+
+/// An error returned when a [`Fingerprint`] cannot be parsed or does not
+/// describe a connection this library can reproduce.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidFingerprint {
+    kind: Kind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Kind {
+    /// The string does not have exactly four `|`-separated sections.
+    Sections,
+    /// A SETTINGS parameter is not of the form `id:value`.
+    Setting,
+    /// A SETTINGS parameter appears more than once.
+    DuplicateSetting(u16),
+    /// A SETTINGS parameter has a value the parameter does not allow.
+    SettingValue(u16),
+    /// A SETTINGS parameter with this id cannot be sent.
+    UnsupportedSetting(u16),
+    /// The WINDOW_UPDATE increment is not a valid increment.
+    WindowUpdate,
+    /// A PRIORITY frame is not of the form `stream:exclusive:dependency:weight`.
+    Priority,
+    /// A PRIORITY frame cannot be sent for this stream.
+    PriorityStream(u32),
+    /// A pseudo-header field letter is not one of `m`, `a`, `s` or `p`.
+    PseudoHeader,
+    /// A pseudo-header field appears more than once.
+    DuplicatePseudoHeader,
+}
+
+impl Fingerprint {
+    /// Returns the SETTINGS parameters as `(id, value)` pairs, in the order
+    /// they were sent.
+    pub fn settings(&self) -> &[(u16, u32)] {
+        &self.settings
+    }
+
+    /// Returns the increment of the first connection-level WINDOW_UPDATE
+    /// frame, if one was sent.
+    pub fn window_update(&self) -> Option<u32> {
+        self.window_update
+    }
+
+    /// Returns the PRIORITY frames sent before the first HEADERS frame.
+    pub fn priorities(&self) -> &[Priority] {
+        &self.priorities
+    }
+
+    /// Returns the pseudo-header fields of the first HEADERS frame, in the
+    /// order they were sent.
+    pub fn pseudo_order(&self) -> &[PseudoId] {
+        &self.pseudo_order
+    }
+
+    pub(crate) fn push_setting(&mut self, id: u16, value: u32) {
+        self.settings.push((id, value));
+    }
+
+    pub(crate) fn set_window_update(&mut self, increment: u32) {
+        self.window_update = Some(increment);
+    }
+
+    pub(crate) fn push_priority(&mut self, priority: Priority) {
+        self.priorities.push(priority);
+    }
+
+    pub(crate) fn set_pseudo_order(&mut self, order: impl IntoIterator<Item = PseudoId>) {
+        self.pseudo_order = order.into_iter().collect();
+    }
+
+    /// Returns the initial SETTINGS frame described by the fingerprint.
+    pub(crate) fn settings_frame(&self) -> Result<Settings, InvalidFingerprint> {
+        let mut settings = Settings::default();
+        let mut order = SettingsOrder::builder();
+        #[cfg(feature = "unstable")]
+        let mut experimental = ExperimentalSettings::builder();
+        #[cfg(feature = "unstable")]
+        let mut has_experimental = false;
+
+        for (i, &(id, value)) in self.settings.iter().enumerate() {
+            if self.settings[..i].iter().any(|&(prev, _)| prev == id) {
+                return Err(Kind::DuplicateSetting(id).into());
+            }
+
+            let is_flag = value <= 1;
+            match SettingId::from(id) {
+                SettingId::HeaderTableSize => settings.set_header_table_size(Some(value)),
+                SettingId::EnablePush if is_flag => settings.set_enable_push(value == 1),
+                SettingId::MaxConcurrentStreams => settings.set_max_concurrent_streams(Some(value)),
+                SettingId::InitialWindowSize if value <= MAX_WINDOW_SIZE => {
+                    settings.set_initial_window_size(Some(value))
+                }
+                SettingId::MaxFrameSize
+                    if (DEFAULT_MAX_FRAME_SIZE..=MAX_MAX_FRAME_SIZE).contains(&value) =>
+                {
+                    settings.set_max_frame_size(Some(value))
+                }
+                SettingId::MaxHeaderListSize => settings.set_max_header_list_size(Some(value)),
+                SettingId::EnableConnectProtocol if is_flag => {
+                    settings.set_enable_connect_protocol(Some(value))
+                }
+                SettingId::NoRfc7540Priorities if is_flag => {
+                    settings.set_no_rfc7540_priorities(value == 1)
+                }
+                SettingId::Unknown(_) => {
+                    // Unknown parameters can only be sent as experimental
+                    // settings, which are limited to a few ids.
+                    #[cfg(feature = "unstable")]
+                    if let Some(setting) = Setting::from_id(id, value) {
+                        experimental = experimental.push(setting);
+                        has_experimental = true;
+                        order = order.push(SettingId::Unknown(id));
+                        continue;
+                    }
+                    return Err(Kind::UnsupportedSetting(id).into());
+                }
+                _ => return Err(Kind::SettingValue(id).into()),
+            }
+            order = order.push(SettingId::from(id));
+        }
+
+        #[cfg(feature = "unstable")]
+        if has_experimental {
+            settings.set_experimental_settings(experimental.build());
+        }
+        settings.set_settings_order(order.build());
+        Ok(settings)
+    }
+
+    /// Returns the connection window size that makes the connection send the
+    /// fingerprint's WINDOW_UPDATE increment.
+    pub(crate) fn connection_window_size(&self) -> Result<Option<u32>, InvalidFingerprint> {
+        match self.window_update {
+            None => Ok(None),
+            Some(0) => Err(Kind::WindowUpdate.into()),
+            Some(increment) => increment
+                .checked_add(DEFAULT_INITIAL_WINDOW_SIZE)
+                .filter(|&size| size <= MAX_WINDOW_SIZE)
+                .map(Some)
+                .ok_or_else(|| Kind::WindowUpdate.into()),
+        }
+    }
+
+    /// Returns the PRIORITY frames to send before the first request.
+    pub(crate) fn priority_frames(&self) -> Result<Option<Priorities>, InvalidFingerprint> {
+        if self.priorities.is_empty() {
+            return Ok(None);
+        }
+
+        let mut priorities = Priorities::builder();
+        for (i, priority) in self.priorities.iter().enumerate() {
+            let stream_id = priority.stream_id();
+            // The first request must be able to use a higher stream id.
+            if stream_id.is_zero()
+                || u32::from(stream_id) >= u32::from(StreamId::MAX) - 1
+                || self.priorities[..i]
+                    .iter()
+                    .any(|prev| prev.stream_id() == stream_id)
+            {
+                return Err(Kind::PriorityStream(stream_id.into()).into());
+            }
+            priorities = priorities.push(priority.clone());
+        }
+        Ok(Some(priorities.build()))
+    }
+
+    /// Returns the lowest client stream id above every PRIORITY frame's
+    /// stream, which is the first id a request can use.
+    pub(crate) fn first_stream_id(&self) -> Option<StreamId> {
+        let max = self
+            .priorities
+            .iter()
+            .map(|priority| u32::from(priority.stream_id()))
+            .max()?;
+        Some(StreamId::from((max + 1) | 1))
+    }
+
+    /// Returns the order of pseudo-header fields for outgoing requests.
+    pub(crate) fn pseudo_header_order(&self) -> Result<Option<PseudoOrder>, InvalidFingerprint> {
+        if self.pseudo_order.is_empty() {
+            return Ok(None);
+        }
+
+        for (i, id) in self.pseudo_order.iter().enumerate() {
+            if self.pseudo_order[..i].contains(id) {
+                return Err(Kind::DuplicatePseudoHeader.into());
+            }
+        }
+        Ok(Some(
+            PseudoOrder::builder()
+                .extend(self.pseudo_order.iter().copied())
+                .build(),
+        ))
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = InvalidFingerprint;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut sections = s.split('|');
+        let (Some(settings), Some(window_update), Some(priorities), Some(pseudo_order), None) = (
+            sections.next(),
+            sections.next(),
+            sections.next(),
+            sections.next(),
+            sections.next(),
+        ) else {
+            return Err(Kind::Sections.into());
+        };
+
+        let mut fingerprint = Fingerprint::default();
+
+        for setting in settings.split(';').filter(|s| !s.is_empty()) {
+            let (id, value) = setting.split_once(':').ok_or(Kind::Setting)?;
+            let id = id.parse().map_err(|_| Kind::Setting)?;
+            let value = value.parse().map_err(|_| Kind::Setting)?;
+            fingerprint.push_setting(id, value);
+        }
+
+        if window_update != "00" {
+            let increment = window_update.parse().map_err(|_| Kind::WindowUpdate)?;
+            fingerprint.set_window_update(increment);
+        }
+
+        if priorities != "0" {
+            for priority in priorities.split(',') {
+                fingerprint.push_priority(parse_priority(priority).ok_or(Kind::Priority)?);
+            }
+        }
+
+        for letter in pseudo_order.split(',').filter(|s| !s.is_empty()) {
+            let id = match letter {
+                "m" => PseudoId::Method,
+                "a" => PseudoId::Authority,
+                "s" => PseudoId::Scheme,
+                "p" => PseudoId::Path,
+                _ => return Err(Kind::PseudoHeader.into()),
+            };
+            fingerprint.pseudo_order.push(id);
+        }
+
+        Ok(fingerprint)
+    }
+}
+
+/// Parses a `stream:exclusive:dependency:weight` priority, where `weight` is
+/// in the range 1-256.
+fn parse_priority(s: &str) -> Option<Priority> {
+    let mut parts = s.split(':');
+    let stream_id: u32 = parts.next()?.parse().ok()?;
+    let is_exclusive = match parts.next()? {
+        "0" => false,
+        "1" => true,
+        _ => return None,
+    };
+    let dependency_id: u32 = parts.next()?.parse().ok()?;
+    let weight: u16 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || !(1..=256).contains(&weight) {
+        return None;
+    }
+    if stream_id > StreamId::MAX.into() || dependency_id > StreamId::MAX.into() {
+        return None;
+    }
+
+    Some(Priority::new(
+        StreamId::from(stream_id),
+        StreamDependency::new(
+            StreamId::from(dependency_id),
+            (weight - 1) as u8,
+            is_exclusive,
+        ),
+    ))
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (id, value)) in self.settings.iter().enumerate() {
+            if i > 0 {
+                f.write_str(";")?;
+            }
+            write!(f, "{}:{}", id, value)?;
+        }
+
+        match self.window_update {
+            Some(increment) => write!(f, "|{}|", increment)?,
+            None => f.write_str("|00|")?,
+        }
+
+        if self.priorities.is_empty() {
+            f.write_str("0")?;
+        }
+        for (i, priority) in self.priorities.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            let dependency = priority.dependency();
+            // The format uses the real weight (1-256), not the wire value.
+            write!(
+                f,
+                "{}:{}:{}:{}",
+                u32::from(priority.stream_id()),
+                dependency.is_exclusive() as u8,
+                u32::from(dependency.dependency_id()),
+                u16::from(dependency.weight()) + 1,
+            )?;
+        }
+
+        f.write_str("|")?;
+
+        // Only the request pseudo-header fields have a letter assigned.
+        let mut first = true;
+        for id in &self.pseudo_order {
+            let letter = match id {
+                PseudoId::Method => "m",
+                PseudoId::Authority => "a",
+                PseudoId::Scheme => "s",
+                PseudoId::Path => "p",
+                PseudoId::Protocol | PseudoId::Status => continue,
+            };
+            if !first {
+                f.write_str(",")?;
+            }
+            f.write_str(letter)?;
+            first = false;
+        }
+
+        Ok(())
+    }
+}
+
+// ===== impl InvalidFingerprint =====
+
+impl From<Kind> for InvalidFingerprint {
+    fn from(kind: Kind) -> InvalidFingerprint {
+        InvalidFingerprint { kind }
+    }
+}
+
+impl fmt::Display for InvalidFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid HTTP/2 fingerprint: ")?;
+        match self.kind {
+            Kind::Sections => f.write_str("expected four `|`-separated sections"),
+            Kind::Setting => f.write_str("malformed SETTINGS parameter"),
+            Kind::DuplicateSetting(id) => write!(f, "duplicate SETTINGS parameter {}", id),
+            Kind::SettingValue(id) => write!(f, "invalid value for SETTINGS parameter {}", id),
+            Kind::UnsupportedSetting(id) => write!(f, "unsupported SETTINGS parameter {}", id),
+            Kind::WindowUpdate => f.write_str("invalid WINDOW_UPDATE increment"),
+            Kind::Priority => f.write_str("malformed PRIORITY frame"),
+            Kind::PriorityStream(id) => write!(f, "invalid PRIORITY frame for stream {}", id),
+            Kind::PseudoHeader => f.write_str("unknown pseudo-header field"),
+            Kind::DuplicatePseudoHeader => f.write_str("duplicate pseudo-header field"),
+        }
+    }
+}
+
+impl error::Error for InvalidFingerprint {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::{StreamDependency, StreamId};
+
+    #[test]
+    fn display_akamai() {
+        let mut fingerprint = Fingerprint::default();
+        fingerprint.push_setting(1, 65536);
+        fingerprint.push_setting(2, 0);
+        fingerprint.push_setting(4, 6291456);
+        fingerprint.push_setting(6, 262144);
+        fingerprint.set_window_update(15663105);
+        fingerprint.set_pseudo_order([
+            PseudoId::Method,
+            PseudoId::Authority,
+            PseudoId::Scheme,
+            PseudoId::Path,
+        ]);
+
+        assert_eq!(
+            fingerprint.to_string(),
+            "1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p"
+        );
+    }
+
+    #[test]
+    fn display_akamai_priorities() {
+        let mut fingerprint = Fingerprint::default();
+        fingerprint.push_setting(1, 65536);
+        fingerprint.push_priority(Priority::new(
+            StreamId::from(3),
+            StreamDependency::new(StreamId::zero(), 200, false),
+        ));
+        fingerprint.push_priority(Priority::new(
+            StreamId::from(9),
+            StreamDependency::new(StreamId::from(7), 0, true),
+        ));
+        fingerprint.set_pseudo_order([PseudoId::Method, PseudoId::Path]);
+
+        assert_eq!(fingerprint.to_string(), "1:65536|00|3:0:0:201,9:1:7:1|m,p");
+    }
+
+    #[test]
+    fn parse_akamai_round_trip() {
+        for s in [
+            "1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p",
+            "1:65536;4:131072;5:16384|12517377|3:0:0:201,5:0:0:101,7:0:0:1,9:0:7:1|m,p,a,s",
+            "2:0;3:100;4:2097152;9:1;2570:7|00|0|",
+        ] {
+            let fingerprint: Fingerprint = s.parse().unwrap();
+            assert_eq!(fingerprint.to_string(), s);
+        }
+
+        let fingerprint: Fingerprint = "4:6291456;2:0|10485760|3:1:0:256|m,s".parse().unwrap();
+        assert_eq!(fingerprint.settings(), &[(4, 6291456), (2, 0)]);
+        assert_eq!(fingerprint.window_update(), Some(10485760));
+        assert_eq!(
+            fingerprint.priorities(),
+            &[Priority::new(
+                StreamId::from(3),
+                StreamDependency::new(StreamId::zero(), 255, true),
+            )]
+        );
+        assert_eq!(
+            fingerprint.pseudo_order(),
+            &[PseudoId::Method, PseudoId::Scheme]
+        );
+    }
+
+    #[test]
+    fn parse_akamai_invalid() {
+        for s in [
+            "",
+            "1:65536|00|0",
+            "1:65536|00|0|m|x",
+            "1|00|0|m",
+            "1:x|00|0|m",
+            "1:65536|-1|0|m",
+            "1:65536|00|3:0:0|m",
+            "1:65536|00|3:2:0:16|m",
+            "1:65536|00|3:0:0:0|m",
+            "1:65536|00|3:0:0:257|m",
+            "1:65536|00|0|m,x",
+        ] {
+            assert!(s.parse::<Fingerprint>().is_err(), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn settings_frame_in_order() {
+        let fingerprint: Fingerprint = "4:6291456;2:0;1:65536|00|0|".parse().unwrap();
+        let settings = fingerprint.settings_frame().unwrap();
+        assert_eq!(settings.initial_window_size(), Some(6291456));
+        assert_eq!(settings.is_push_enabled(), Some(false));
+        assert_eq!(settings.header_table_size(), Some(65536));
+        assert_eq!(settings.max_frame_size(), None);
+
+        let mut buf = bytes::BytesMut::new();
+        settings.encode(&mut buf);
+        assert_eq!(
+            &buf[9..],
+            &[0, 4, 0, 0x60, 0, 0, 0, 2, 0, 0, 0, 0, 0, 1, 0, 1, 0, 0]
+        );
+    }
+
+    #[test]
+    fn invalid_for_client() {
+        for s in [
+            "1:65536;1:4096|00|0|",
+            "2:2|00|0|",
+            "5:1024|00|0|",
+            "4:2147483648|00|0|",
+            "2570:7|00|0|",
+        ] {
+            let fingerprint: Fingerprint = s.parse().unwrap();
+            assert!(fingerprint.settings_frame().is_err(), "{:?}", s);
+        }
+
+        let fingerprint: Fingerprint = "|0|0|".parse().unwrap();
+        assert!(fingerprint.connection_window_size().is_err());
+        let fingerprint: Fingerprint = "|2147418113|0|".parse().unwrap();
+        assert!(fingerprint.connection_window_size().is_err());
+        let fingerprint: Fingerprint = "|2147418112|0|".parse().unwrap();
+        assert_eq!(
+            fingerprint.connection_window_size().unwrap(),
+            Some(2147483647)
+        );
+
+        let fingerprint: Fingerprint = "|00|0:0:0:16|".parse().unwrap();
+        assert!(fingerprint.priority_frames().is_err());
+        let fingerprint: Fingerprint = "|00|3:0:0:16,3:0:0:32|".parse().unwrap();
+        assert!(fingerprint.priority_frames().is_err());
+        let fingerprint: Fingerprint = "|00|2147483646:0:0:16|".parse().unwrap();
+        assert!(fingerprint.priority_frames().is_err());
+
+        let fingerprint: Fingerprint = "|00|3:0:0:16,6:0:0:32|".parse().unwrap();
+        assert_eq!(fingerprint.first_stream_id(), Some(StreamId::from(7)));
+        let fingerprint: Fingerprint = "|00|3:0:0:16,7:0:0:32|".parse().unwrap();
+        assert_eq!(fingerprint.first_stream_id(), Some(StreamId::from(9)));
+
+        let fingerprint: Fingerprint = "|00|0|m,a,m".parse().unwrap();
+        assert!(fingerprint.pseudo_header_order().is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use std::fmt;
+
+use bytes::{BufMut, Bytes};
+
+use crate::frame::{self, Error, Head, Kind, StreamId};
+use crate::tracing;
+
+/// An alternative service advertised by a server in an ALTSVC frame.
+///
+/// An ALTSVC frame sent on stream zero applies to the origin it names. One
+/// sent on a stream applies to the origin of that stream's request and has an
+/// empty origin. The field value uses the syntax of the `Alt-Svc` header
+/// field, for instance `h3=":443"; ma=3600` (see [RFC 7838 section 4]).
+///
+/// [RFC 7838 section 4]: https://datatracker.ietf.org/doc/html/rfc7838#section-4
+#[derive(Clone, Eq, PartialEq)]
+pub struct AltSvc {
+    stream_id: StreamId,
+    origin: Bytes,
+    field_value: Bytes,
+}
+
+impl AltSvc {
+    /// Creates a new ALTSVC frame.
+    pub fn new(stream_id: StreamId, origin: Bytes, field_value: Bytes) -> AltSvc {
+        AltSvc {
+            stream_id,
+            origin,
+            field_value,
+        }
+    }
+
+    /// Returns the stream the frame was sent on, zero for a frame about the
+    /// whole connection.
+    pub fn stream_id(&self) -> StreamId {
+        self.stream_id
+    }
+
+    /// Returns the origin the alternative service applies to, which is empty
+    /// when the frame was sent on a stream.
+    pub fn origin(&self) -> &Bytes {
+        &self.origin
+    }
+
+    /// Returns the `Alt-Svc` field value describing the alternative service.
+    pub fn field_value(&self) -> &Bytes {
+        &self.field_value
+    }
+
+    /// Creates a connection level frame advertising alternative services for
+    /// `origin`, checking that it can be sent.
+    pub(crate) fn for_origin(
+        origin: &str,
+        field_value: Bytes,
+    ) -> Result<AltSvc, crate::codec::UserError> {
+        use crate::codec::UserError;
+
+        if !super::origin::is_valid_origin(origin) {
+            return Err(UserError::InvalidOrigin);
+        }
+
+        let frame = AltSvc::new(
+            StreamId::zero(),
+            Bytes::copy_from_slice(origin.as_bytes()),
+            field_value,
+        );
+
+        if frame.payload_len() > frame::DEFAULT_MAX_FRAME_SIZE as usize {
+            return Err(UserError::PayloadTooBig);
+        }
+
+        Ok(frame)
+    }
+
+    /// Returns true if the frame is well formed: an origin on stream zero and
+    /// no origin on any other stream.
+    pub(crate) fn is_valid(&self) -> bool {
+        self.stream_id.is_zero() != self.origin.is_empty()
+    }
+
+    pub(crate) fn payload_len(&self) -> usize {
+        2 + self.origin.len() + self.field_value.len()
+    }
+
+    pub(crate) fn load(head: Head, mut payload: Bytes) -> Result<AltSvc, Error> {
+        debug_assert_eq!(head.kind(), Kind::AltSvc);
+
+        if payload.len() < 2 {
+            return Err(Error::BadFrameSize);
+        }
+
+        let origin_len = ((payload[0] as usize) << 8) | payload[1] as usize;
+        if payload.len() < 2 + origin_len {
+            return Err(Error::BadFrameSize);
+        }
+
+        let field_value = payload.split_off(2 + origin_len);
+        let origin = payload.split_off(2);
+
+        Ok(AltSvc {
+            stream_id: head.stream_id(),
+            origin,
+            field_value,
+        })
+    }
+
+    pub(crate) fn encode<B: BufMut>(&self, dst: &mut B) {
+        tracing::trace!("encoding ALTSVC; id={:?}", self.stream_id);
+        let head = Head::new(Kind::AltSvc, 0, self.stream_id);
+        head.encode(self.payload_len(), dst);
+        dst.put_u16(self.origin.len() as u16);
+        dst.put(self.origin.slice(..));
+        dst.put(self.field_value.slice(..));
+    }
+}
+
+impl<B> From<AltSvc> for frame::Frame<B> {
+    fn from(src: AltSvc) -> Self {
+        frame::Frame::AltSvc(src)
+    }
+}
+
+impl fmt::Debug for AltSvc {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("AltSvc")
+            .field("stream_id", &self.stream_id)
+            .field("origin", &self.origin)
+            .field("field_value", &self.field_value)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_load_round_trip() {
+        let frame = AltSvc::new(
+            StreamId::zero(),
+            Bytes::from_static(b"https://example.com"),
+            Bytes::from_static(b"h3=\":443\""),
+        );
+        assert!(frame.is_valid());
+
+        let mut buf = Vec::new();
+        frame.encode(&mut buf);
+        assert_eq!(buf[3], 0xa);
+        assert_eq!(&buf[9..11], &[0, 19]);
+
+        let head = Head::parse(&buf);
+        let payload = Bytes::copy_from_slice(&buf[9..]);
+        assert_eq!(AltSvc::load(head, payload).unwrap(), frame);
+    }
+
+    #[test]
+    fn load_stream_frame() {
+        let head = Head::new(Kind::AltSvc, 0, 1.into());
+        let frame = AltSvc::load(head, Bytes::from_static(b"\0\0h2=\":8443\"")).unwrap();
+        assert!(frame.is_valid());
+        assert!(frame.origin().is_empty());
+        assert_eq!(frame.field_value(), &b"h2=\":8443\""[..]);
+    }
+
+    #[test]
+    fn load_truncated_origin() {
+        let head = Head::new(Kind::AltSvc, 0, StreamId::zero());
+        assert_eq!(
+            AltSvc::load(head, Bytes::from_static(b"\0\x05abc")),
+            Err(Error::BadFrameSize)
+        );
+        assert_eq!(
+            AltSvc::load(head, Bytes::from_static(b"\0")),
+            Err(Error::BadFrameSize)
+        );
+    }
+
+    #[test]
+    fn is_valid() {
+        let origin = Bytes::from_static(b"https://example.com");
+        let value = Bytes::from_static(b"clear");
+
+        assert!(!AltSvc::new(StreamId::zero(), Bytes::new(), value.clone()).is_valid());
+        assert!(!AltSvc::new(1.into(), origin, value).is_valid());
+    }
+}
@@ -75,6 +75,35 @@ impl<T> Data<T> {
         self.flags.set_padded();
     }
 
+    /// Returns the length of the padding that follows the payload, if the
+    /// frame is padded.
+    ///
+    /// This does **not** include the Pad Length field itself.
+    pub fn pad_len(&self) -> Option<u8> {
+        if self.flags.is_padded() {
+            self.pad_len
+        } else {
+            None
+        }
+    }
+
+    /// Sets the amount of padding to encode after the payload, or removes it.
+    pub(crate) fn set_pad_len(&mut self, pad_len: Option<u8>) {
+        self.pad_len = pad_len;
+
+        if pad_len.is_some() {
+            self.flags.set_padded();
+        } else {
+            self.flags.unset_padded();
+        }
+    }
+
+    /// Returns the number of bytes that padding adds to the frame payload,
+    /// the Pad Length field included.
+    pub(crate) fn padding_len(&self) -> usize {
+        self.pad_len().map_or(0, |pad_len| 1 + pad_len as usize)
+    }
+
     /// Returns a reference to this frame's payload.
     ///
     /// This does **not** include any padding that might have been originally
@@ -103,6 +132,23 @@ impl<T> Data<T> {
         Head::new(Kind::Data, self.flags.into(), self.stream_id)
     }
 
+    /// Encodes the frame head, and the Pad Length field if the frame is
+    /// padded, for a payload of `len` bytes.
+    pub(crate) fn encode_head<U: BufMut>(&self, len: usize, dst: &mut U) {
+        self.head().encode(len + self.padding_len(), dst);
+
+        if let Some(pad_len) = self.pad_len() {
+            dst.put_u8(pad_len);
+        }
+    }
+
+    /// Encodes the padding that follows the payload.
+    pub(crate) fn encode_padding<U: BufMut>(&self, dst: &mut U) {
+        if let Some(pad_len) = self.pad_len() {
+            dst.put_bytes(0, pad_len as usize);
+        }
+    }
+
     pub(crate) fn map<F, U>(self, f: F) -> Data<U>
     where
         F: FnOnce(T) -> U,
@@ -150,10 +196,11 @@ impl<T: Buf> Data<T> {
     pub(crate) fn encode_chunk<U: BufMut>(&mut self, dst: &mut U) {
         let len = self.data.remaining();
 
-        assert!(dst.remaining_mut() >= len);
+        assert!(dst.remaining_mut() >= len + self.padding_len());
 
-        self.head().encode(len, dst);
+        self.encode_head(len, dst);
         dst.put(&mut self.data);
+        self.encode_padding(dst);
     }
 }
 
@@ -205,10 +252,13 @@ impl DataFlags {
         self.0 & PADDED == PADDED
     }
 
-    #[cfg(feature = "unstable")]
     fn set_padded(&mut self) {
         self.0 |= PADDED
     }
+
+    fn unset_padded(&mut self) {
+        self.0 &= !PADDED
+    }
 }
 
 impl From<DataFlags> for u8 {
@@ -0,0 +1,123 @@
+use std::fmt;
+
+use bytes::{BufMut, Bytes};
+
+use crate::frame::{self, Head, Kind, StreamId};
+use crate::tracing;
+
+/// A frame of a type that is not known to the library.
+///
+/// HTTP/2 can be extended with new frame types (see [RFC 9113 section 5.5]).
+/// The frame types defined by RFC 9113 itself, type 0 (DATA) to type 9
+/// (CONTINUATION), and the extension frames the library implements, such as
+/// ORIGIN, ALTSVC and PRIORITY_UPDATE, are handled by the library and cannot
+/// be represented by an `ExtensionFrame`. Any other type is passed through
+/// untouched: the payload is neither parsed nor flow controlled.
+///
+/// [RFC 9113 section 5.5]: https://datatracker.ietf.org/doc/html/rfc9113#section-5.5
+#[derive(Clone, Eq, PartialEq)]
+pub struct ExtensionFrame {
+    kind: u8,
+    flags: u8,
+    stream_id: StreamId,
+    payload: Bytes,
+}
+
+impl ExtensionFrame {
+    /// Creates a new extension frame.
+    ///
+    /// The payload of a frame must not be larger than 16,384 bytes, the
+    /// largest frame size every peer has to accept. Frames of a type defined
+    /// by RFC 9113 and oversized frames are rejected when they are sent.
+    pub fn new(kind: u8, flags: u8, stream_id: StreamId, payload: Bytes) -> Self {
+        ExtensionFrame {
+            kind,
+            flags,
+            stream_id,
+            payload,
+        }
+    }
+
+    /// Returns the frame type.
+    pub fn kind(&self) -> u8 {
+        self.kind
+    }
+
+    /// Returns the frame flags.
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
+
+    /// Returns the stream the frame was sent on, which is zero for frames
+    /// about the whole connection.
+    pub fn stream_id(&self) -> StreamId {
+        self.stream_id
+    }
+
+    /// Returns the frame payload.
+    pub fn payload(&self) -> &Bytes {
+        &self.payload
+    }
+
+    /// Consumes the frame, returning its payload.
+    pub fn into_payload(self) -> Bytes {
+        self.payload
+    }
+
+    /// Checks that the frame can be sent: its type is not known to the library
+    /// and its payload fits in any frame.
+    pub(crate) fn validate(&self) -> Result<(), crate::codec::UserError> {
+        use crate::codec::UserError;
+
+        if Kind::new(self.kind) != Kind::Unknown {
+            return Err(UserError::UnexpectedFrameType);
+        }
+
+        if self.payload.len() > frame::DEFAULT_MAX_FRAME_SIZE as usize {
+            return Err(UserError::PayloadTooBig);
+        }
+
+        Ok(())
+    }
+
+    /// Builds an `ExtensionFrame` from the frame head, the frame type byte and
+    /// the payload.
+    pub(crate) fn load(head: Head, kind: u8, payload: Bytes) -> Self {
+        debug_assert_eq!(head.kind(), Kind::Unknown);
+
+        ExtensionFrame {
+            kind,
+            flags: head.flag(),
+            stream_id: head.stream_id(),
+            payload,
+        }
+    }
+
+    pub(crate) fn encode<B: BufMut>(&self, dst: &mut B) {
+        tracing::trace!("encoding extension frame; kind={}", self.kind);
+
+        // `Head` cannot represent the frame type, write the head by hand.
+        dst.put_uint(self.payload.len() as u64, 3);
+        dst.put_u8(self.kind);
+        dst.put_u8(self.flags);
+        dst.put_u32(self.stream_id.into());
+        dst.put(self.payload.slice(..));
+    }
+}
+
+impl<B> From<ExtensionFrame> for frame::Frame<B> {
+    fn from(src: ExtensionFrame) -> Self {
+        frame::Frame::Extension(src)
+    }
+}
+
+impl fmt::Debug for ExtensionFrame {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("ExtensionFrame")
+            .field("kind", &self.kind)
+            .field("flags", &format_args!("{:#x}", self.flags))
+            .field("stream_id", &self.stream_id)
+            .field("payload_len", &self.payload.len())
+            .finish()
+    }
+}
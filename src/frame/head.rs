@@ -9,19 +9,37 @@ pub struct Head {
     stream_id: StreamId,
 }
 
+/// The type of a frame.
 #[repr(u8)]
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Kind {
+    /// DATA, see RFC 7540 section 6.1.
     Data = 0,
+    /// HEADERS, see RFC 7540 section 6.2.
     Headers = 1,
+    /// PRIORITY, see RFC 7540 section 6.3.
     Priority = 2,
+    /// RST_STREAM, see RFC 7540 section 6.4.
     Reset = 3,
+    /// SETTINGS, see RFC 7540 section 6.5.
     Settings = 4,
+    /// PUSH_PROMISE, see RFC 7540 section 6.6.
     PushPromise = 5,
+    /// PING, see RFC 7540 section 6.7.
     Ping = 6,
+    /// GOAWAY, see RFC 7540 section 6.8.
     GoAway = 7,
+    /// WINDOW_UPDATE, see RFC 7540 section 6.9.
     WindowUpdate = 8,
+    /// CONTINUATION, see RFC 7540 section 6.10.
     Continuation = 9,
+    /// ALTSVC, see RFC 7838 section 4.
+    AltSvc = 10,
+    /// ORIGIN, see RFC 8336.
+    Origin = 12,
+    /// PRIORITY_UPDATE, see RFC 9218 section 7.1.
+    PriorityUpdate = 16,
+    /// Any other frame type.
     Unknown,
 }
 
@@ -76,6 +94,7 @@ impl Head {
 // ===== impl Kind =====
 
 impl Kind {
+    /// Returns the kind of frame with the given frame type byte.
     pub fn new(byte: u8) -> Kind {
         match byte {
             0 => Kind::Data,
@@ -88,6 +107,9 @@ impl Kind {
             7 => Kind::GoAway,
             8 => Kind::WindowUpdate,
             9 => Kind::Continuation,
+            10 => Kind::AltSvc,
+            12 => Kind::Origin,
+            16 => Kind::PriorityUpdate,
             _ => Kind::Unknown,
         }
     }
@@ -1,6 +1,6 @@
 use super::{util, StreamDependency, StreamId};
 use crate::ext::Protocol;
-use crate::frame::{Error, Frame, Head, Kind};
+use crate::frame::{Error, Frame, Head, Kind, Padder, HEADER_LEN};
 use crate::hpack::{self, BytesStr};
 use crate::tracing;
 
@@ -63,7 +63,7 @@ pub struct Continuation {
 }
 
 // TODO: These fields shouldn't be `pub`
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Debug, Default, Eq)]
 pub struct Pseudo {
     // Request
     pub method: Option<Method>,
@@ -76,6 +76,9 @@ pub struct Pseudo {
     pub status: Option<StatusCode>,
 
     // Pseudo order
+    //
+    // When decoded, the pseudo headers that were received come first, in the
+    // order they were received, followed by the remaining ones.
     pub order: PseudoOrder,
 }
 
@@ -87,11 +90,17 @@ define_enum_with_values! {
     /// possible pseudo-header fields and their standard order according to RFC 7540.
     @U8
     pub enum PseudoId {
+        /// The `:method` pseudo-header field.
         Method => 0x0001,
+        /// The `:scheme` pseudo-header field.
         Scheme => 0x0002,
+        /// The `:authority` pseudo-header field.
         Authority => 0x0003,
+        /// The `:path` pseudo-header field.
         Path => 0x0004,
+        /// The `:protocol` pseudo-header field of the Extended CONNECT Protocol.
         Protocol => 0x0005,
+        /// The `:status` pseudo-header field.
         Status => 0x0006,
     }
 }
@@ -105,6 +114,10 @@ define_enum_with_values! {
 ///
 /// Typically, a `PseudoOrder` is constructed using the [`PseudoOrderBuilder`] to enforce uniqueness
 /// and protocol-compliant ordering.
+///
+/// The order decoded from a received header block only lists the pseudo-header fields that were
+/// received. When such an order is used to encode a header block, fields it does not list follow
+/// the listed ones in their default order.
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct PseudoOrder {
     ids: SmallVec<[PseudoId; PseudoId::DEFAULT_STACK_SIZE]>,
@@ -125,12 +138,21 @@ pub struct PseudoOrderBuilder {
 // ===== impl PseudoOrder =====
 
 impl PseudoOrder {
+    /// Creates a new `PseudoOrderBuilder`.
     pub fn builder() -> PseudoOrderBuilder {
         PseudoOrderBuilder {
             ids: SmallVec::new(),
             mask: 0,
         }
     }
+
+    /// Creates an order listing exactly the given fields, without the
+    /// default ones the builder appends.
+    fn received(ids: impl IntoIterator<Item = PseudoId>) -> PseudoOrder {
+        PseudoOrder {
+            ids: PseudoOrder::builder().extend(ids).ids,
+        }
+    }
 }
 
 impl Default for PseudoOrder {
@@ -153,6 +175,7 @@ impl<'a> IntoIterator for &'a PseudoOrder {
 // ===== impl PseudoOrderBuilder =====
 
 impl PseudoOrderBuilder {
+    /// Appends `id` to the order, unless it is already present.
     pub fn push(mut self, id: PseudoId) -> Self {
         let mask_id = id.mask_id();
         if mask_id != 0 {
@@ -166,6 +189,7 @@ impl PseudoOrderBuilder {
         self
     }
 
+    /// Appends every id yielded by `iter` to the order.
     pub fn extend(mut self, iter: impl IntoIterator<Item = PseudoId>) -> Self {
         for id in iter {
             self = self.push(id);
@@ -173,6 +197,8 @@ impl PseudoOrderBuilder {
         self
     }
 
+    /// Builds the `PseudoOrder`. Pseudo-header fields that were not pushed
+    /// follow the pushed ones, in their default order.
     pub fn build(mut self) -> PseudoOrder {
         if self.ids.len() != PseudoId::DEFAULT_IDS.len() {
             self = self.extend(PseudoId::DEFAULT_IDS);
@@ -181,6 +207,118 @@ impl PseudoOrderBuilder {
     }
 }
 
+/// Represents the order of regular (non pseudo) header fields in a header block.
+///
+/// When a `HeaderOrder` is applied to an outgoing header block, the listed fields are
+/// encoded first, in the given order, followed by any remaining fields in their original
+/// `HeaderMap` order. Names that are not present in the header block are skipped.
+///
+/// A `HeaderOrder` is constructed using the [`HeaderOrderBuilder`], which discards
+/// duplicate names.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct HeaderOrder {
+    names: Vec<HeaderName>,
+}
+
+/// A builder for constructing a `HeaderOrder`.
+///
+/// Header names are pushed in the order they should be encoded. Pushing a name that is
+/// already part of the order has no effect. Call `.build()` to obtain the `HeaderOrder`.
+#[derive(Debug, Default)]
+pub struct HeaderOrderBuilder {
+    names: Vec<HeaderName>,
+}
+
+// ===== impl HeaderOrder =====
+
+impl HeaderOrder {
+    /// Returns a new `HeaderOrderBuilder`.
+    pub fn builder() -> HeaderOrderBuilder {
+        HeaderOrderBuilder::default()
+    }
+
+    /// Returns `true` if no header names are part of the order.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns an iterator over the ordered header names.
+    pub fn iter(&self) -> std::slice::Iter<'_, HeaderName> {
+        self.names.iter()
+    }
+
+    /// Rebuilds `fields` so that iteration yields the ordered names first.
+    fn apply(&self, fields: HeaderMap) -> HeaderMap {
+        if self.names.is_empty() || fields.is_empty() {
+            return fields;
+        }
+
+        let mut ordered = HeaderMap::with_capacity(fields.len());
+
+        for name in &self.names {
+            for value in fields.get_all(name) {
+                ordered.append(name.clone(), value.clone());
+            }
+        }
+
+        // Unlisted fields keep their relative order. `IntoIter` only yields the
+        // name for the first value of each field, so the filter has to remember
+        // whether the current field is being skipped.
+        let mut skip = false;
+        ordered.extend(fields.into_iter().filter(|(name, _)| {
+            if let Some(name) = name {
+                skip = self.names.contains(name);
+            }
+            !skip
+        }));
+
+        ordered
+    }
+}
+
+impl<'a> IntoIterator for &'a HeaderOrder {
+    type Item = &'a HeaderName;
+    type IntoIter = std::slice::Iter<'a, HeaderName>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.names.iter()
+    }
+}
+
+impl<N: Into<HeaderName>> FromIterator<N> for HeaderOrder {
+    fn from_iter<I: IntoIterator<Item = N>>(iter: I) -> Self {
+        HeaderOrder::builder().extend(iter).build()
+    }
+}
+
+// ===== impl HeaderOrderBuilder =====
+
+impl HeaderOrderBuilder {
+    /// Appends `name` to the order, unless it is already present.
+    pub fn push(mut self, name: impl Into<HeaderName>) -> Self {
+        let name = name.into();
+        if !self.names.contains(&name) {
+            self.names.push(name);
+        } else {
+            tracing::trace!("duplicate header name in order: {:?}", name);
+        }
+        self
+    }
+
+    /// Appends every name yielded by `iter` to the order.
+    pub fn extend<N: Into<HeaderName>>(mut self, iter: impl IntoIterator<Item = N>) -> Self {
+        for name in iter {
+            self = self.push(name);
+        }
+        self
+    }
+
+    /// Builds the `HeaderOrder`.
+    pub fn build(self) -> HeaderOrder {
+        HeaderOrder { names: self.names }
+    }
+}
+
 #[derive(Debug)]
 pub struct Iter {
     /// Pseudo headers
@@ -371,6 +509,17 @@ impl Headers {
         self.stream_dep = Some(stream_dep);
     }
 
+    /// Returns the stream dependency, if the PRIORITY flag is set.
+    pub fn stream_dependency(&self) -> Option<StreamDependency> {
+        self.stream_dep
+    }
+
+    /// Reorders the regular header fields according to `order`.
+    pub fn set_header_order(&mut self, order: &HeaderOrder) {
+        let fields = std::mem::take(&mut self.header_block.fields);
+        self.header_block.fields = order.apply(fields);
+    }
+
     /// Whether it has status 1xx
     pub(crate) fn is_informational(&self) -> bool {
         self.header_block.pseudo.is_informational()
@@ -384,9 +533,10 @@ impl Headers {
         self.header_block.fields
     }
 
-    pub fn encode(
+    pub(crate) fn encode(
         self,
         encoder: &mut hpack::Encoder,
+        padder: &mut Padder,
         dst: &mut EncodeBuf<'_>,
     ) -> Option<Continuation> {
         // At this point, the `is_end_headers` flag should always be set
@@ -395,14 +545,16 @@ impl Headers {
         // Get the HEADERS frame head
         let head = self.head();
 
-        self.header_block
-            .into_encoding(encoder)
-            .encode(head, dst, |dst| {
-                if let Some(ref stream_dep) = self.stream_dep {
-                    // write 5 bytes for the stream dependency
-                    stream_dep.encode(dst);
-                }
-            })
+        let block = self.header_block.into_encoding(encoder);
+        let dep_len = if self.stream_dep.is_some() { 5 } else { 0 };
+        let pad_len = block.pad_len(padder, dep_len, dst);
+
+        block.encode(head, dst, pad_len, |dst| {
+            if let Some(ref stream_dep) = self.stream_dep {
+                // write 5 bytes for the stream dependency
+                stream_dep.encode(dst);
+            }
+        })
     }
 
     fn head(&self) -> Head {
@@ -521,6 +673,12 @@ impl PushPromise {
         &self.header_block.fields
     }
 
+    /// Reorders the regular header fields according to `order`.
+    pub fn set_header_order(&mut self, order: &HeaderOrder) {
+        let fields = std::mem::take(&mut self.header_block.fields);
+        self.header_block.fields = order.apply(fields);
+    }
+
     #[cfg(feature = "unstable")]
     pub fn into_fields(self) -> HeaderMap {
         self.header_block.fields
@@ -610,9 +768,10 @@ impl PushPromise {
         self.header_block.is_over_size
     }
 
-    pub fn encode(
+    pub(crate) fn encode(
         self,
         encoder: &mut hpack::Encoder,
+        padder: &mut Padder,
         dst: &mut EncodeBuf<'_>,
     ) -> Option<Continuation> {
         // At this point, the `is_end_headers` flag should always be set
@@ -621,11 +780,12 @@ impl PushPromise {
         let head = self.head();
         let promised_id = self.promised_id;
 
-        self.header_block
-            .into_encoding(encoder)
-            .encode(head, dst, |dst| {
-                dst.put_u32(promised_id.into());
-            })
+        let block = self.header_block.into_encoding(encoder);
+        let pad_len = block.pad_len(padder, 4, dst);
+
+        block.encode(head, dst, pad_len, |dst| {
+            dst.put_u32(promised_id.into());
+        })
     }
 
     fn head(&self) -> Head {
@@ -666,7 +826,7 @@ impl Continuation {
         // Get the CONTINUATION frame head
         let head = self.head();
 
-        self.header_block.encode(head, dst, |_| {})
+        self.header_block.encode(head, dst, None, |_| {})
     }
 }
 
@@ -758,6 +918,23 @@ impl Pseudo {
         self.order = order;
     }
 
+    /// Returns the order of the pseudo header fields.
+    pub fn order(&self) -> &PseudoOrder {
+        &self.order
+    }
+
+    /// Returns whether the pseudo header field `id` is set.
+    pub fn contains(&self, id: PseudoId) -> bool {
+        match id {
+            PseudoId::Method => self.method.is_some(),
+            PseudoId::Scheme => self.scheme.is_some(),
+            PseudoId::Authority => self.authority.is_some(),
+            PseudoId::Path => self.path.is_some(),
+            PseudoId::Protocol => self.protocol.is_some(),
+            PseudoId::Status => self.status.is_some(),
+        }
+    }
+
     /// Whether it has status 1xx
     pub(crate) fn is_informational(&self) -> bool {
         self.status
@@ -765,10 +942,43 @@ impl Pseudo {
     }
 }
 
+impl PartialEq for Pseudo {
+    /// The order is not taken into account, only the pseudo header values.
+    fn eq(&self, other: &Pseudo) -> bool {
+        self.method == other.method
+            && self.scheme == other.scheme
+            && self.authority == other.authority
+            && self.path == other.path
+            && self.protocol == other.protocol
+            && self.status == other.status
+    }
+}
+
 // ===== impl EncodingHeaderBlock =====
 
 impl EncodingHeaderBlock {
-    fn encode<F>(mut self, head: Head, dst: &mut EncodeBuf<'_>, f: F) -> Option<Continuation>
+    /// Returns the amount of padding to add to the frame carrying this block,
+    /// `fixed_len` being the length of the fields preceding the block.
+    ///
+    /// Only frames carrying the whole block are padded.
+    fn pad_len(&self, padder: &mut Padder, fixed_len: usize, dst: &EncodeBuf<'_>) -> Option<u8> {
+        if !padder.is_enabled() {
+            return None;
+        }
+
+        let len = fixed_len + self.hpack.len();
+        let room = dst.remaining_mut().checked_sub(HEADER_LEN + len)?;
+
+        padder.pad_len(len, room)
+    }
+
+    fn encode<F>(
+        mut self,
+        head: Head,
+        dst: &mut EncodeBuf<'_>,
+        pad_len: Option<u8>,
+        f: F,
+    ) -> Option<Continuation>
     where
         F: FnOnce(&mut EncodeBuf<'_>),
     {
@@ -781,6 +991,11 @@ impl EncodingHeaderBlock {
 
         let payload_pos = dst.get_ref().len();
 
+        if let Some(pad_len) = pad_len {
+            dst.put_u8(pad_len);
+            dst.get_mut()[head_pos + 4] |= PADDED;
+        }
+
         f(dst);
 
         // Now, encode the header payload
@@ -797,6 +1012,11 @@ impl EncodingHeaderBlock {
             None
         };
 
+        if let Some(pad_len) = pad_len {
+            debug_assert!(continuation.is_none());
+            dst.put_bytes(0, pad_len as usize);
+        }
+
         // Compute the header block length
         let payload_len = (dst.get_ref().len() - payload_pos) as u64;
 
@@ -826,7 +1046,7 @@ impl Iterator for Iter {
         use crate::hpack::Header::*;
 
         if let Some(ref mut pseudo) = self.pseudo {
-            for pseudo_type in &pseudo.order {
+            for pseudo_type in pseudo.order.ids.iter().chain(&PseudoId::DEFAULT_IDS) {
                 match pseudo_type {
                     PseudoId::Method => {
                         if let Some(method) = pseudo.method.take() {
@@ -988,9 +1208,10 @@ impl HeaderBlock {
         let mut reg = !self.fields.is_empty();
         let mut malformed = false;
         let mut headers_size = self.calculate_header_list_size();
+        let mut order: SmallVec<[PseudoId; PseudoId::DEFAULT_STACK_SIZE]> = SmallVec::new();
 
         macro_rules! set_pseudo {
-            ($field:ident, $val:expr) => {{
+            ($field:ident, $id:ident, $val:expr) => {{
                 if reg {
                     tracing::trace!("load_hpack; header malformed -- pseudo not at head of block");
                     malformed = true;
@@ -1003,6 +1224,7 @@ impl HeaderBlock {
                         decoded_header_size(stringify!($field).len() + 1, __val.as_str().len());
                     if headers_size < max_header_list_size {
                         self.pseudo.$field = Some(__val);
+                        order.push(PseudoId::$id);
                     } else if !self.is_over_size {
                         tracing::trace!("load_hpack; header list size over max");
                         self.is_over_size = true;
@@ -1053,15 +1275,17 @@ impl HeaderBlock {
                         }
                     }
                 }
-                Authority(v) => set_pseudo!(authority, v),
-                Method(v) => set_pseudo!(method, v),
-                Scheme(v) => set_pseudo!(scheme, v),
-                Path(v) => set_pseudo!(path, v),
-                Protocol(v) => set_pseudo!(protocol, v),
-                Status(v) => set_pseudo!(status, v),
+                Authority(v) => set_pseudo!(authority, Authority, v),
+                Method(v) => set_pseudo!(method, Method, v),
+                Scheme(v) => set_pseudo!(scheme, Scheme, v),
+                Path(v) => set_pseudo!(path, Path, v),
+                Protocol(v) => set_pseudo!(protocol, Protocol, v),
+                Status(v) => set_pseudo!(status, Status, v),
             }
         });
 
+        self.pseudo.order = PseudoOrder::received(order);
+
         if let Err(e) = res {
             tracing::trace!("hpack decoding error; err={:?}", e);
             return Err(e.into());
@@ -1159,7 +1383,11 @@ mod test {
         );
 
         let continuation = headers
-            .encode(&mut encoder, &mut (&mut dst).limit(frame::HEADER_LEN + 8))
+            .encode(
+                &mut encoder,
+                &mut Padder::default(),
+                &mut (&mut dst).limit(frame::HEADER_LEN + 8),
+            )
             .unwrap();
 
         assert_eq!(17, dst.len());
@@ -1370,4 +1598,155 @@ mod test {
         assert_eq!(order.ids[0], PseudoId::Scheme);
         assert_ne!(order.ids[1], PseudoId::Scheme);
     }
+
+    #[test]
+    fn test_received_pseudo_order_encoding() {
+        let mut pseudo =
+            Pseudo::request(Method::GET, Uri::from_static("https://example.com/"), None);
+        pseudo.set_pseudo_order(PseudoOrder::received([PseudoId::Path, PseudoId::Method]));
+
+        // Fields the order does not list follow in their default order.
+        let headers = Iter {
+            pseudo: Some(pseudo),
+            fields: HeaderMap::new().into_iter(),
+        };
+        let ids: Vec<_> = headers
+            .map(|header| match header {
+                hpack::Header::Method(_) => PseudoId::Method,
+                hpack::Header::Scheme(_) => PseudoId::Scheme,
+                hpack::Header::Authority(_) => PseudoId::Authority,
+                hpack::Header::Path(_) => PseudoId::Path,
+                _ => unreachable!("unexpected header {:?}", header),
+            })
+            .collect();
+        assert_eq!(
+            ids,
+            [
+                PseudoId::Path,
+                PseudoId::Method,
+                PseudoId::Scheme,
+                PseudoId::Authority,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_header_order_duplicate() {
+        let order = HeaderOrder::builder()
+            .push(header::ACCEPT)
+            .push(header::USER_AGENT)
+            .push(header::ACCEPT)
+            .build();
+
+        let names: Vec<_> = order.iter().cloned().collect();
+        assert_eq!(names, [header::ACCEPT, header::USER_AGENT]);
+    }
+
+    #[test]
+    fn test_header_order_encoding() {
+        let mut encoder = Encoder::default();
+        let mut dst = BytesMut::new();
+
+        let mut headers = Headers::new(
+            StreamId::ZERO,
+            Pseudo::request(Method::GET, Uri::from_static("https://example.com/"), None),
+            HeaderMap::from_iter(vec![
+                (header::ACCEPT, HeaderValue::from_static("*/*")),
+                (header::COOKIE, HeaderValue::from_static("a=b")),
+                (header::USER_AGENT, HeaderValue::from_static("test")),
+                (header::COOKIE, HeaderValue::from_static("c=d")),
+                (header::ACCEPT_ENCODING, HeaderValue::from_static("gzip")),
+                (header::HOST, HeaderValue::from_static("example.com")),
+            ]),
+        );
+
+        // `content-type` is not present and must be skipped; `accept-encoding`
+        // and `host` are unlisted and keep their relative order.
+        headers.set_header_order(
+            &HeaderOrder::builder()
+                .push(header::USER_AGENT)
+                .push(header::CONTENT_TYPE)
+                .push(header::COOKIE)
+                .push(header::ACCEPT)
+                .build(),
+        );
+
+        assert!(headers
+            .encode(
+                &mut encoder,
+                &mut Padder::default(),
+                &mut (&mut dst).limit(1024)
+            )
+            .is_none());
+
+        let mut decoder = hpack::Decoder::new(4096);
+        let mut fields = Vec::new();
+        decoder
+            .decode(
+                &mut Cursor::new(&mut dst.split_off(frame::HEADER_LEN)),
+                |h| {
+                    if let hpack::Header::Field { name, value } = h {
+                        fields.push((name, value));
+                    }
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            fields,
+            [
+                (header::USER_AGENT, HeaderValue::from_static("test")),
+                (header::COOKIE, HeaderValue::from_static("a=b")),
+                (header::COOKIE, HeaderValue::from_static("c=d")),
+                (header::ACCEPT, HeaderValue::from_static("*/*")),
+                (header::ACCEPT_ENCODING, HeaderValue::from_static("gzip")),
+                (header::HOST, HeaderValue::from_static("example.com")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pseudo_order_decoding() {
+        let mut encoder = Encoder::default();
+        let mut dst = BytesMut::new();
+
+        let mut pseudo =
+            Pseudo::request(Method::GET, Uri::from_static("https://example.com/"), None);
+        pseudo.set_pseudo_order(
+            PseudoOrder::builder()
+                .push(PseudoId::Path)
+                .push(PseudoId::Method)
+                .push(PseudoId::Authority)
+                .push(PseudoId::Scheme)
+                .build(),
+        );
+        let headers = Headers::new(StreamId::from(1), pseudo, HeaderMap::new());
+        assert!(headers
+            .encode(
+                &mut encoder,
+                &mut Padder::default(),
+                &mut (&mut dst).limit(1024)
+            )
+            .is_none());
+
+        let head = frame::Head::parse(&dst);
+        let (mut headers, mut src) = Headers::load(head, dst.split_off(frame::HEADER_LEN)).unwrap();
+        headers
+            .load_hpack(&mut src, 1024, &mut hpack::Decoder::new(4096))
+            .unwrap();
+
+        // Only the fields that were received are listed.
+        let order: Vec<_> = headers.pseudo().order().into_iter().copied().collect();
+        assert_eq!(
+            order,
+            [
+                PseudoId::Path,
+                PseudoId::Method,
+                PseudoId::Authority,
+                PseudoId::Scheme,
+            ]
+        );
+        assert!(headers.pseudo().contains(PseudoId::Path));
+        assert!(!headers.pseudo().contains(PseudoId::Status));
+    }
 }
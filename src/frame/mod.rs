@@ -37,12 +37,17 @@ mod tests {
 
 #[macro_use]
 mod macros;
+mod alt_svc;
 mod data;
+mod extension;
 mod go_away;
 mod head;
 mod headers;
+mod origin;
+mod padding;
 mod ping;
 mod priority;
+mod priority_update;
 mod reason;
 mod reset;
 mod settings;
@@ -50,17 +55,23 @@ mod stream_id;
 mod util;
 mod window_update;
 
+pub use self::alt_svc::AltSvc;
 pub use self::data::Data;
+pub use self::extension::ExtensionFrame;
 pub use self::go_away::GoAway;
 pub use self::head::{Head, Kind};
 #[allow(unused_imports)]
 pub use self::headers::{
-    parse_u64, Continuation, Headers, Pseudo, PseudoId, PseudoOrder, PseudoOrderBuilder,
-    PushPromise, PushPromiseHeaderError,
+    parse_u64, Continuation, HeaderOrder, HeaderOrderBuilder, Headers, Pseudo, PseudoId,
+    PseudoOrder, PseudoOrderBuilder, PushPromise, PushPromiseHeaderError,
 };
+pub use self::origin::Origin;
+pub(crate) use self::padding::Padder;
+pub use self::padding::PaddingPolicy;
 pub use self::ping::Ping;
 #[allow(unused_imports)]
 pub use self::priority::{Priorities, PrioritiesBuilder, Priority, StreamDependency};
+pub use self::priority_update::{ExtensiblePriority, PriorityUpdate};
 pub use self::reason::Reason;
 pub use self::reset::Reset;
 #[cfg(feature = "unstable")]
@@ -95,6 +106,10 @@ pub enum Frame<T = Bytes> {
     GoAway(GoAway),
     WindowUpdate(WindowUpdate),
     Reset(Reset),
+    Origin(Origin),
+    AltSvc(AltSvc),
+    PriorityUpdate(PriorityUpdate),
+    Extension(ExtensionFrame),
 }
 
 impl<T> Frame<T> {
@@ -114,6 +129,10 @@ impl<T> Frame<T> {
             GoAway(frame) => frame.into(),
             WindowUpdate(frame) => frame.into(),
             Reset(frame) => frame.into(),
+            Origin(frame) => frame.into(),
+            AltSvc(frame) => frame.into(),
+            PriorityUpdate(frame) => frame.into(),
+            Extension(frame) => frame.into(),
         }
     }
 }
@@ -132,6 +151,10 @@ impl<T> fmt::Debug for Frame<T> {
             GoAway(ref frame) => fmt::Debug::fmt(frame, fmt),
             WindowUpdate(ref frame) => fmt::Debug::fmt(frame, fmt),
             Reset(ref frame) => fmt::Debug::fmt(frame, fmt),
+            Origin(ref frame) => fmt::Debug::fmt(frame, fmt),
+            AltSvc(ref frame) => fmt::Debug::fmt(frame, fmt),
+            PriorityUpdate(ref frame) => fmt::Debug::fmt(frame, fmt),
+            Extension(ref frame) => fmt::Debug::fmt(frame, fmt),
         }
     }
 }
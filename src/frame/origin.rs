@@ -0,0 +1,186 @@
+use std::fmt;
+
+use bytes::BufMut;
+use http::Uri;
+
+use crate::frame::{self, Error, Head, Kind, StreamId};
+use crate::tracing;
+
+/// The ORIGIN frame, see RFC 8336.
+///
+/// Lists the origins the server is authoritative for, so that clients may
+/// send requests for them over the same connection.
+#[derive(Clone, Default, Eq, PartialEq)]
+pub struct Origin {
+    origins: Vec<String>,
+}
+
+impl Origin {
+    pub fn new() -> Origin {
+        Origin::default()
+    }
+
+    /// Adds an origin to the frame.
+    ///
+    /// The origin must be an ASCII serialization of an origin, such as
+    /// `https://example.com` or `https://example.com:8443`, without any path.
+    pub fn push(&mut self, origin: &str) -> Result<(), crate::codec::UserError> {
+        use crate::codec::UserError;
+
+        if !is_valid_origin(origin) {
+            return Err(UserError::InvalidOrigin);
+        }
+
+        if self.payload_len() + 2 + origin.len() > frame::DEFAULT_MAX_FRAME_SIZE as usize {
+            return Err(UserError::PayloadTooBig);
+        }
+
+        self.origins.push(origin.to_owned());
+        Ok(())
+    }
+
+    pub fn origins(&self) -> &[String] {
+        &self.origins
+    }
+
+    pub fn into_origins(self) -> Vec<String> {
+        self.origins
+    }
+
+    /// Decodes an ORIGIN frame payload.
+    ///
+    /// Entries that are not ASCII are skipped, as they cannot be an origin.
+    pub fn load(head: Head, payload: &[u8]) -> Result<Origin, Error> {
+        debug_assert_eq!(head.kind(), Kind::Origin);
+
+        if !head.stream_id().is_zero() {
+            return Err(Error::InvalidStreamId);
+        }
+
+        let mut origins = Vec::new();
+        let mut rem = payload;
+
+        while !rem.is_empty() {
+            if rem.len() < 2 {
+                return Err(Error::BadFrameSize);
+            }
+
+            let len = ((rem[0] as usize) << 8) | rem[1] as usize;
+            rem = &rem[2..];
+
+            if rem.len() < len {
+                return Err(Error::BadFrameSize);
+            }
+
+            let (entry, tail) = rem.split_at(len);
+            rem = tail;
+
+            if entry.is_ascii() {
+                // ASCII is always valid UTF-8
+                origins.push(String::from_utf8(entry.to_vec()).unwrap());
+            } else {
+                tracing::trace!("ignoring non-ASCII ORIGIN entry");
+            }
+        }
+
+        Ok(Origin { origins })
+    }
+
+    pub fn encode<B: BufMut>(&self, dst: &mut B) {
+        tracing::trace!("encoding ORIGIN; origins={}", self.origins.len());
+        let head = Head::new(Kind::Origin, 0, StreamId::zero());
+        head.encode(self.payload_len(), dst);
+
+        for origin in self.origins() {
+            dst.put_u16(origin.len() as u16);
+            dst.put_slice(origin.as_bytes());
+        }
+    }
+
+    fn payload_len(&self) -> usize {
+        self.origins.iter().map(|origin| 2 + origin.len()).sum()
+    }
+}
+
+/// Returns true if `origin` is the ASCII serialization of an origin: a scheme
+/// and an authority without user info, and nothing else.
+pub(super) fn is_valid_origin(origin: &str) -> bool {
+    let uri = match origin.parse::<Uri>() {
+        Ok(uri) => uri,
+        Err(_) => return false,
+    };
+
+    match (uri.scheme_str(), uri.authority()) {
+        (Some(scheme), Some(authority)) => {
+            !authority.as_str().contains('@')
+                && origin.len() == scheme.len() + 3 + authority.as_str().len()
+                && origin.starts_with(scheme)
+                && origin[scheme.len()..].starts_with("://")
+        }
+        _ => false,
+    }
+}
+
+impl<B> From<Origin> for frame::Frame<B> {
+    fn from(src: Origin) -> Self {
+        frame::Frame::Origin(src)
+    }
+}
+
+impl fmt::Debug for Origin {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Origin")
+            .field("origins", &self.origins)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn head() -> Head {
+        Head::new(Kind::Origin, 0, StreamId::zero())
+    }
+
+    #[test]
+    fn encode_load_round_trip() {
+        let mut frame = Origin::new();
+        frame.push("https://example.com").unwrap();
+        frame.push("https://example.net:8443").unwrap();
+
+        let mut buf = Vec::new();
+        frame.encode(&mut buf);
+        assert_eq!(buf[3], 0xc);
+        assert_eq!(&buf[9..11], &[0, 19]);
+
+        let head = Head::parse(&buf);
+        assert_eq!(Origin::load(head, &buf[9..]).unwrap(), frame);
+    }
+
+    #[test]
+    fn push_rejects_non_origins() {
+        let mut frame = Origin::new();
+        assert!(frame.push("").is_err());
+        assert!(frame.push("example.com").is_err());
+        assert!(frame.push("https://example.com/").is_err());
+        assert!(frame.push("https://example.com/path").is_err());
+        assert!(frame.push("https://user@example.com").is_err());
+        assert!(frame.origins().is_empty());
+    }
+
+    #[test]
+    fn load_truncated_entry() {
+        assert_eq!(
+            Origin::load(head(), &[0, 5, b'a']),
+            Err(Error::BadFrameSize)
+        );
+        assert_eq!(Origin::load(head(), &[0]), Err(Error::BadFrameSize));
+    }
+
+    #[test]
+    fn load_skips_non_ascii_entries() {
+        let frame = Origin::load(head(), &[0, 2, 0xc3, 0xa9, 0, 1, b'a']).unwrap();
+        assert_eq!(frame.origins(), &["a".to_string()]);
+    }
+}
@@ -0,0 +1,156 @@
+use std::cmp;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// The largest amount of padding a single frame can carry.
+const MAX_PADDING: usize = u8::MAX as usize;
+
+/// How outgoing DATA, HEADERS and PUSH_PROMISE frames are padded.
+///
+/// Padding hides the exact size of frame payloads from observers (see
+/// [RFC 9113 section 10.7]). A padded frame has its `PADDED` flag set and
+/// carries a one byte Pad Length field followed, after the payload, by the
+/// padding itself.
+///
+/// Padding is applied on a best effort basis: it is reduced, possibly to
+/// nothing, when the frame would otherwise exceed the peer's maximum frame
+/// size. DATA frame padding counts against flow control, so it is also
+/// limited to the window left over once the data itself has been accounted
+/// for. HEADERS and PUSH_PROMISE frames that need CONTINUATION frames are
+/// never padded.
+///
+/// [RFC 9113 section 10.7]: https://datatracker.ietf.org/doc/html/rfc9113#section-10.7
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum PaddingPolicy {
+    /// Frames are not padded.
+    #[default]
+    None,
+
+    /// Frames are padded with exactly this many bytes.
+    Fixed(u8),
+
+    /// Frames are padded with a random number of bytes, up to and including
+    /// this many.
+    Random(u8),
+
+    /// Frames are padded so that their payload length, Pad Length field
+    /// included, is a multiple of this block size.
+    ///
+    /// A frame carries at most 255 bytes of padding, so larger block sizes
+    /// are not always reached. A block size of zero disables padding.
+    Block(u16),
+}
+
+/// Applies a `PaddingPolicy` to frames as they are sent.
+#[derive(Debug)]
+pub(crate) struct Padder {
+    policy: PaddingPolicy,
+    rng: u64,
+}
+
+impl Padder {
+    pub(crate) fn new(policy: PaddingPolicy) -> Padder {
+        let seed = match policy {
+            PaddingPolicy::Random(_) => RandomState::new().build_hasher().finish(),
+            _ => 0,
+        };
+
+        Padder {
+            policy,
+            // xorshift must not be seeded with zero
+            rng: seed | 1,
+        }
+    }
+
+    /// Returns true if frames may be padded at all.
+    pub(crate) fn is_enabled(&self) -> bool {
+        !matches!(self.policy, PaddingPolicy::None | PaddingPolicy::Block(0))
+    }
+
+    /// Returns the amount of padding for a frame whose payload is `len` bytes
+    /// long without padding, when at most `room` more bytes may be added to
+    /// it, the Pad Length field included.
+    ///
+    /// Returns `None` if the frame must not be padded.
+    pub(crate) fn pad_len(&mut self, len: usize, room: usize) -> Option<u8> {
+        if room == 0 {
+            return None;
+        }
+
+        let pad = match self.policy {
+            PaddingPolicy::None | PaddingPolicy::Block(0) => return None,
+            PaddingPolicy::Fixed(n) => n as usize,
+            PaddingPolicy::Random(n) => (self.next_u64() % (n as u64 + 1)) as usize,
+            PaddingPolicy::Block(size) => {
+                let size = size as usize;
+                (size - (len + 1) % size) % size
+            }
+        };
+
+        Some(cmp::min(pad, cmp::min(room - 1, MAX_PADDING)) as u8)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        // xorshift64
+        let mut x = self.rng;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.rng = x;
+        x
+    }
+}
+
+impl Default for Padder {
+    fn default() -> Padder {
+        Padder::new(PaddingPolicy::None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pad_len_none() {
+        let mut padder = Padder::default();
+        assert!(!padder.is_enabled());
+        assert_eq!(padder.pad_len(10, 100), None);
+
+        let mut padder = Padder::new(PaddingPolicy::Block(0));
+        assert!(!padder.is_enabled());
+        assert_eq!(padder.pad_len(10, 100), None);
+    }
+
+    #[test]
+    fn pad_len_fixed() {
+        let mut padder = Padder::new(PaddingPolicy::Fixed(8));
+        assert_eq!(padder.pad_len(10, 100), Some(8));
+        // Limited by the room left, the Pad Length field included.
+        assert_eq!(padder.pad_len(10, 5), Some(4));
+        assert_eq!(padder.pad_len(10, 1), Some(0));
+        assert_eq!(padder.pad_len(10, 0), None);
+    }
+
+    #[test]
+    fn pad_len_random() {
+        let mut padder = Padder::new(PaddingPolicy::Random(4));
+
+        for _ in 0..100 {
+            let pad = padder.pad_len(10, 100).unwrap();
+            assert!(pad <= 4);
+        }
+    }
+
+    #[test]
+    fn pad_len_block() {
+        let mut padder = Padder::new(PaddingPolicy::Block(16));
+        assert_eq!(padder.pad_len(10, 100), Some(5));
+        assert_eq!(padder.pad_len(15, 100), Some(0));
+        assert_eq!(padder.pad_len(16, 100), Some(15));
+        assert_eq!(padder.pad_len(16, 10), Some(9));
+
+        let mut padder = Padder::new(PaddingPolicy::Block(1024));
+        assert_eq!(padder.pad_len(0, 2048), Some(255));
+    }
+}
@@ -16,6 +16,8 @@ pub struct Ping {
 // zeroes to distinguish this specific PING from any other.
 const SHUTDOWN_PAYLOAD: Payload = [0x0b, 0x7b, 0xa2, 0xf0, 0x8b, 0x9b, 0xfe, 0x54];
 const USER_PAYLOAD: Payload = [0x3b, 0x7c, 0xdb, 0x7a, 0x0b, 0x87, 0x16, 0xb4];
+const BDP_PAYLOAD: Payload = [0x9d, 0x1e, 0x55, 0x27, 0xc4, 0x6a, 0x30, 0xe2];
+const KEEP_ALIVE_PAYLOAD: Payload = [0x51, 0xe4, 0x0c, 0x73, 0xa8, 0x2f, 0xd6, 0x19];
 
 impl Ping {
     #[cfg(feature = "unstable")]
@@ -30,6 +32,18 @@ impl Ping {
     #[cfg(not(feature = "unstable"))]
     pub(crate) const USER: Payload = USER_PAYLOAD;
 
+    #[cfg(feature = "unstable")]
+    pub const BDP: Payload = BDP_PAYLOAD;
+
+    #[cfg(not(feature = "unstable"))]
+    pub(crate) const BDP: Payload = BDP_PAYLOAD;
+
+    #[cfg(feature = "unstable")]
+    pub const KEEP_ALIVE: Payload = KEEP_ALIVE_PAYLOAD;
+
+    #[cfg(not(feature = "unstable"))]
+    pub(crate) const KEEP_ALIVE: Payload = KEEP_ALIVE_PAYLOAD;
+
     pub fn new(payload: Payload) -> Ping {
         Ping {
             ack: false,
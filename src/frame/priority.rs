@@ -78,14 +78,22 @@ impl Priority {
         })
     }
 
+    /// Returns the frame head.
     pub fn head(&self) -> Head {
         Head::new(Kind::Priority, 0, self.stream_id)
     }
 
+    /// Returns the ID of the stream this frame is for.
     pub fn stream_id(&self) -> StreamId {
         self.stream_id
     }
 
+    /// Returns the stream dependency carried by this frame.
+    pub fn dependency(&self) -> StreamDependency {
+        self.dependency
+    }
+
+    /// Encodes the frame, including its head, into `dst`.
     pub fn encode<B: BufMut>(&self, dst: &mut B) {
         let head = self.head();
         head.encode(5, dst);
@@ -136,18 +144,22 @@ impl StreamDependency {
         Ok(StreamDependency::new(dependency_id, weight, is_exclusive))
     }
 
+    /// Returns the ID of the stream dependency target.
     pub fn dependency_id(&self) -> StreamId {
         self.dependency_id
     }
 
+    /// Returns the weight, in the range [0, 255] (representing 1-256).
     pub fn weight(&self) -> u8 {
         self.weight
     }
 
+    /// Returns true if the stream dependency is exclusive.
     pub fn is_exclusive(&self) -> bool {
         self.is_exclusive
     }
 
+    /// Encodes the stream dependency into `dst`.
     pub fn encode<T: BufMut>(&self, dst: &mut T) {
         const STREAM_ID_MASK: u32 = 1 << 31;
         let mut dependency_id = self.dependency_id().into();
@@ -191,6 +203,7 @@ pub struct PrioritiesBuilder {
 // ===== impl Priorities =====
 
 impl Priorities {
+    /// Creates a new `PrioritiesBuilder`.
     pub fn builder() -> PrioritiesBuilder {
         PrioritiesBuilder {
             priorities: SmallVec::new(),
@@ -216,6 +229,8 @@ impl IntoIterator for Priorities {
 // ===== impl PrioritiesBuilder =====
 
 impl PrioritiesBuilder {
+    /// Appends `priority`, unless its stream ID is zero or a priority for the
+    /// same stream was already pushed.
     pub fn push(mut self, priority: Priority) -> Self {
         if priority.stream_id.is_zero() {
             tracing::warn!("ignoring priority frame with stream ID 0");
@@ -260,6 +275,7 @@ impl PrioritiesBuilder {
         self
     }
 
+    /// Appends every priority yielded by `priorities`.
     pub fn extend(mut self, priorities: impl IntoIterator<Item = Priority>) -> Self {
         for priority in priorities {
             self = self.push(priority);
@@ -267,6 +283,7 @@ impl PrioritiesBuilder {
         self
     }
 
+    /// Builds the `Priorities`.
     pub fn build(self) -> Priorities {
         Priorities {
             priorities: self.priorities,
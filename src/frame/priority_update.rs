@@ -0,0 +1,273 @@
+use std::{cmp, fmt};
+
+use bytes::{BufMut, Bytes};
+use http::HeaderValue;
+
+use crate::frame::{self, Error, Head, Kind, StreamId};
+use crate::tracing;
+
+/// The urgency of a request that carries no priority signal.
+const DEFAULT_URGENCY: u8 = 3;
+
+/// The lowest urgency, as a number.
+const MAX_URGENCY: u8 = 7;
+
+/// The priority parameters of a request, as defined by the [RFC 9218]
+/// extensible prioritization scheme.
+///
+/// Clients signal the priority of a response with the `priority` request
+/// header field, or with PRIORITY_UPDATE frames once the request is in
+/// flight. Servers send the responses of lower urgency numbers first. Among
+/// responses of the same urgency, non-incremental ones are sent one at a time
+/// while incremental ones share the connection.
+///
+/// The default is an urgency of 3, not incremental.
+///
+/// [RFC 9218]: https://datatracker.ietf.org/doc/html/rfc9218
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ExtensiblePriority {
+    urgency: u8,
+    incremental: bool,
+}
+
+/// A PRIORITY_UPDATE frame, see RFC 9218 section 7.1.
+#[derive(Clone, Eq, PartialEq)]
+pub struct PriorityUpdate {
+    prioritized_id: StreamId,
+    field_value: Bytes,
+}
+
+// ===== impl ExtensiblePriority =====
+
+impl ExtensiblePriority {
+    /// Creates new priority parameters.
+    ///
+    /// `urgency` ranges from 0, the most urgent, to 7. Larger values are
+    /// clamped to 7.
+    pub fn new(urgency: u8, incremental: bool) -> Self {
+        ExtensiblePriority {
+            urgency: cmp::min(urgency, MAX_URGENCY),
+            incremental,
+        }
+    }
+
+    /// Returns the urgency, from 0, the most urgent, to 7.
+    pub fn urgency(&self) -> u8 {
+        self.urgency
+    }
+
+    /// Returns true if the response can be processed incrementally, and so
+    /// may be interleaved with other responses of the same urgency.
+    pub fn is_incremental(&self) -> bool {
+        self.incremental
+    }
+
+    /// Parses the value of a `priority` header field, or of a PRIORITY_UPDATE
+    /// frame.
+    ///
+    /// Parsing is lenient: unknown parameters and parameters with invalid
+    /// values are ignored, leaving the default in place.
+    pub fn parse(value: &[u8]) -> Self {
+        let mut priority = ExtensiblePriority::default();
+
+        for member in value.split(|&b| b == b',') {
+            // Parameters of the member are not used by RFC 9218.
+            let member = member.split(|&b| b == b';').next().unwrap_or(&[]);
+            let member = trim(member);
+
+            let (key, value) = match member.iter().position(|&b| b == b'=') {
+                Some(pos) => (&member[..pos], Some(&member[pos + 1..])),
+                None => (member, None),
+            };
+
+            match (key, value) {
+                (b"u", Some(value)) => {
+                    if let [digit @ b'0'..=b'7'] = value {
+                        priority.urgency = digit - b'0';
+                    } else {
+                        tracing::trace!("ignoring invalid urgency");
+                    }
+                }
+                (b"i", None) | (b"i", Some(b"?1")) => priority.incremental = true,
+                (b"i", Some(b"?0")) => priority.incremental = false,
+                _ => {}
+            }
+        }
+
+        priority
+    }
+
+    /// Returns the `priority` header field value for these parameters.
+    pub fn to_header_value(&self) -> HeaderValue {
+        let value = if self.incremental {
+            format!("u={}, i", self.urgency)
+        } else {
+            format!("u={}", self.urgency)
+        };
+
+        HeaderValue::try_from(value).expect("priority is a valid header value")
+    }
+}
+
+impl Default for ExtensiblePriority {
+    fn default() -> Self {
+        ExtensiblePriority {
+            urgency: DEFAULT_URGENCY,
+            incremental: false,
+        }
+    }
+}
+
+fn trim(mut bytes: &[u8]) -> &[u8] {
+    while let [b' ' | b'\t', rest @ ..] = bytes {
+        bytes = rest;
+    }
+    while let [rest @ .., b' ' | b'\t'] = bytes {
+        bytes = rest;
+    }
+    bytes
+}
+
+// ===== impl PriorityUpdate =====
+
+impl PriorityUpdate {
+    pub fn new(prioritized_id: StreamId, priority: ExtensiblePriority) -> Self {
+        let value = priority.to_header_value();
+
+        PriorityUpdate {
+            prioritized_id,
+            field_value: Bytes::copy_from_slice(value.as_bytes()),
+        }
+    }
+
+    pub fn prioritized_id(&self) -> StreamId {
+        self.prioritized_id
+    }
+
+    pub fn priority(&self) -> ExtensiblePriority {
+        ExtensiblePriority::parse(&self.field_value)
+    }
+
+    pub fn load(head: Head, payload: &[u8]) -> Result<PriorityUpdate, Error> {
+        debug_assert_eq!(head.kind(), Kind::PriorityUpdate);
+
+        if !head.stream_id().is_zero() {
+            return Err(Error::InvalidStreamId);
+        }
+
+        if payload.len() < 4 {
+            return Err(Error::BadFrameSize);
+        }
+
+        let (prioritized_id, _) = StreamId::parse(&payload[..4]);
+        if prioritized_id.is_zero() {
+            return Err(Error::InvalidStreamId);
+        }
+
+        Ok(PriorityUpdate {
+            prioritized_id,
+            field_value: Bytes::copy_from_slice(&payload[4..]),
+        })
+    }
+
+    pub fn encode<B: BufMut>(&self, dst: &mut B) {
+        tracing::trace!(
+            "encoding PRIORITY_UPDATE; prioritized_id={:?}",
+            self.prioritized_id
+        );
+        let head = Head::new(Kind::PriorityUpdate, 0, StreamId::zero());
+        head.encode(4 + self.field_value.len(), dst);
+        dst.put_u32(self.prioritized_id.into());
+        dst.put(self.field_value.slice(..));
+    }
+}
+
+impl<B> From<PriorityUpdate> for frame::Frame<B> {
+    fn from(src: PriorityUpdate) -> Self {
+        frame::Frame::PriorityUpdate(src)
+    }
+}
+
+impl fmt::Debug for PriorityUpdate {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("PriorityUpdate")
+            .field("prioritized_id", &self.prioritized_id)
+            .field("field_value", &self.field_value)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> (u8, bool) {
+        let priority = ExtensiblePriority::parse(value.as_bytes());
+        (priority.urgency(), priority.is_incremental())
+    }
+
+    #[test]
+    fn parse_priority() {
+        assert_eq!(parse(""), (3, false));
+        assert_eq!(parse("u=0"), (0, false));
+        assert_eq!(parse("u=5, i"), (5, true));
+        assert_eq!(parse("i, u=7"), (7, true));
+        assert_eq!(parse("u=1,i=?1"), (1, true));
+        assert_eq!(parse("u=1, i=?0"), (1, false));
+        assert_eq!(parse("  u=2 ;foo=bar , i "), (2, true));
+        // The last occurrence wins.
+        assert_eq!(parse("u=1, u=6"), (6, false));
+    }
+
+    #[test]
+    fn parse_priority_ignores_invalid() {
+        assert_eq!(parse("u=8"), (3, false));
+        assert_eq!(parse("u=-1"), (3, false));
+        assert_eq!(parse("u=12"), (3, false));
+        assert_eq!(parse("u"), (3, false));
+        assert_eq!(parse("i=1"), (3, false));
+        assert_eq!(parse("x=1, u=4"), (4, false));
+    }
+
+    #[test]
+    fn priority_header_value() {
+        assert_eq!(ExtensiblePriority::default().to_header_value(), "u=3");
+        assert_eq!(ExtensiblePriority::new(0, true).to_header_value(), "u=0, i");
+        assert_eq!(ExtensiblePriority::new(9, false).urgency(), 7);
+    }
+
+    #[test]
+    fn encode_load_round_trip() {
+        let frame = PriorityUpdate::new(5.into(), ExtensiblePriority::new(1, true));
+
+        let mut buf = Vec::new();
+        frame.encode(&mut buf);
+        assert_eq!(buf[3], 0x10);
+        assert_eq!(&buf[9..13], &[0, 0, 0, 5]);
+        assert_eq!(&buf[13..], b"u=1, i");
+
+        let head = Head::parse(&buf);
+        let loaded = PriorityUpdate::load(head, &buf[9..]).unwrap();
+        assert_eq!(loaded, frame);
+        assert_eq!(loaded.priority(), ExtensiblePriority::new(1, true));
+    }
+
+    #[test]
+    fn load_invalid() {
+        let head = Head::new(Kind::PriorityUpdate, 0, StreamId::zero());
+        assert_eq!(
+            PriorityUpdate::load(head, &[0, 0, 1]),
+            Err(Error::BadFrameSize)
+        );
+        assert_eq!(
+            PriorityUpdate::load(head, &[0, 0, 0, 0]),
+            Err(Error::InvalidStreamId)
+        );
+
+        let head = Head::new(Kind::PriorityUpdate, 0, 1.into());
+        assert_eq!(
+            PriorityUpdate::load(head, &[0, 0, 0, 1]),
+            Err(Error::InvalidStreamId)
+        );
+    }
+}
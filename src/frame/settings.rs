@@ -222,7 +222,7 @@ impl ExperimentalSettingsBuilder {
     }
 }
 
-#[derive(Clone, Default, Eq, PartialEq)]
+#[derive(Clone, Default, Eq)]
 pub struct Settings {
     flags: SettingsFlags,
     // Fields
@@ -238,6 +238,9 @@ pub struct Settings {
     experimental_settings: Option<ExperimentalSettings>,
     // Settings order
     settings_order: SettingsOrder,
+    // Settings as they appeared on the wire, including unknown ids. Only
+    // populated by `load`.
+    received: Vec<Setting>,
 }
 
 /// An enum that lists all valid settings that can be sent in a SETTINGS
@@ -357,6 +360,14 @@ impl Settings {
         self.settings_order = settings_order;
     }
 
+    /// Returns the settings in the order they were received, including
+    /// settings with unknown ids.
+    ///
+    /// This is empty for frames that were not decoded from the wire.
+    pub fn received(&self) -> &[Setting] {
+        &self.received
+    }
+
     pub fn load(head: Head, payload: &[u8]) -> Result<Settings, Error> {
         debug_assert_eq!(head.kind(), crate::frame::Kind::Settings);
 
@@ -387,6 +398,8 @@ impl Settings {
         debug_assert!(!settings.flags.is_ack());
 
         for raw in payload.chunks(6) {
+            settings.received.push(Setting::load_raw(raw));
+
             if let Some(setting) = Setting::load(raw) {
                 match setting.id {
                     SettingId::HeaderTableSize => {
@@ -545,6 +558,27 @@ impl Settings {
     }
 }
 
+impl PartialEq for Settings {
+    /// Two SETTINGS frames are equal when they carry the same parameters,
+    /// regardless of the order they are (or were) sent in.
+    fn eq(&self, other: &Settings) -> bool {
+        let eq = self.flags == other.flags
+            && self.header_table_size == other.header_table_size
+            && self.enable_push == other.enable_push
+            && self.max_concurrent_streams == other.max_concurrent_streams
+            && self.initial_window_size == other.initial_window_size
+            && self.max_frame_size == other.max_frame_size
+            && self.max_header_list_size == other.max_header_list_size
+            && self.enable_connect_protocol == other.enable_connect_protocol
+            && self.no_rfc7540_priorities == other.no_rfc7540_priorities;
+
+        #[cfg(feature = "unstable")]
+        let eq = eq && self.experimental_settings == other.experimental_settings;
+
+        eq
+    }
+}
+
 impl<T> From<Settings> for Frame<T> {
     fn from(src: Settings) -> Frame<T> {
         Frame::Settings(src)
@@ -619,10 +653,30 @@ impl Setting {
     ///
     /// If given a buffer shorter than 6 bytes, the function will panic.
     fn load(raw: &[u8]) -> Option<Setting> {
+        let setting = Setting::load_raw(raw);
+        Setting::from_id(setting.id, setting.value)
+    }
+
+    /// Like `load`, but keeps any setting id, including ones outside of the
+    /// range accepted by `from_id`.
+    fn load_raw(raw: &[u8]) -> Setting {
         let id: u16 = (u16::from(raw[0]) << 8) | u16::from(raw[1]);
-        let val: u32 = unpack_octets_4!(raw, 2, u32);
+        let value: u32 = unpack_octets_4!(raw, 2, u32);
+
+        Setting {
+            id: id.into(),
+            value,
+        }
+    }
+
+    /// Returns the id of the setting.
+    pub fn id(&self) -> SettingId {
+        self.id
+    }
 
-        Setting::from_id(id, val)
+    /// Returns the value of the setting.
+    pub fn value(&self) -> u32 {
+        self.value
     }
 
     fn encode(&self, dst: &mut BytesMut) {
@@ -1,4 +1,5 @@
 use super::{header::BytesStr, huffman, Header};
+use crate::stats::HeaderTableStats;
 use crate::{frame, tracing};
 
 use bytes::{Buf, Bytes, BytesMut};
@@ -161,6 +162,15 @@ impl Decoder {
         }
     }
 
+    /// Returns the state of the dynamic table.
+    pub(crate) fn table_stats(&self) -> HeaderTableStats {
+        HeaderTableStats {
+            size: self.table.size(),
+            max_size: self.table.max_size,
+            entries: self.table.len(),
+        }
+    }
+
     /// Queues a potential size update
     #[allow(dead_code)]
     pub fn queue_size_update(&mut self, size: usize) {
@@ -226,10 +236,14 @@ impl Decoder {
                 LiteralNeverIndexed => {
                     tracing::trace!(rem = src.remaining(), kind = %"LiteralNeverIndexed");
                     can_resize = false;
-                    let entry = self.decode_literal(src, false)?;
+                    let mut entry = self.decode_literal(src, false)?;
                     consume(src);
 
-                    // TODO: Track that this should never be indexed
+                    // Mark the value as sensitive so that it stays never
+                    // indexed if it gets encoded again.
+                    if let Header::Field { ref mut value, .. } = entry {
+                        value.set_sensitive(true);
+                    }
 
                     f(entry);
                 }
@@ -493,11 +507,14 @@ impl Table {
         }
     }
 
-    #[cfg(any(feature = "tracing", test))]
     fn size(&self) -> usize {
         self.size
     }
 
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
     /// Returns the entry located at the given index.
     ///
     /// The table is 1-indexed and constructed in such a way that the first
@@ -885,6 +902,69 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_decode_never_indexed_is_sensitive() {
+        use crate::hpack::{Encoder, Indexing, SharedIndexingPolicy};
+        use http::header::{HeaderName, HeaderValue};
+
+        let mut value = HeaderValue::from_static("secret");
+        value.set_sensitive(true);
+
+        let headers = vec![
+            Header::Field {
+                name: Some(http::header::AUTHORIZATION),
+                value,
+            },
+            Header::Field {
+                name: Some(http::header::COOKIE),
+                value: HeaderValue::from_static("a=b"),
+            },
+            Header::Field {
+                name: Some(http::header::CONTENT_LENGTH),
+                value: HeaderValue::from_static("42"),
+            },
+            Header::Field {
+                name: Some(HeaderName::from_static("x-foo")),
+                value: HeaderValue::from_static("bar"),
+            },
+        ];
+
+        // The policy makes `cookie` never indexed, `authorization` is never
+        // indexed because its value is sensitive.
+        let mut encoder = Encoder::default();
+        encoder.set_indexing_policy(SharedIndexingPolicy::new(
+            |name: &str, _: &[u8]| match name {
+                "cookie" => Indexing::NeverIndexed,
+                _ => Indexing::Auto,
+            },
+        ));
+
+        let mut first = BytesMut::new();
+        encoder.encode(headers, &mut first);
+
+        let mut de = Decoder::new(4096);
+        let mut res = vec![];
+        de.decode(&mut Cursor::new(&mut first.clone()), |h| res.push(h))
+            .unwrap();
+
+        let sensitive: Vec<_> = res
+            .iter()
+            .map(|h| match *h {
+                Header::Field { ref value, .. } => value.is_sensitive(),
+                _ => panic!(),
+            })
+            .collect();
+        assert_eq!(sensitive, [true, true, false, false]);
+
+        // Encoding the decoded fields again, without any policy, keeps the
+        // never indexed representations.
+        let mut encoder = Encoder::default();
+        let mut second = BytesMut::new();
+        encoder.encode(res.into_iter().map(Into::into), &mut second);
+
+        assert_eq!(first, second);
+    }
+
     fn huff_encode(src: &[u8]) -> BytesMut {
         let mut buf = BytesMut::new();
         huffman::encode(src, &mut buf);
@@ -1,5 +1,6 @@
 use super::table::{Index, Table};
-use super::{huffman, Header};
+use super::{huffman, Header, HuffmanPolicy, Indexing, SharedIndexingPolicy};
+use crate::stats::HeaderTableStats;
 use crate::tracing;
 
 use bytes::{BufMut, BytesMut};
@@ -9,6 +10,8 @@ use http::header::{HeaderName, HeaderValue};
 pub struct Encoder {
     table: Table,
     size_update: Option<SizeUpdate>,
+    indexing_policy: Option<SharedIndexingPolicy>,
+    huffman_policy: HuffmanPolicy,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -22,6 +25,27 @@ impl Encoder {
         Encoder {
             table: Table::new(max_size, capacity),
             size_update: None,
+            indexing_policy: None,
+            huffman_policy: HuffmanPolicy::default(),
+        }
+    }
+
+    /// Sets the policy deciding how each header field is indexed.
+    pub(crate) fn set_indexing_policy(&mut self, policy: SharedIndexingPolicy) {
+        self.indexing_policy = Some(policy);
+    }
+
+    /// Sets when string literals are Huffman encoded.
+    pub(crate) fn set_huffman_policy(&mut self, huffman: HuffmanPolicy) {
+        self.huffman_policy = huffman;
+    }
+
+    /// Returns the state of the dynamic table.
+    pub(crate) fn table_stats(&self) -> HeaderTableStats {
+        HeaderTableStats {
+            size: self.table.size(),
+            max_size: self.table.max_size(),
+            entries: self.table.len(),
         }
     }
 
@@ -74,10 +98,7 @@ impl Encoder {
                 // The header has an associated name. In which case, try to
                 // index it in the table.
                 Ok(header) => {
-                    let index = self.table.index(header);
-                    self.encode_header(&index, dst);
-
-                    last_index = Some(index);
+                    last_index = Some(self.encode_header(header, dst));
                 }
                 // The header does not have an associated name. This means that
                 // the name is the same as the previously yielded header. In
@@ -112,45 +133,80 @@ impl Encoder {
         }
     }
 
-    fn encode_header(&mut self, index: &Index, dst: &mut BytesMut) {
-        match *index {
+    /// Returns how the indexing policy wants `header` to be represented.
+    fn indexing(&self, header: &Header) -> Indexing {
+        // Sensitive values are never indexed whatever the policy says, which
+        // the table already takes care of.
+        if header.is_sensitive() {
+            return Indexing::Auto;
+        }
+
+        match self.indexing_policy {
+            Some(ref policy) => policy.indexing(header.name().as_str(), header.value_slice()),
+            None => Indexing::Auto,
+        }
+    }
+
+    fn encode_header(&mut self, header: Header, dst: &mut BytesMut) -> Index {
+        let indexing = self.indexing(&header);
+
+        let never_indexed = indexing == Indexing::NeverIndexed;
+
+        let index = match indexing {
+            Indexing::Auto => self.table.index(header),
+            Indexing::Incremental => self.table.index_incremental(header),
+            Indexing::WithoutIndexing | Indexing::NeverIndexed => {
+                self.table.index_static(header, never_indexed)
+            }
+        };
+
+        match index {
             Index::Indexed(idx, _) => {
                 encode_int(idx, 7, 0x80, dst);
             }
             Index::Name(idx, _) => {
-                let header = self.table.resolve(index);
+                let header = self.table.resolve(&index);
 
-                encode_not_indexed(idx, header.value_slice(), header.is_sensitive(), dst);
+                encode_not_indexed(
+                    idx,
+                    header.value_slice(),
+                    never_indexed || header.is_sensitive(),
+                    self.huffman_policy,
+                    dst,
+                );
             }
             Index::Inserted(_) => {
-                let header = self.table.resolve(index);
+                let header = self.table.resolve(&index);
 
                 assert!(!header.is_sensitive());
 
                 dst.put_u8(0b0100_0000);
 
-                encode_str(header.name().as_slice(), dst);
-                encode_str(header.value_slice(), dst);
+                encode_str(header.name().as_slice(), self.huffman_policy, dst);
+                encode_str(header.value_slice(), self.huffman_policy, dst);
             }
             Index::InsertedValue(idx, _) => {
-                let header = self.table.resolve(index);
+                let header = self.table.resolve(&index);
 
                 assert!(!header.is_sensitive());
 
                 encode_int(idx, 6, 0b0100_0000, dst);
-                encode_str(header.value_slice(), dst);
+                encode_str(header.value_slice(), self.huffman_policy, dst);
             }
             Index::NotIndexed(_) => {
-                let header = self.table.resolve(index);
+                let header = self.table.resolve(&index);
 
                 encode_not_indexed2(
                     header.name().as_slice(),
                     header.value_slice(),
-                    header.is_sensitive(),
+                    never_indexed || header.is_sensitive(),
+                    self.huffman_policy,
                     dst,
                 );
             }
         }
+
+        index
     }
 
     fn encode_header_without_name(
@@ -159,6 +215,14 @@ impl Encoder {
         value: &HeaderValue,
         dst: &mut BytesMut,
     ) {
+        // Values sharing a name are never added to the table, so the policy
+        // can only decide whether they are never indexed.
+        let never_indexed = value.is_sensitive()
+            || self.indexing_policy.as_ref().map_or(false, |policy| {
+                let name = self.table.resolve(last).name();
+                policy.indexing(name.as_str(), value.as_ref()) == Indexing::NeverIndexed
+            });
+
         match *last {
             Index::Indexed(..)
             | Index::Name(..)
@@ -166,7 +230,7 @@ impl Encoder {
             | Index::InsertedValue(..) => {
                 let idx = self.table.resolve_idx(last);
 
-                encode_not_indexed(idx, value.as_ref(), value.is_sensitive(), dst);
+                encode_not_indexed(idx, value.as_ref(), never_indexed, self.huffman_policy, dst);
             }
             Index::NotIndexed(_) => {
                 let last = self.table.resolve(last);
@@ -174,7 +238,8 @@ impl Encoder {
                 encode_not_indexed2(
                     last.name().as_slice(),
                     value.as_ref(),
-                    value.is_sensitive(),
+                    never_indexed,
+                    self.huffman_policy,
                     dst,
                 );
             }
@@ -192,29 +257,51 @@ fn encode_size_update(val: usize, dst: &mut BytesMut) {
     encode_int(val, 5, 0b0010_0000, dst)
 }
 
-fn encode_not_indexed(name: usize, value: &[u8], sensitive: bool, dst: &mut BytesMut) {
+fn encode_not_indexed(
+    name: usize,
+    value: &[u8],
+    sensitive: bool,
+    policy: HuffmanPolicy,
+    dst: &mut BytesMut,
+) {
     if sensitive {
         encode_int(name, 4, 0b10000, dst);
     } else {
         encode_int(name, 4, 0, dst);
     }
 
-    encode_str(value, dst);
+    encode_str(value, policy, dst);
 }
 
-fn encode_not_indexed2(name: &[u8], value: &[u8], sensitive: bool, dst: &mut BytesMut) {
+fn encode_not_indexed2(
+    name: &[u8],
+    value: &[u8],
+    sensitive: bool,
+    policy: HuffmanPolicy,
+    dst: &mut BytesMut,
+) {
     if sensitive {
         dst.put_u8(0b10000);
     } else {
         dst.put_u8(0);
     }
 
-    encode_str(name, dst);
-    encode_str(value, dst);
+    encode_str(name, policy, dst);
+    encode_str(value, policy, dst);
 }
 
-fn encode_str(val: &[u8], dst: &mut BytesMut) {
-    if !val.is_empty() {
+fn encode_str(val: &[u8], policy: HuffmanPolicy, dst: &mut BytesMut) {
+    let use_huffman = match policy {
+        HuffmanPolicy::Always => true,
+        HuffmanPolicy::Never => false,
+        // Like nghttp2, only use Huffman coding when it saves space.
+        HuffmanPolicy::Shortest => huffman::encoded_len(val) < val.len(),
+    };
+
+    if !use_huffman {
+        encode_int(val.len(), 7, 0, dst);
+        dst.put_slice(val);
+    } else if !val.is_empty() {
         let idx = position(dst);
 
         // Push a placeholder byte for the length header
@@ -299,6 +386,7 @@ fn position(buf: &BytesMut) -> usize {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::hpack::BytesStr;
     use http::*;
 
     #[test]
@@ -687,6 +775,191 @@ mod test {
         assert_eq!([63, 225, 129, 148, 144, 7], &dst[..]);
     }
 
+    #[test]
+    fn test_indexing_policy_never_indexed() {
+        let mut encoder = Encoder::default();
+        encoder.set_indexing_policy(SharedIndexingPolicy::new(
+            |name: &str, value: &[u8]| match name {
+                "cookie" => Indexing::NeverIndexed,
+                ":path" if value.len() > 8 => Indexing::NeverIndexed,
+                ":path" => Indexing::Incremental,
+                _ => Indexing::Auto,
+            },
+        ));
+
+        let res = encode(
+            &mut encoder,
+            vec![
+                header("cookie", "a=b"),
+                path("/"),
+                path("/index.html"),
+                path("/a"),
+            ],
+        );
+
+        assert_eq!(
+            *res,
+            [
+                // cookie: a=b, never indexed, name from the static table
+                0x1f, 0x11, 0x83, 0x1c, 0x11, 0xff, //
+                // :path /, fully indexed in the static table
+                0x84, //
+                // :path /index.html, never indexed
+                0x15, 0x88, 0x60, 0xd5, 0x48, 0x5f, 0x2b, 0xce, 0x9a, 0x68, //
+                // :path /a, added to the dynamic table
+                0x44, 0x82, 0x60, 0x7f,
+            ]
+        );
+        assert_eq!(encoder.table.len(), 1);
+    }
+
+    #[test]
+    fn test_indexing_policy_repeated_name() {
+        let mut encoder = Encoder::default();
+        encoder.set_indexing_policy(SharedIndexingPolicy::new(
+            |name: &str, _: &[u8]| match name {
+                "cookie" => Indexing::NeverIndexed,
+                _ => Indexing::Auto,
+            },
+        ));
+
+        let value = HeaderValue::from_static("c=d");
+        let res = encode(
+            &mut encoder,
+            vec![header("cookie", "a=b"), Header::Field { name: None, value }],
+        );
+
+        assert_eq!(
+            *res,
+            [
+                0x1f, 0x11, 0x83, 0x1c, 0x11, 0xff, //
+                0x1f, 0x11, 0x83, 0x24, 0x12, 0x7f,
+            ]
+        );
+        assert_eq!(encoder.table.len(), 0);
+    }
+
+    #[test]
+    fn test_indexing_policy_incremental() {
+        let mut encoder = Encoder::default();
+        encoder.set_indexing_policy(SharedIndexingPolicy::new(|_: &str, _: &[u8]| {
+            Indexing::Incremental
+        }));
+
+        // `content-length` is normally sent without indexing.
+        let res = encode(&mut encoder, vec![header("content-length", "1234")]);
+        assert_eq!(*res, [0x5c, 0x83, 0x08, 0x99, 0x6b]);
+        assert_eq!(encoder.table.len(), 1);
+
+        let res = encode(&mut encoder, vec![header("content-length", "1234")]);
+        assert_eq!(*res, [0x80 | 62]);
+
+        // Fields larger than the whole table are not indexed.
+        let mut encoder = Encoder::new(48, 0);
+        encoder.set_indexing_policy(SharedIndexingPolicy::new(|_: &str, _: &[u8]| {
+            Indexing::Incremental
+        }));
+
+        let res = encode(&mut encoder, vec![header("x-large", "0123456789")]);
+        assert_eq!(res[0], 0);
+        assert_eq!(encoder.table.len(), 0);
+    }
+
+    #[test]
+    fn test_indexing_policy_without_indexing() {
+        let mut encoder = Encoder::default();
+        encoder.set_indexing_policy(SharedIndexingPolicy::new(|_: &str, _: &[u8]| {
+            Indexing::WithoutIndexing
+        }));
+
+        let res = encode(
+            &mut encoder,
+            vec![header("x-foo", "bar"), method("GET"), method("PATCH")],
+        );
+
+        assert_eq!(
+            *res,
+            [
+                // x-foo: bar, literal name
+                0x00, 0x84, 0xf2, 0xb4, 0xa7, 0x3f, 0x83, 0x8c, 0x76, 0x7f, //
+                // :method GET, fully indexed in the static table
+                0x82, //
+                // :method PATCH, name from the static table
+                0x02, 0x85, 0xd7, 0x0e, 0xfb, 0xd8, 0xff,
+            ]
+        );
+        assert_eq!(encoder.table.len(), 0);
+    }
+
+    #[test]
+    fn test_indexing_policy_sensitive_value() {
+        let mut encoder = Encoder::default();
+        encoder.set_indexing_policy(SharedIndexingPolicy::new(|_: &str, _: &[u8]| {
+            Indexing::Incremental
+        }));
+
+        let mut value = HeaderValue::from_static("secret");
+        value.set_sensitive(true);
+        let name = Some(http::header::AUTHORIZATION);
+
+        let res = encode(&mut encoder, vec![Header::Field { name, value }]);
+
+        assert_eq!(&res[..2], &[0x1f, 0x08]);
+        assert_eq!(encoder.table.len(), 0);
+    }
+
+    #[test]
+    fn test_huffman_policy_never() {
+        let mut encoder = Encoder::default();
+        encoder.set_huffman_policy(HuffmanPolicy::Never);
+
+        let res = encode(
+            &mut encoder,
+            vec![
+                header("x-foo", "bar"),
+                header("x-empty", ""),
+                method("PATCH"),
+            ],
+        );
+
+        assert_eq!(
+            *res,
+            [
+                &[0x40, 0x05][..],
+                b"x-foo",
+                &[0x03],
+                b"bar",
+                &[0x40, 0x07],
+                b"x-empty",
+                &[0x00],
+                &[0x42, 0x05],
+                b"PATCH",
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn test_huffman_policy_shortest() {
+        let mut encoder = Encoder::default();
+        encoder.set_huffman_policy(HuffmanPolicy::Shortest);
+
+        // `~` takes 13 bits once Huffman encoded, so the raw value is shorter.
+        let res = encode(&mut encoder, vec![header("x-foo", "~~~~")]);
+
+        assert_eq!(
+            *res,
+            [&[0x40, 0x84, 0xf2, 0xb4, 0xa7, 0x3f, 0x04][..], b"~~~~"].concat()
+        );
+
+        // The default always Huffman encodes.
+        let mut encoder = Encoder::default();
+        let res = encode(&mut encoder, vec![header("x-foo", "~~~~")]);
+
+        assert_eq!(&res[..7], &[0x40, 0x84, 0xf2, 0xb4, 0xa7, 0x3f, 0x87]);
+        assert_eq!(res.len(), 14);
+    }
+
     #[test]
     #[ignore]
     fn test_evicted_overflow() {
@@ -703,6 +976,10 @@ mod test {
         Header::Method(Method::from_bytes(s.as_bytes()).unwrap())
     }
 
+    fn path(s: &str) -> Header<Option<HeaderName>> {
+        Header::Path(BytesStr::from(s))
+    }
+
     fn header(name: &str, val: &str) -> Header<Option<HeaderName>> {
         let name = HeaderName::from_bytes(name.as_bytes()).unwrap();
         let value = HeaderValue::from_bytes(val.as_bytes()).unwrap();
@@ -248,17 +248,21 @@ impl<'a> Name<'a> {
         }
     }
 
-    pub fn as_slice(&self) -> &[u8] {
+    pub fn as_str(&self) -> &str {
         match *self {
-            Name::Field(ref name) => name.as_ref(),
-            Name::Authority => b":authority",
-            Name::Method => b":method",
-            Name::Scheme => b":scheme",
-            Name::Path => b":path",
-            Name::Protocol => b":protocol",
-            Name::Status => b":status",
+            Name::Field(name) => name.as_str(),
+            Name::Authority => ":authority",
+            Name::Method => ":method",
+            Name::Scheme => ":scheme",
+            Name::Path => ":path",
+            Name::Protocol => ":protocol",
+            Name::Status => ":status",
         }
     }
+
+    pub fn as_slice(&self) -> &[u8] {
+        self.as_str().as_bytes()
+    }
 }
 
 // ===== impl BytesStr =====
@@ -40,6 +40,12 @@ pub fn decode(src: &[u8], buf: &mut BytesMut) -> Result<BytesMut, DecoderError>
     Ok(buf.split())
 }
 
+/// Returns the number of bytes `src` takes once Huffman encoded.
+pub fn encoded_len(src: &[u8]) -> usize {
+    let bits: usize = src.iter().map(|&b| ENCODE_TABLE[b as usize].0).sum();
+    (bits + 7) / 8
+}
+
 pub fn encode(src: &[u8], dst: &mut BytesMut) {
     let mut bits: u64 = 0;
     let mut bits_left = 40;
@@ -175,6 +181,7 @@ mod test {
             let mut dst = BytesMut::with_capacity(s.len());
 
             encode(s.as_bytes(), &mut dst);
+            assert_eq!(encoded_len(s.as_bytes()), dst.len());
 
             let decoded = decode(&dst).unwrap();
 
@@ -190,6 +197,7 @@ mod test {
             let mut dst = BytesMut::with_capacity(s.len());
 
             encode(s, &mut dst);
+            assert_eq!(encoded_len(s), dst.len());
 
             let decoded = decode(&dst).unwrap();
 
@@ -2,6 +2,7 @@ mod decoder;
 mod encoder;
 pub(crate) mod header;
 pub(crate) mod huffman;
+mod policy;
 mod table;
 
 #[cfg(test)]
@@ -10,3 +11,5 @@ mod test;
 pub use self::decoder::{Decoder, DecoderError, NeedMore};
 pub use self::encoder::Encoder;
 pub use self::header::{BytesStr, Header};
+pub(crate) use self::policy::SharedIndexingPolicy;
+pub use self::policy::{HuffmanPolicy, Indexing, IndexingPolicy};
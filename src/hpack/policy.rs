@@ -0,0 +1,111 @@
+use std::fmt;
+use std::sync::Arc;
+
+/// The HPACK representation used for a header field.
+///
+/// See [RFC 7541 section 6.2] for the literal representations.
+///
+/// [RFC 7541 section 6.2]: https://datatracker.ietf.org/doc/html/rfc7541#section-6.2
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Indexing {
+    /// Let the encoder decide, as it does when no policy is configured.
+    ///
+    /// Sensitive values are never indexed, a few fields such as
+    /// `content-length` are sent without indexing and everything else is
+    /// added to the dynamic table unless it is too large.
+    #[default]
+    Auto,
+
+    /// Literal with incremental indexing: the field is added to the dynamic
+    /// table.
+    ///
+    /// Fields that already fully match a table entry are sent as an index,
+    /// and fields larger than the whole table are sent without indexing.
+    Incremental,
+
+    /// Literal without indexing: the field is not added to the dynamic table.
+    WithoutIndexing,
+
+    /// Literal never indexed: the field is not added to the dynamic table and
+    /// intermediaries must not index it either.
+    NeverIndexed,
+}
+
+/// Picks the HPACK representation of each header field that is sent.
+///
+/// The policy is consulted with the field name, pseudo-headers included (for
+/// example `":path"`), and the field value. It is implemented for closures
+/// with a matching signature.
+///
+/// A value marked as [sensitive] is always sent as never indexed, whatever
+/// the policy returns.
+///
+/// # Examples
+///
+/// ```
+/// use http2::ext::Indexing;
+///
+/// // Never index cookies, nor paths longer than 32 bytes.
+/// let policy = |name: &str, value: &[u8]| match name {
+///     "cookie" => Indexing::NeverIndexed,
+///     ":path" if value.len() > 32 => Indexing::NeverIndexed,
+///     _ => Indexing::Auto,
+/// };
+/// # let _ = http2::client::Builder::new().indexing_policy(policy);
+/// ```
+///
+/// [sensitive]: http::header::HeaderValue::set_sensitive
+pub trait IndexingPolicy: Send + Sync + 'static {
+    /// Returns the representation to use for the field `name: value`.
+    fn indexing(&self, name: &str, value: &[u8]) -> Indexing;
+}
+
+impl<F> IndexingPolicy for F
+where
+    F: Fn(&str, &[u8]) -> Indexing + Send + Sync + 'static,
+{
+    fn indexing(&self, name: &str, value: &[u8]) -> Indexing {
+        self(name, value)
+    }
+}
+
+/// A shared, type-erased `IndexingPolicy`, as stored by the builders and the
+/// encoder.
+#[derive(Clone)]
+pub(crate) struct SharedIndexingPolicy(Arc<dyn IndexingPolicy>);
+
+impl SharedIndexingPolicy {
+    pub(crate) fn new<P: IndexingPolicy>(policy: P) -> Self {
+        SharedIndexingPolicy(Arc::new(policy))
+    }
+
+    pub(crate) fn indexing(&self, name: &str, value: &[u8]) -> Indexing {
+        self.0.indexing(name, value)
+    }
+}
+
+impl fmt::Debug for SharedIndexingPolicy {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("IndexingPolicy").finish_non_exhaustive()
+    }
+}
+
+/// When string literals are Huffman encoded.
+///
+/// See [RFC 7541 section 5.2].
+///
+/// [RFC 7541 section 5.2]: https://datatracker.ietf.org/doc/html/rfc7541#section-5.2
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum HuffmanPolicy {
+    /// Always Huffman encode literals, even when the result is longer than
+    /// the raw bytes.
+    #[default]
+    Always,
+
+    /// Never Huffman encode literals.
+    Never,
+
+    /// Huffman encode a literal only when that makes it shorter, as nghttp2
+    /// does.
+    Shortest,
+}
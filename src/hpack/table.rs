@@ -157,6 +157,34 @@ impl Table {
         self.index_dynamic(header, statik)
     }
 
+    /// Indexes the header, adding it to the table whenever it is allowed to
+    /// fit, regardless of the heuristics applied by `index`.
+    pub fn index_incremental(&mut self, header: Header) -> Index {
+        let statik = index_static(&header);
+
+        if let Some((n, true)) = statik {
+            return Index::Indexed(n, header);
+        }
+
+        // An entry larger than the table would only empty it.
+        if header.len() > self.max_size {
+            return Index::new(statik, header);
+        }
+
+        self.index_dynamic(header, statik)
+    }
+
+    /// Indexes the header against the static table only, without touching
+    /// the dynamic table. A never indexed header is always sent as a literal,
+    /// even if it fully matches a static entry.
+    pub fn index_static(&self, header: Header, never_indexed: bool) -> Index {
+        match index_static(&header) {
+            Some((n, true)) if !never_indexed => Index::Indexed(n, header),
+            Some((n, _)) => Index::Name(n, header),
+            None => Index::NotIndexed(header),
+        }
+    }
+
     fn index_dynamic(&mut self, header: Header, statik: Option<(usize, bool)>) -> Index {
         debug_assert!(self.assert_valid_state("one"));
 
@@ -621,7 +649,6 @@ impl Table {
     }
 }
 
-#[cfg(test)]
 impl Table {
     /// Returns the number of headers in the table
     pub fn len(&self) -> usize {
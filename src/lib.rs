@@ -110,6 +110,7 @@ macro_rules! ready {
 #[cfg_attr(feature = "unstable", allow(missing_docs))]
 mod codec;
 mod error;
+mod fingerprint;
 mod hpack;
 
 #[cfg(not(feature = "unstable"))]
@@ -128,15 +129,20 @@ pub mod frame;
 
 pub mod client;
 pub mod ext;
+mod observer;
 pub mod server;
 mod share;
+mod stats;
 
 #[cfg(fuzzing)]
 #[cfg_attr(feature = "unstable", allow(missing_docs))]
 pub mod fuzz_bridge;
 
 pub use crate::error::{Error, Reason};
-pub use crate::share::{FlowControl, Ping, PingPong, Pong, RecvStream, SendStream, StreamId};
+pub use crate::fingerprint::{Fingerprint, InvalidFingerprint};
+pub use crate::share::{
+    ExtensionFrames, FlowControl, Ping, PingPong, Pong, RecvStream, SendStream, StreamId,
+};
 
 #[cfg(feature = "unstable")]
 pub use codec::{Codec, SendError, UserError};
@@ -0,0 +1,196 @@
+use crate::frame::{Head, Kind, HEADER_LEN};
+use crate::StreamId;
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// The PADDED flag of HEADERS and PUSH_PROMISE frames.
+const PADDED: u8 = 0x8;
+
+/// The PRIORITY flag of HEADERS frames.
+const PRIORITY: u8 = 0x20;
+
+/// Observes the frames read from and written to a connection.
+///
+/// An observer is installed with the `frame_observer` method of
+/// [`client::Builder`] or [`server::Builder`]. It is called with every frame
+/// read from the connection once the frame is parsed, before the connection
+/// acts on it, and with every frame handed to the codec to be written. Frames
+/// that fail to parse are not observed.
+///
+/// Both methods do nothing by default. They are called from the task driving
+/// the connection and should return quickly.
+///
+/// # Examples
+///
+/// ```
+/// use http2::ext::{FrameKind, FrameObserver, ObservedFrame};
+///
+/// struct LogHeaders;
+///
+/// impl FrameObserver for LogHeaders {
+///     fn frame_received(&self, frame: &ObservedFrame<'_>) {
+///         if frame.kind() == FrameKind::Headers {
+///             println!("{:?}: {:?}", frame.stream_id(), frame.header_block());
+///         }
+///     }
+/// }
+/// # let _ = http2::client::Builder::new().frame_observer(LogHeaders);
+/// ```
+///
+/// [`client::Builder`]: crate::client::Builder
+/// [`server::Builder`]: crate::server::Builder
+pub trait FrameObserver: Send + Sync + 'static {
+    /// Called with a frame read from the connection.
+    fn frame_received(&self, frame: &ObservedFrame<'_>) {
+        let _ = frame;
+    }
+
+    /// Called with a frame handed to the codec to be written.
+    fn frame_sent(&self, frame: &ObservedFrame<'_>) {
+        let _ = frame;
+    }
+}
+
+impl<O: FrameObserver + ?Sized> FrameObserver for Arc<O> {
+    fn frame_received(&self, frame: &ObservedFrame<'_>) {
+        (**self).frame_received(frame)
+    }
+
+    fn frame_sent(&self, frame: &ObservedFrame<'_>) {
+        (**self).frame_sent(frame)
+    }
+}
+
+/// A read-only view of a frame, as sent or received on the wire.
+#[derive(Clone, Copy)]
+pub struct ObservedFrame<'a> {
+    head: Head,
+    payload_len: usize,
+    payload: Option<&'a [u8]>,
+    timestamp: Instant,
+}
+
+/// A shared, type-erased `FrameObserver`, as stored by the builders and the
+/// codec.
+#[derive(Clone)]
+pub(crate) struct SharedFrameObserver(Arc<dyn FrameObserver>);
+
+// ===== impl ObservedFrame =====
+
+impl<'a> ObservedFrame<'a> {
+    /// Creates a view of the frame at the start of `buf`, which holds the
+    /// frame header and, if `with_payload` is set, the whole payload.
+    pub(crate) fn new(buf: &'a [u8], with_payload: bool, timestamp: Instant) -> Self {
+        let payload_len = (buf[0] as usize) << 16 | (buf[1] as usize) << 8 | buf[2] as usize;
+        let payload = if with_payload {
+            buf.get(HEADER_LEN..HEADER_LEN + payload_len)
+        } else {
+            None
+        };
+
+        ObservedFrame {
+            head: Head::parse(buf),
+            payload_len,
+            payload,
+            timestamp,
+        }
+    }
+
+    /// Returns the type of the frame.
+    pub fn kind(&self) -> Kind {
+        self.head.kind()
+    }
+
+    /// Returns the stream the frame was sent on, zero for the frames about
+    /// the whole connection.
+    pub fn stream_id(&self) -> StreamId {
+        StreamId::from_internal(self.head.stream_id())
+    }
+
+    /// Returns the flags of the frame header.
+    pub fn flags(&self) -> u8 {
+        self.head.flag()
+    }
+
+    /// Returns the length of the payload, as given by the frame header.
+    pub fn payload_len(&self) -> usize {
+        self.payload_len
+    }
+
+    /// Returns the payload of the frame, padding included.
+    ///
+    /// Returns `None` for the DATA frames that are sent, whose payload is
+    /// written without being copied.
+    pub fn payload(&self) -> Option<&'a [u8]> {
+        self.payload
+    }
+
+    /// Returns the HPACK header block fragment carried by a HEADERS,
+    /// PUSH_PROMISE or CONTINUATION frame, without the padding and the
+    /// priority or promised stream fields.
+    ///
+    /// A header block split over CONTINUATION frames is observed one fragment
+    /// at a time.
+    pub fn header_block(&self) -> Option<&'a [u8]> {
+        let payload = self.payload?;
+        let flags = self.head.flag();
+
+        let (padded, skip) = match self.head.kind() {
+            Kind::Headers if flags & PRIORITY == PRIORITY => (flags & PADDED == PADDED, 5),
+            Kind::Headers => (flags & PADDED == PADDED, 0),
+            Kind::PushPromise => (flags & PADDED == PADDED, 4),
+            Kind::Continuation => (false, 0),
+            _ => return None,
+        };
+
+        let (payload, pad_len) = if padded {
+            (payload.get(1..)?, *payload.first()? as usize)
+        } else {
+            (payload, 0)
+        };
+
+        payload.get(skip..payload.len().checked_sub(pad_len)?)
+    }
+
+    /// Returns when the frame was read from the connection, or handed to the
+    /// codec to be written.
+    pub fn timestamp(&self) -> Instant {
+        self.timestamp
+    }
+}
+
+impl fmt::Debug for ObservedFrame<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("ObservedFrame")
+            .field("kind", &self.head.kind())
+            .field("stream_id", &self.head.stream_id())
+            .field("flags", &self.head.flag())
+            .field("payload_len", &self.payload_len)
+            .field("timestamp", &self.timestamp)
+            .finish()
+    }
+}
+
+// ===== impl SharedFrameObserver =====
+
+impl SharedFrameObserver {
+    pub(crate) fn new<O: FrameObserver>(observer: O) -> Self {
+        SharedFrameObserver(Arc::new(observer))
+    }
+
+    pub(crate) fn frame_received(&self, frame: &ObservedFrame<'_>) {
+        self.0.frame_received(frame)
+    }
+
+    pub(crate) fn frame_sent(&self, frame: &ObservedFrame<'_>) {
+        self.0.frame_sent(frame)
+    }
+}
+
+impl fmt::Debug for SharedFrameObserver {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("FrameObserver").finish_non_exhaustive()
+    }
+}
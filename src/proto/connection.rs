@@ -1,5 +1,9 @@
 use crate::codec::UserError;
-use crate::frame::{Priorities, PseudoOrder, Reason, StreamDependency, StreamId};
+use crate::fingerprint::Fingerprint;
+use crate::frame::{
+    HeaderOrder, PaddingPolicy, Priorities, PseudoOrder, Reason, StreamDependency, StreamId,
+};
+use crate::stats::ConnectionStats;
 use crate::{client, server, tracing};
 
 use crate::frame::DEFAULT_INITIAL_WINDOW_SIZE;
@@ -48,6 +52,12 @@ where
     /// Ping/pong handler
     ping_pong: PingPong,
 
+    /// Extension frame handler
+    extensions: Extensions,
+
+    /// Records the fingerprint of the remote peer
+    fingerprint: Recorder,
+
     /// Connection settings
     settings: Settings,
 
@@ -72,6 +82,10 @@ struct DynConnection<'a, B: Buf = Bytes> {
     error: &'a mut Option<frame::GoAway>,
 
     ping_pong: &'a mut PingPong,
+
+    extensions: &'a mut Extensions,
+
+    fingerprint: &'a mut Recorder,
 }
 
 #[derive(Debug, Clone)]
@@ -85,8 +99,16 @@ pub(crate) struct Config {
     pub local_error_reset_streams_max: Option<usize>,
     pub settings: frame::Settings,
     pub headers_pseudo_order: Option<PseudoOrder>,
+    pub headers_order: Option<HeaderOrder>,
     pub headers_stream_dependency: Option<StreamDependency>,
     pub priorities: Option<Priorities>,
+    pub padding: PaddingPolicy,
+    pub rfc7540_priorities: bool,
+    pub scheduling_strategy: SchedulingStrategy,
+    pub adaptive_window: Option<WindowSize>,
+    pub keep_alive_interval: Option<Duration>,
+    pub keep_alive_timeout: Duration,
+    pub keep_alive_while_idle: bool,
 }
 
 #[derive(Debug)]
@@ -129,17 +151,38 @@ where
                 local_max_error_reset_streams: config.local_error_reset_streams_max,
                 headers_stream_dependency: config.headers_stream_dependency,
                 headers_pseudo_order: config.headers_pseudo_order.clone(),
+                headers_order: config.headers_order.clone(),
                 priorities: config.priorities.clone(),
+                padding: config.padding,
+                rfc7540_priorities: config.rfc7540_priorities,
+                scheduling_strategy: config.scheduling_strategy,
             }
         }
         let streams = Streams::new(streams_config(&config));
+        let mut ping_pong = PingPong::new();
+        if let Some(max) = config.adaptive_window {
+            let window = config
+                .settings
+                .initial_window_size()
+                .unwrap_or(DEFAULT_INITIAL_WINDOW_SIZE);
+            ping_pong.enable_bdp(window, max);
+        }
+        if let Some(interval) = config.keep_alive_interval {
+            ping_pong.enable_keep_alive(
+                interval,
+                config.keep_alive_timeout,
+                config.keep_alive_while_idle,
+            );
+        }
         Connection {
             codec,
             inner: ConnectionInner {
                 state: State::Open,
                 error: None,
                 go_away: GoAway::new(),
-                ping_pong: PingPong::new(),
+                ping_pong,
+                extensions: Extensions::new(),
+                fingerprint: Recorder::default(),
                 settings: Settings::new(config.settings),
                 streams,
                 #[cfg(feature = "tracing")]
@@ -156,6 +199,14 @@ where
         debug_assert!(_res.is_ok());
     }
 
+    /// Grows the connection window by a WINDOW_UPDATE increment that was
+    /// sent outside of flow control, as part of the connection preface.
+    pub(crate) fn inc_connection_window(&mut self, size: WindowSize) {
+        let _res = self.inner.streams.inc_connection_window(size);
+        // TODO: proper error handling
+        debug_assert!(_res.is_ok());
+    }
+
     /// Send a new SETTINGS frame with an updated initial window size.
     pub(crate) fn set_initial_window_size(&mut self, size: WindowSize) -> Result<(), UserError> {
         let mut settings = frame::Settings::default();
@@ -170,6 +221,12 @@ where
         self.inner.settings.send_settings(settings)
     }
 
+    /// Returns the fingerprint of the remote peer, as far as it has been
+    /// received.
+    pub(crate) fn peer_fingerprint(&self) -> &Fingerprint {
+        self.inner.fingerprint.fingerprint()
+    }
+
     /// Returns the maximum number of concurrent streams that may be initiated
     /// by this peer.
     pub(crate) fn max_send_streams(&self) -> usize {
@@ -192,12 +249,20 @@ where
     /// Returns `Error` as this may raise errors that are caused by delayed
     /// processing of received frames.
     fn poll_ready(&mut self, cx: &mut Context) -> Poll<Result<(), Error>> {
+        // Cloned for the same reason as in `poll`: `set_bdp_window` borrows
+        // `self` mutably.
         #[cfg(feature = "tracing")]
-        let _e = self.inner.span.enter();
+        let _e = self.inner.span.clone().entered();
         let _span = tracing::trace_span!("poll_ready");
+        if let Some(window) = self.inner.ping_pong.take_bdp_window() {
+            self.set_bdp_window(window);
+        }
+        let has_streams = self.inner.streams.has_streams();
+        self.inner.ping_pong.poll_keep_alive(cx, has_streams)?;
         // The order of these calls don't really matter too much
         ready!(self.inner.ping_pong.send_pending_pong(cx, &mut self.codec))?;
         ready!(self.inner.ping_pong.send_pending_ping(cx, &mut self.codec))?;
+        ready!(self.inner.extensions.send_pending(cx, &mut self.codec))?;
         ready!(self
             .inner
             .settings
@@ -207,6 +272,24 @@ where
         Poll::Ready(Ok(()))
     }
 
+    /// Grows the receive windows to the bandwidth-delay product estimate.
+    fn set_bdp_window(&mut self, window: WindowSize) {
+        let mut settings = frame::Settings::default();
+        settings.set_initial_window_size(Some(window));
+        if self.inner.settings.send_settings(settings).is_err() {
+            // Retry once the peer acknowledges the SETTINGS in flight.
+            self.inner.ping_pong.defer_bdp_window(window);
+            return;
+        }
+
+        let _res = self
+            .inner
+            .streams
+            .grow_target_connection_window_size(window);
+        // TODO: proper error handling
+        debug_assert!(_res.is_ok());
+    }
+
     /// Send any pending GOAWAY frames.
     ///
     /// This will return `Some(reason)` if the connection should be closed
@@ -266,6 +349,46 @@ where
         self.inner.ping_pong.take_user_pings()
     }
 
+    pub(crate) fn smoothed_rtt(&self) -> Option<Duration> {
+        self.inner.ping_pong.smoothed_rtt()
+    }
+
+    pub(crate) fn min_rtt(&self) -> Option<Duration> {
+        self.inner.ping_pong.min_rtt()
+    }
+
+    pub(crate) fn take_user_extensions(&mut self) -> Option<UserExtensions> {
+        self.inner.extensions.take_user_frames()
+    }
+
+    /// Queues a connection level extension frame to be sent.
+    pub(crate) fn send_extension_frame(&mut self, frame: frame::ExtensionFrame) {
+        self.inner.extensions.send(frame)
+    }
+
+    /// Queues an ORIGIN frame to be sent.
+    pub(crate) fn send_origin(&mut self, frame: frame::Origin) {
+        self.inner.extensions.send_origin(frame)
+    }
+
+    /// Queues a connection level ALTSVC frame to be sent.
+    pub(crate) fn send_alt_svc(&mut self, frame: frame::AltSvc) {
+        self.inner.extensions.send_alt_svc(frame)
+    }
+
+    /// Returns a snapshot of the connection statistics.
+    pub(crate) fn stats(&self) -> ConnectionStats {
+        let mut stats = ConnectionStats::default();
+        self.codec.fill_stats(&mut stats);
+        self.inner.streams.fill_stats(&mut stats);
+        stats
+    }
+
+    /// Returns the connection level ALTSVC frames received so far.
+    pub(crate) fn alt_svc(&self) -> Vec<frame::AltSvc> {
+        self.inner.extensions.alt_svc().to_vec()
+    }
+
     /// Advances the internal state of the connection.
     pub fn poll(&mut self, cx: &mut Context) -> Poll<Result<(), Error>> {
         // XXX(eliza): cloning the span is unfortunately necessary here in
@@ -388,6 +511,8 @@ where
             streams,
             error,
             ping_pong,
+            extensions,
+            fingerprint,
             ..
         } = self;
         let streams = streams.as_dyn();
@@ -397,6 +522,8 @@ where
             streams,
             error,
             ping_pong,
+            extensions,
+            fingerprint,
         }
     }
 }
@@ -460,6 +587,12 @@ where
                 }
                 Ok(())
             }
+            // The peer stopped acknowledging PINGs, it is likely gone: the
+            // streams fail and the connection closes without a GOAWAY.
+            Err(Error::KeepAliveTimedOut) => {
+                self.streams.handle_error(Error::KeepAliveTimedOut);
+                Err(Error::KeepAliveTimedOut)
+            }
             // Attempting to read a frame resulted in an I/O error. All
             // active streams must be reset.
             //
@@ -516,13 +649,18 @@ where
 
     fn recv_frame(&mut self, frame: Option<Frame>) -> Result<ReceivedFrame, Error> {
         use crate::frame::Frame::*;
+        if frame.is_some() {
+            self.ping_pong.record_read();
+        }
         match frame {
             Some(Headers(frame)) => {
                 tracing::trace!(?frame, "recv HEADERS");
+                self.fingerprint.recv_headers(&frame);
                 self.streams.recv_headers(frame)?;
             }
             Some(Data(frame)) => {
                 tracing::trace!(?frame, "recv DATA");
+                self.ping_pong.recv_data(frame.payload().len());
                 self.streams.recv_data(frame)?;
             }
             Some(Reset(frame)) => {
@@ -535,6 +673,7 @@ where
             }
             Some(Settings(frame)) => {
                 tracing::trace!(?frame, "recv SETTINGS");
+                self.fingerprint.recv_settings(&frame);
                 return Ok(ReceivedFrame::Settings(frame));
             }
             Some(GoAway(frame)) => {
@@ -561,11 +700,41 @@ where
             }
             Some(WindowUpdate(frame)) => {
                 tracing::trace!(?frame, "recv WINDOW_UPDATE");
+                self.fingerprint.recv_window_update(&frame);
                 self.streams.recv_window_update(frame)?;
             }
-            Some(Priority(_frame)) => {
-                tracing::trace!(?_frame, "recv PRIORITY");
-                // TODO: handle
+            Some(Priority(frame)) => {
+                tracing::trace!(?frame, "recv PRIORITY");
+                self.fingerprint.recv_priority(&frame);
+                self.streams.recv_priority(frame);
+            }
+            Some(Origin(frame)) => {
+                tracing::trace!(?frame, "recv ORIGIN");
+                // Servers ignore ORIGIN frames (RFC 8336 section 2.1).
+                if !self.streams.is_server() {
+                    self.streams.recv_origin(frame);
+                }
+            }
+            Some(AltSvc(frame)) => {
+                tracing::trace!(?frame, "recv ALTSVC");
+                // Servers ignore ALTSVC frames, and so do clients when the
+                // frame lacks an origin on stream zero or has one on another
+                // stream (RFC 7838 section 4).
+                if self.streams.is_server() || !frame.is_valid() {
+                    tracing::trace!("ignoring ALTSVC");
+                } else if frame.stream_id().is_zero() {
+                    self.extensions.recv_alt_svc(frame);
+                } else {
+                    self.streams.recv_alt_svc(frame);
+                }
+            }
+            Some(PriorityUpdate(frame)) => {
+                tracing::trace!(?frame, "recv PRIORITY_UPDATE");
+                self.streams.recv_priority_update(frame)?;
+            }
+            Some(Extension(frame)) => {
+                tracing::trace!(?frame, "recv extension frame");
+                self.extensions.recv(frame);
             }
             None => {
                 tracing::trace!("codec closed");
@@ -577,6 +746,7 @@ where
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 enum ReceivedFrame {
     Settings(frame::Settings),
     Continue,
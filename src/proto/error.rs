@@ -11,6 +11,8 @@ pub enum Error {
     Reset(StreamId, Reason, Initiator),
     GoAway(Bytes, Reason, Initiator),
     Io(io::ErrorKind, Option<String>),
+    /// No PING acknowledgement was received in time.
+    KeepAliveTimedOut,
 }
 
 pub struct GoAway {
@@ -18,10 +20,14 @@ pub struct GoAway {
     pub reason: Reason,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The party that caused an error, such as a stream reset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Initiator {
+    /// The user of the library, for instance by calling `send_reset`.
     User,
+    /// The library, for instance because the peer broke the protocol.
     Library,
+    /// The peer.
     Remote,
 }
 
@@ -29,7 +35,7 @@ impl Error {
     pub(crate) fn is_local(&self) -> bool {
         match *self {
             Self::Reset(_, _, initiator) | Self::GoAway(_, _, initiator) => initiator.is_local(),
-            Self::Io(..) => true,
+            Self::Io(..) | Self::KeepAliveTimedOut => true,
         }
     }
 
@@ -77,6 +83,7 @@ impl fmt::Display for Error {
             Self::Reset(_, reason, _) | Self::GoAway(_, reason, _) => reason.fmt(fmt),
             Self::Io(_, Some(ref inner)) => inner.fmt(fmt),
             Self::Io(kind, None) => io::Error::from(kind).fmt(fmt),
+            Self::KeepAliveTimedOut => fmt.write_str("keep-alive timed out"),
         }
     }
 }
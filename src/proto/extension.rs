@@ -0,0 +1,200 @@
+use crate::codec::Codec;
+use crate::frame::{self, ExtensionFrame};
+use crate::proto::streams::Mutex;
+use crate::tracing;
+
+use atomic_waker::AtomicWaker;
+use bytes::Buf;
+use std::collections::VecDeque;
+use std::io;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::AsyncWrite;
+
+/// The maximum number of received extension frames buffered for the user.
+///
+/// Frames received while the buffer is full are dropped.
+const MAX_PENDING_RECV: usize = 256;
+
+/// The maximum number of origins for which alternative services are kept.
+const MAX_ALT_SVC_ORIGINS: usize = 256;
+
+/// Sends and receives extension frames on behalf of the user.
+#[derive(Debug)]
+pub(crate) struct Extensions {
+    /// Connection level frames waiting to be written.
+    pending_send: VecDeque<Pending>,
+    /// Where received frames go, once the user asked for them.
+    user_frames: Option<UserExtensionsRx>,
+    /// The last connection level ALTSVC frame received for each origin.
+    alt_svc: Vec<frame::AltSvc>,
+}
+
+/// A connection level extension frame waiting to be written.
+#[derive(Debug)]
+enum Pending {
+    Extension(ExtensionFrame),
+    Origin(frame::Origin),
+    AltSvc(frame::AltSvc),
+}
+
+/// The user's end of the received extension frames.
+#[derive(Debug)]
+pub(crate) struct UserExtensions(Arc<UserExtensionsInner>);
+
+#[derive(Debug)]
+struct UserExtensionsRx(Arc<UserExtensionsInner>);
+
+#[derive(Debug)]
+struct UserExtensionsInner {
+    queue: Mutex<RecvQueue>,
+    /// Task to wake up `share::ExtensionFrames::poll_frame`.
+    recv_task: AtomicWaker,
+}
+
+#[derive(Debug, Default)]
+struct RecvQueue {
+    frames: VecDeque<ExtensionFrame>,
+    closed: bool,
+}
+
+// ===== impl Extensions =====
+
+impl Extensions {
+    pub(crate) fn new() -> Self {
+        Extensions {
+            pending_send: VecDeque::new(),
+            user_frames: None,
+            alt_svc: Vec::new(),
+        }
+    }
+
+    /// Can only be called once. If called a second time, returns `None`.
+    pub(crate) fn take_user_frames(&mut self) -> Option<UserExtensions> {
+        if self.user_frames.is_some() {
+            return None;
+        }
+
+        let inner = Arc::new(UserExtensionsInner {
+            queue: Mutex::new(RecvQueue::default()),
+            recv_task: AtomicWaker::new(),
+        });
+        self.user_frames = Some(UserExtensionsRx(inner.clone()));
+        Some(UserExtensions(inner))
+    }
+
+    /// Queues a connection level frame to be written.
+    pub(crate) fn send(&mut self, frame: ExtensionFrame) {
+        self.pending_send.push_back(Pending::Extension(frame));
+    }
+
+    /// Queues an ORIGIN frame to be written.
+    pub(crate) fn send_origin(&mut self, frame: frame::Origin) {
+        self.pending_send.push_back(Pending::Origin(frame));
+    }
+
+    /// Queues a connection level ALTSVC frame to be written.
+    pub(crate) fn send_alt_svc(&mut self, frame: frame::AltSvc) {
+        self.pending_send.push_back(Pending::AltSvc(frame));
+    }
+
+    /// Send any pending frames.
+    pub(crate) fn send_pending<T, B>(
+        &mut self,
+        cx: &mut Context,
+        dst: &mut Codec<T, B>,
+    ) -> Poll<io::Result<()>>
+    where
+        T: AsyncWrite + Unpin,
+        B: Buf,
+    {
+        while !self.pending_send.is_empty() {
+            if !dst.poll_ready(cx)?.is_ready() {
+                return Poll::Pending;
+            }
+
+            let frame = match self.pending_send.pop_front().unwrap() {
+                Pending::Extension(frame) => frame.into(),
+                Pending::Origin(frame) => frame.into(),
+                Pending::AltSvc(frame) => frame.into(),
+            };
+            dst.buffer(frame).expect("invalid extension frame");
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    /// Hands a received frame to the user, or drops it if nobody is
+    /// interested.
+    pub(crate) fn recv(&mut self, frame: ExtensionFrame) {
+        let users = match self.user_frames {
+            // Only deliver frames while the user still holds their end.
+            Some(ref users) if Arc::strong_count(&users.0) > 1 => users,
+            _ => {
+                tracing::trace!("ignoring extension frame; frame={:?}", frame);
+                return;
+            }
+        };
+
+        let mut queue = users.0.queue.lock();
+        if queue.frames.len() >= MAX_PENDING_RECV {
+            tracing::debug!(
+                "dropping extension frame, too many pending; frame={:?}",
+                frame
+            );
+            return;
+        }
+
+        queue.frames.push_back(frame);
+        drop(queue);
+        users.0.recv_task.wake();
+    }
+
+    /// Records a connection level ALTSVC frame, replacing the alternative
+    /// services previously advertised for the same origin.
+    pub(crate) fn recv_alt_svc(&mut self, frame: frame::AltSvc) {
+        if let Some(prev) = self
+            .alt_svc
+            .iter_mut()
+            .find(|prev| prev.origin() == frame.origin())
+        {
+            *prev = frame;
+        } else if self.alt_svc.len() < MAX_ALT_SVC_ORIGINS {
+            self.alt_svc.push(frame);
+        } else {
+            tracing::debug!("too many ALTSVC origins; ignoring frame={:?}", frame);
+        }
+    }
+
+    pub(crate) fn alt_svc(&self) -> &[frame::AltSvc] {
+        &self.alt_svc
+    }
+}
+
+// ===== impl UserExtensions =====
+
+impl UserExtensions {
+    pub(crate) fn poll_frame(&self, cx: &mut Context) -> Poll<Option<ExtensionFrame>> {
+        // Must register before checking the queue, in case a frame were to
+        // be pushed in between.
+        self.0.recv_task.register(cx.waker());
+
+        let mut queue = self.0.queue.lock();
+        if let Some(frame) = queue.frames.pop_front() {
+            Poll::Ready(Some(frame))
+        } else if queue.closed {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+// ===== impl UserExtensionsRx =====
+
+impl Drop for UserExtensionsRx {
+    fn drop(&mut self) {
+        self.0.queue.lock().closed = true;
+        self.0.recv_task.wake();
+    }
+}
@@ -0,0 +1,62 @@
+use crate::fingerprint::Fingerprint;
+use crate::frame;
+
+/// Records the peer's `Fingerprint` from the frames it sends at the start of
+/// the connection.
+#[derive(Debug, Default)]
+pub(super) struct Recorder {
+    fingerprint: Fingerprint,
+
+    /// Set once the first (non ACK) SETTINGS frame has been recorded.
+    recv_settings: bool,
+
+    /// Set once the first HEADERS frame has been recorded. PRIORITY frames
+    /// received after it are not part of the preface.
+    recv_headers: bool,
+}
+
+impl Recorder {
+    pub(super) fn fingerprint(&self) -> &Fingerprint {
+        &self.fingerprint
+    }
+
+    pub(super) fn recv_settings(&mut self, frame: &frame::Settings) {
+        if self.recv_settings || frame.is_ack() {
+            return;
+        }
+
+        self.recv_settings = true;
+        for setting in frame.received() {
+            self.fingerprint
+                .push_setting(setting.id().into(), setting.value());
+        }
+    }
+
+    pub(super) fn recv_window_update(&mut self, frame: &frame::WindowUpdate) {
+        if frame.stream_id().is_zero() && self.fingerprint.window_update().is_none() {
+            self.fingerprint.set_window_update(frame.size_increment());
+        }
+    }
+
+    pub(super) fn recv_priority(&mut self, frame: &frame::Priority) {
+        if !self.recv_headers {
+            self.fingerprint.push_priority(frame.clone());
+        }
+    }
+
+    pub(super) fn recv_headers(&mut self, frame: &frame::Headers) {
+        if self.recv_headers {
+            return;
+        }
+
+        self.recv_headers = true;
+        let pseudo = frame.pseudo();
+        self.fingerprint.set_pseudo_order(
+            pseudo
+                .order()
+                .into_iter()
+                .copied()
+                .filter(|&id| pseudo.contains(id)),
+        );
+    }
+}
@@ -1,5 +1,7 @@
 mod connection;
 mod error;
+mod extension;
+mod fingerprint;
 mod go_away;
 mod peer;
 mod ping_pong;
@@ -8,13 +10,17 @@ mod streams;
 
 pub(crate) use self::connection::{Config, Connection};
 pub use self::error::{Error, Initiator};
+pub(crate) use self::extension::UserExtensions;
 pub(crate) use self::peer::{Dyn as DynPeer, Peer};
-pub(crate) use self::ping_pong::UserPings;
+pub(crate) use self::ping_pong::{UserPing, UserPings};
+pub use self::streams::SchedulingStrategy;
 pub(crate) use self::streams::{DynStreams, OpaqueStreamRef, StreamRef, Streams};
 pub(crate) use self::streams::{Open, PollReset, Prioritized};
 
 use crate::codec::Codec;
 
+use self::extension::Extensions;
+use self::fingerprint::Recorder;
 use self::go_away::GoAway;
 use self::ping_pong::PingPong;
 use self::settings::Settings;
@@ -40,3 +46,5 @@ pub const DEFAULT_RESET_STREAM_MAX: usize = 50;
 // reasonable guess of the average here.
 pub const DEFAULT_RESET_STREAM_SECS: u64 = 1;
 pub const DEFAULT_MAX_SEND_BUFFER_SIZE: usize = 1024 * 400;
+pub const DEFAULT_MAX_ADAPTIVE_WINDOW_SIZE: WindowSize = 1024 * 1024 * 16;
+pub const DEFAULT_KEEP_ALIVE_TIMEOUT_SECS: u64 = 20;
@@ -1,15 +1,19 @@
 use crate::codec::Codec;
 use crate::frame::Ping;
-use crate::proto::{self, PingPayload};
+use crate::proto::streams::Mutex;
+use crate::proto::{self, PingPayload, WindowSize};
 use crate::tracing;
 
 use atomic_waker::AtomicWaker;
 use bytes::Buf;
-use std::io;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+use std::{cmp, io};
 use tokio::io::AsyncWrite;
+use tokio::time::Sleep;
 
 /// Acknowledges ping requests from the remote.
 #[derive(Debug)]
@@ -17,6 +21,9 @@ pub(crate) struct PingPong {
     pending_ping: Option<PendingPing>,
     pending_pong: Option<PingPayload>,
     user_pings: Option<UserPingsRx>,
+    bdp: Option<Bdp>,
+    keep_alive: Option<KeepAlive>,
+    rtt: Rtt,
 }
 
 #[derive(Debug)]
@@ -25,13 +32,46 @@ pub(crate) struct UserPings(Arc<UserPingsInner>);
 #[derive(Debug)]
 struct UserPingsRx(Arc<UserPingsInner>);
 
+/// A PING sent by the user. It is forgotten if dropped before its pong is
+/// received.
+#[derive(Debug)]
+pub(crate) struct UserPing {
+    inner: Arc<UserPingsInner>,
+    payload: PingPayload,
+}
+
 #[derive(Debug)]
 struct UserPingsInner {
-    state: AtomicUsize,
+    state: Mutex<UserPingsState>,
     /// Task to wake up the main `Connection`.
     ping_task: AtomicWaker,
-    /// Task to wake up `share::PingPong::poll_pong`.
-    pong_task: AtomicWaker,
+    /// Task to wake up `share::PingPong::poll_pong` when no PING is in flight.
+    close_task: AtomicWaker,
+}
+
+#[derive(Debug, Default)]
+struct UserPingsState {
+    /// The PINGs sent by the user, each with a distinct payload.
+    pings: Vec<QueuedPing>,
+    closed: bool,
+}
+
+#[derive(Debug)]
+struct QueuedPing {
+    payload: PingPayload,
+    /// When the PING was written, `None` until then.
+    sent_at: Option<Instant>,
+    /// The round trip time, once the PING is acknowledged.
+    rtt: Option<Duration>,
+    /// Task to wake up once the PING is acknowledged.
+    waker: Option<Waker>,
+}
+
+/// Round trip times measured from the acknowledged PINGs.
+#[derive(Debug, Default)]
+struct Rtt {
+    smoothed: Option<Duration>,
+    min: Option<Duration>,
 }
 
 #[derive(Debug)]
@@ -40,6 +80,64 @@ struct PendingPing {
     sent: bool,
 }
 
+/// Estimates the bandwidth-delay product of the connection from the DATA
+/// received while a PING is in flight, and grows the receive windows to match.
+#[derive(Debug)]
+struct Bdp {
+    /// The receive window of streams, as last estimated.
+    window: WindowSize,
+
+    /// The window does not grow past this size.
+    max_window: WindowSize,
+
+    /// A window the connection has yet to apply.
+    update: Option<WindowSize>,
+
+    /// Bytes of DATA received since the PING in flight was sent.
+    bytes: usize,
+
+    /// Whether a PING should be sent to take a sample.
+    ping_pending: bool,
+
+    /// When the PING in flight was sent.
+    ping_sent_at: Option<Instant>,
+
+    /// How long to wait after a sample that did not grow the window.
+    ping_delay: Duration,
+
+    /// No sample is taken before this time.
+    next_ping_at: Option<Instant>,
+
+    /// The smoothed round trip time, in seconds.
+    rtt: f64,
+
+    /// The largest bandwidth sampled, in bytes per second.
+    max_bandwidth: f64,
+}
+
+/// Sends a PING when nothing was received for a while, and closes the
+/// connection if it is not acknowledged in time.
+#[derive(Debug)]
+struct KeepAlive {
+    interval: Duration,
+    timeout: Duration,
+    while_idle: bool,
+    state: KeepAliveState,
+    last_read_at: tokio::time::Instant,
+    timer: Pin<Box<Sleep>>,
+}
+
+#[derive(Debug)]
+enum KeepAliveState {
+    /// Waiting for `interval` to pass without receiving anything.
+    Idle,
+    /// A PING should be sent.
+    PingPending,
+    /// Waiting for the PING, sent at the given time, to be acknowledged
+    /// before the timer expires.
+    PingSent(Instant),
+}
+
 /// Status returned from `PingPong::recv_ping`.
 #[derive(Debug)]
 pub(crate) enum ReceivedPing {
@@ -48,16 +146,10 @@ pub(crate) enum ReceivedPing {
     Shutdown,
 }
 
-/// No user ping pending.
-const USER_STATE_EMPTY: usize = 0;
-/// User has called `send_ping`, but PING hasn't been written yet.
-const USER_STATE_PENDING_PING: usize = 1;
-/// User PING has been written, waiting for PONG.
-const USER_STATE_PENDING_PONG: usize = 2;
-/// We've received user PONG, waiting for user to `poll_pong`.
-const USER_STATE_RECEIVED_PONG: usize = 3;
-/// The connection is closed.
-const USER_STATE_CLOSED: usize = 4;
+/// The wait between samples once the window stops growing, at first...
+const BDP_MIN_PING_DELAY: Duration = Duration::from_millis(100);
+/// ...and at most.
+const BDP_MAX_PING_DELAY: Duration = Duration::from_secs(10);
 
 // ===== impl PingPong =====
 
@@ -67,6 +159,91 @@ impl PingPong {
             pending_ping: None,
             pending_pong: None,
             user_pings: None,
+            bdp: None,
+            keep_alive: None,
+            rtt: Rtt::default(),
+        }
+    }
+
+    /// Returns the smoothed round trip time of the acknowledged PINGs.
+    pub(crate) fn smoothed_rtt(&self) -> Option<Duration> {
+        self.rtt.smoothed
+    }
+
+    /// Returns the smallest round trip time of the acknowledged PINGs.
+    pub(crate) fn min_rtt(&self) -> Option<Duration> {
+        self.rtt.min
+    }
+
+    /// Sends a PING once `interval` passes without receiving a frame, and
+    /// fails the connection if it is not acknowledged within `timeout`.
+    ///
+    /// Unless `while_idle` is set, no PING is sent while there are no
+    /// streams.
+    pub(crate) fn enable_keep_alive(
+        &mut self,
+        interval: Duration,
+        timeout: Duration,
+        while_idle: bool,
+    ) {
+        let now = tokio::time::Instant::now();
+
+        self.keep_alive = Some(KeepAlive {
+            interval,
+            timeout,
+            while_idle,
+            state: KeepAliveState::Idle,
+            last_read_at: now,
+            timer: Box::pin(tokio::time::sleep_until(now + interval)),
+        });
+    }
+
+    /// Notes that a frame was received, which shows the connection is alive.
+    pub(crate) fn record_read(&mut self) {
+        if let Some(ref mut keep_alive) = self.keep_alive {
+            keep_alive.last_read_at = tokio::time::Instant::now();
+        }
+    }
+
+    /// Checks the keep-alive timers, scheduling a PING when one is due.
+    ///
+    /// Returns an error if the last PING was not acknowledged in time.
+    pub(crate) fn poll_keep_alive(
+        &mut self,
+        cx: &mut Context,
+        has_streams: bool,
+    ) -> Result<(), proto::Error> {
+        match self.keep_alive {
+            Some(ref mut keep_alive) => keep_alive.poll(cx, has_streams),
+            None => Ok(()),
+        }
+    }
+
+    /// Grows the receive windows from `window` up to `max_window` as the
+    /// bandwidth-delay product of the connection is sampled.
+    pub(crate) fn enable_bdp(&mut self, window: WindowSize, max_window: WindowSize) {
+        self.bdp = Some(Bdp::new(window, max_window));
+    }
+
+    /// Accounts for a DATA frame received, possibly asking for a PING to be
+    /// sent to sample the bandwidth-delay product.
+    pub(crate) fn recv_data(&mut self, len: usize) {
+        if let Some(ref mut bdp) = self.bdp {
+            bdp.recv_data(len);
+        }
+    }
+
+    /// Takes the receive window size the bandwidth-delay product estimate
+    /// last grew to, if it has not been applied yet.
+    pub(crate) fn take_bdp_window(&mut self) -> Option<WindowSize> {
+        self.bdp.as_mut().and_then(|bdp| bdp.update.take())
+    }
+
+    /// Puts back a window returned by `take_bdp_window` that could not be
+    /// applied yet.
+    pub(crate) fn defer_bdp_window(&mut self, window: WindowSize) {
+        if let Some(ref mut bdp) = self.bdp {
+            bdp.update.get_or_insert(window);
         }
     }
 
@@ -77,9 +254,9 @@ impl PingPong {
         }
 
         let user_pings = Arc::new(UserPingsInner {
-            state: AtomicUsize::new(USER_STATE_EMPTY),
+            state: Mutex::new(UserPingsState::default()),
             ping_task: AtomicWaker::new(),
-            pong_task: AtomicWaker::new(),
+            close_task: AtomicWaker::new(),
         });
         self.user_pings = Some(UserPingsRx(user_pings.clone()));
         Some(UserPings(user_pings))
@@ -116,9 +293,32 @@ impl PingPong {
                 self.pending_ping = Some(pending);
             }
 
+            if let Some(ref mut keep_alive) = self.keep_alive {
+                if ping.payload() == &Ping::KEEP_ALIVE {
+                    if let Some(rtt) = keep_alive.recv_pong() {
+                        tracing::trace!("recv PING KEEP_ALIVE ack");
+                        self.rtt.sample(rtt);
+                        return ReceivedPing::Unknown;
+                    }
+                }
+            }
+
+            if let Some(ref mut bdp) = self.bdp {
+                if ping.payload() == &Ping::BDP {
+                    if let Some(sent_at) = bdp.ping_sent_at.take() {
+                        tracing::trace!("recv PING BDP ack");
+                        let rtt = sent_at.elapsed();
+                        self.rtt.sample(rtt);
+                        bdp.recv_pong(rtt);
+                        return ReceivedPing::Unknown;
+                    }
+                }
+            }
+
             if let Some(ref users) = self.user_pings {
-                if ping.payload() == &Ping::USER && users.receive_pong() {
+                if let Some(rtt) = users.receive_pong(ping.payload()) {
                     tracing::trace!("recv PING USER ack");
+                    self.rtt.sample(rtt);
                     return ReceivedPing::Unknown;
                 }
             }
@@ -179,19 +379,42 @@ impl PingPong {
                 ping.sent = true;
             }
         } else if let Some(ref users) = self.user_pings {
-            if users.0.state.load(Ordering::Acquire) == USER_STATE_PENDING_PING {
+            // Register first, so that a PING queued meanwhile is not missed.
+            users.0.ping_task.register(cx.waker());
+
+            while let Some(payload) = users.next_unsent() {
+                if !dst.poll_ready(cx)?.is_ready() {
+                    return Poll::Pending;
+                }
+
+                dst.buffer(Ping::new(payload).into())
+                    .expect("invalid ping frame");
+                users.ping_sent(&payload);
+            }
+        }
+
+        if let Some(ref mut bdp) = self.bdp {
+            if bdp.ping_pending {
                 if !dst.poll_ready(cx)?.is_ready() {
                     return Poll::Pending;
                 }
 
-                dst.buffer(Ping::new(Ping::USER).into())
+                dst.buffer(Ping::new(Ping::BDP).into())
                     .expect("invalid ping frame");
-                users
-                    .0
-                    .state
-                    .store(USER_STATE_PENDING_PONG, Ordering::Release);
-            } else {
-                users.0.ping_task.register(cx.waker());
+                bdp.ping_pending = false;
+                bdp.ping_sent_at = Some(Instant::now());
+            }
+        }
+
+        if let Some(ref mut keep_alive) = self.keep_alive {
+            if let KeepAliveState::PingPending = keep_alive.state {
+                if !dst.poll_ready(cx)?.is_ready() {
+                    return Poll::Pending;
+                }
+
+                dst.buffer(Ping::new(Ping::KEEP_ALIVE).into())
+                    .expect("invalid ping frame");
+                keep_alive.ping_sent(cx);
             }
         }
 
@@ -199,6 +422,153 @@ impl PingPong {
     }
 }
 
+// ===== impl Bdp =====
+
+impl Bdp {
+    fn new(window: WindowSize, max_window: WindowSize) -> Self {
+        Bdp {
+            window,
+            max_window,
+            update: None,
+            bytes: 0,
+            ping_pending: false,
+            ping_sent_at: None,
+            ping_delay: BDP_MIN_PING_DELAY,
+            next_ping_at: None,
+            rtt: 0.0,
+            max_bandwidth: 0.0,
+        }
+    }
+
+    fn recv_data(&mut self, len: usize) {
+        if self.ping_pending || self.ping_sent_at.is_some() {
+            self.bytes += len;
+            return;
+        }
+
+        // Nothing left to learn once the window is as large as allowed.
+        if self.window >= self.max_window {
+            return;
+        }
+
+        if let Some(at) = self.next_ping_at {
+            if Instant::now() < at {
+                return;
+            }
+        }
+
+        self.bytes = len;
+        self.ping_pending = true;
+    }
+
+    fn recv_pong(&mut self, rtt: Duration) {
+        let bytes = std::mem::take(&mut self.bytes);
+
+        // Smooth the round trip time as TCP does, see RFC 6298.
+        let rtt = cmp::max(rtt, Duration::from_micros(1)).as_secs_f64();
+        if self.rtt == 0.0 {
+            self.rtt = rtt;
+        } else {
+            self.rtt += (rtt - self.rtt) / 8.0;
+        }
+
+        // A sample below the bandwidth already seen was limited by something
+        // else than the window.
+        let bandwidth = bytes as f64 / (self.rtt * 1.5);
+        if bandwidth < self.max_bandwidth {
+            self.stabilize();
+            return;
+        }
+        self.max_bandwidth = bandwidth;
+
+        // The sample used up most of the window: the window is likely what
+        // limited it, so make room for twice as much.
+        if bytes >= self.window as usize * 2 / 3 {
+            let window = cmp::min(bytes.saturating_mul(2), self.max_window as usize);
+            self.window = window as WindowSize;
+            self.update = Some(self.window);
+            tracing::trace!(window = self.window, rtt = self.rtt, "BDP window grew");
+        } else {
+            self.stabilize();
+        }
+    }
+
+    /// Samples less often while the estimate does not change.
+    fn stabilize(&mut self) {
+        self.next_ping_at = Some(Instant::now() + self.ping_delay);
+        self.ping_delay = cmp::min(self.ping_delay * 4, BDP_MAX_PING_DELAY);
+    }
+}
+
+// ===== impl KeepAlive =====
+
+impl KeepAlive {
+    fn poll(&mut self, cx: &mut Context, has_streams: bool) -> Result<(), proto::Error> {
+        match self.state {
+            KeepAliveState::Idle => {
+                if self.timer.as_mut().poll(cx).is_pending() {
+                    return Ok(());
+                }
+
+                // Frames received since the timer was set push the PING back.
+                let deadline = self.last_read_at + self.interval;
+                if deadline > tokio::time::Instant::now() {
+                    self.timer.as_mut().reset(deadline);
+                    let _ = self.timer.as_mut().poll(cx);
+                    return Ok(());
+                }
+
+                if has_streams || self.while_idle {
+                    tracing::trace!("keep-alive interval elapsed; sending PING");
+                    self.state = KeepAliveState::PingPending;
+                }
+                Ok(())
+            }
+            KeepAliveState::PingPending => Ok(()),
+            KeepAliveState::PingSent(_) => {
+                if self.timer.as_mut().poll(cx).is_pending() {
+                    return Ok(());
+                }
+
+                tracing::debug!("keep-alive timed out");
+                Err(proto::Error::KeepAliveTimedOut)
+            }
+        }
+    }
+
+    fn ping_sent(&mut self, cx: &mut Context) {
+        self.state = KeepAliveState::PingSent(Instant::now());
+        self.timer
+            .as_mut()
+            .reset(tokio::time::Instant::now() + self.timeout);
+        let _ = self.timer.as_mut().poll(cx);
+    }
+
+    /// Returns the round trip time if a keep-alive PING was in flight.
+    fn recv_pong(&mut self) -> Option<Duration> {
+        if let KeepAliveState::PingSent(sent_at) = self.state {
+            self.state = KeepAliveState::Idle;
+            self.timer.as_mut().reset(self.last_read_at + self.interval);
+            Some(sent_at.elapsed())
+        } else {
+            None
+        }
+    }
+}
+
+// ===== impl Rtt =====
+
+impl Rtt {
+    fn sample(&mut self, rtt: Duration) {
+        // Smooth the round trip time as TCP does, see RFC 6298.
+        self.smoothed = Some(match self.smoothed {
+            Some(smoothed) => smoothed * 7 / 8 + rtt / 8,
+            None => rtt,
+        });
+        self.min = Some(self.min.map_or(rtt, |min| cmp::min(min, rtt)));
+    }
+}
+
 impl ReceivedPing {
     pub(crate) fn is_shutdown(&self) -> bool {
         matches!(*self, Self::Shutdown)
@@ -208,85 +578,148 @@ impl ReceivedPing {
 // ===== impl UserPings =====
 
 impl UserPings {
-    pub(crate) fn send_ping(&self) -> Result<(), Option<proto::Error>> {
-        let prev = self
-            .0
-            .state
-            .compare_exchange(
-                USER_STATE_EMPTY,        // current
-                USER_STATE_PENDING_PING, // new
-                Ordering::AcqRel,
-                Ordering::Acquire,
-            )
-            .unwrap_or_else(|v| v);
-
-        match prev {
-            USER_STATE_EMPTY => {
-                self.0.ping_task.wake();
-                Ok(())
-            }
-            USER_STATE_CLOSED => Err(Some(broken_pipe().into())),
-            _ => {
-                // Was already pending, user error!
-                Err(None)
-            }
+    /// Queues a PING with a payload that no other PING in flight uses.
+    pub(crate) fn send_ping(&self) -> Result<UserPing, proto::Error> {
+        let mut state = self.0.state.lock();
+
+        if state.closed {
+            return Err(broken_pipe().into());
         }
+
+        let payload = (0..)
+            .map(user_payload)
+            .find(|payload| {
+                !is_reserved(payload) && !state.pings.iter().any(|ping| &ping.payload == payload)
+            })
+            .expect("free ping payload");
+
+        state.pings.push(QueuedPing {
+            payload,
+            sent_at: None,
+            rtt: None,
+            waker: None,
+        });
+        drop(state);
+
+        self.0.ping_task.wake();
+
+        Ok(UserPing {
+            inner: self.0.clone(),
+            payload,
+        })
     }
 
-    pub(crate) fn poll_pong(&self, cx: &mut Context) -> Poll<Result<(), proto::Error>> {
+    /// Waits for the connection to close.
+    pub(crate) fn poll_closed(&self, cx: &mut Context) -> Poll<proto::Error> {
         // Must register before checking state, in case state were to change
-        // before we could register, and then the ping would just be lost.
-        self.0.pong_task.register(cx.waker());
-        let prev = self
-            .0
-            .state
-            .compare_exchange(
-                USER_STATE_RECEIVED_PONG, // current
-                USER_STATE_EMPTY,         // new
-                Ordering::AcqRel,
-                Ordering::Acquire,
-            )
-            .unwrap_or_else(|v| v);
-
-        match prev {
-            USER_STATE_RECEIVED_PONG => Poll::Ready(Ok(())),
-            USER_STATE_CLOSED => Poll::Ready(Err(broken_pipe().into())),
-            _ => Poll::Pending,
+        // before we could register, and then the close would just be lost.
+        self.0.close_task.register(cx.waker());
+
+        if self.0.state.lock().closed {
+            Poll::Ready(broken_pipe().into())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+// ===== impl UserPing =====
+
+impl UserPing {
+    /// Waits for the PING to be acknowledged, returning the round trip time.
+    pub(crate) fn poll_pong(&self, cx: &mut Context) -> Poll<Result<Duration, proto::Error>> {
+        let mut state = self.inner.state.lock();
+        let closed = state.closed;
+
+        let ping = state
+            .pings
+            .iter_mut()
+            .find(|ping| ping.payload == self.payload)
+            .expect("user ping queued");
+
+        if let Some(rtt) = ping.rtt {
+            Poll::Ready(Ok(rtt))
+        } else if closed {
+            Poll::Ready(Err(broken_pipe().into()))
+        } else {
+            ping.waker = Some(cx.waker().clone());
+            Poll::Pending
         }
     }
 }
 
+impl Drop for UserPing {
+    fn drop(&mut self) {
+        self.inner
+            .state
+            .lock()
+            .pings
+            .retain(|ping| ping.payload != self.payload);
+    }
+}
+
 // ===== impl UserPingsRx =====
 
 impl UserPingsRx {
-    fn receive_pong(&self) -> bool {
-        let prev = self
-            .0
-            .state
-            .compare_exchange(
-                USER_STATE_PENDING_PONG,  // current
-                USER_STATE_RECEIVED_PONG, // new
-                Ordering::AcqRel,
-                Ordering::Acquire,
-            )
-            .unwrap_or_else(|v| v);
-
-        if prev == USER_STATE_PENDING_PONG {
-            self.0.pong_task.wake();
-            true
-        } else {
-            false
+    fn next_unsent(&self) -> Option<PingPayload> {
+        let state = self.0.state.lock();
+        state
+            .pings
+            .iter()
+            .find(|ping| ping.sent_at.is_none())
+            .map(|ping| ping.payload)
+    }
+
+    fn ping_sent(&self, payload: &PingPayload) {
+        let mut state = self.0.state.lock();
+        if let Some(ping) = state.pings.iter_mut().find(|ping| &ping.payload == payload) {
+            ping.sent_at = Some(Instant::now());
         }
     }
+
+    /// Returns the round trip time if the pong acknowledges a user PING.
+    fn receive_pong(&self, payload: &PingPayload) -> Option<Duration> {
+        let mut state = self.0.state.lock();
+        let ping = state
+            .pings
+            .iter_mut()
+            .find(|ping| &ping.payload == payload && ping.rtt.is_none())?;
+
+        let rtt = ping.sent_at?.elapsed();
+        ping.rtt = Some(rtt);
+        if let Some(waker) = ping.waker.take() {
+            waker.wake();
+        }
+
+        Some(rtt)
+    }
 }
 
 impl Drop for UserPingsRx {
     fn drop(&mut self) {
-        self.0.state.store(USER_STATE_CLOSED, Ordering::Release);
-        self.0.pong_task.wake();
+        let mut state = self.0.state.lock();
+        state.closed = true;
+        for ping in &mut state.pings {
+            if let Some(waker) = ping.waker.take() {
+                waker.wake();
+            }
+        }
+        drop(state);
+
+        self.0.close_task.wake();
     }
 }
 
+/// Returns the `n`th payload used for user PINGs.
+fn user_payload(n: u64) -> PingPayload {
+    u64::from_be_bytes(Ping::USER).wrapping_add(n).to_be_bytes()
+}
+
+/// Returns true if the payload is used by the PINGs of the connection itself.
+fn is_reserved(payload: &PingPayload) -> bool {
+    [Ping::SHUTDOWN, Ping::BDP, Ping::KEEP_ALIVE].contains(payload)
+}
+
 fn broken_pipe() -> io::Error {
     io::ErrorKind::BrokenPipe.into()
 }
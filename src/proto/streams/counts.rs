@@ -1,4 +1,6 @@
 use super::*;
+use crate::frame::Reason;
+use crate::stats::{ConnectionStats, StreamCounts};
 use crate::tracing;
 
 #[derive(Debug)]
@@ -40,6 +42,10 @@ pub(super) struct Counts {
     /// Total number of locally reset streams due to protocol error across the
     /// lifetime of the connection.
     num_local_error_reset_streams: usize,
+
+    /// Streams opened, closed and reset across the lifetime of the
+    /// connection.
+    stats: StreamCounts,
 }
 
 impl Counts {
@@ -57,9 +63,23 @@ impl Counts {
             num_remote_reset_streams: 0,
             max_local_error_reset_streams: config.local_max_error_reset_streams,
             num_local_error_reset_streams: 0,
+            stats: StreamCounts::default(),
         }
     }
 
+    /// Copies the stream counts into `stats`.
+    pub fn fill_stats(&self, stats: &mut ConnectionStats) {
+        stats.streams = self.stats.clone();
+        stats.local_reset_streams = self.num_local_reset_streams;
+        stats.remote_reset_streams = self.num_remote_reset_streams;
+        stats.local_error_reset_streams = self.num_local_error_reset_streams;
+    }
+
+    /// Accounts for a stream reset.
+    pub fn record_reset(&mut self, initiator: Initiator, reason: Reason) {
+        self.stats.record_reset(initiator, reason);
+    }
+
     /// Returns true when the next opened stream will reach capacity of outbound streams
     ///
     /// The number of client send streams is incremented in prioritize; send_request has to guess if
@@ -115,6 +135,7 @@ impl Counts {
         // Increment the number of remote initiated streams
         self.num_recv_streams += 1;
         stream.is_counted = true;
+        self.stats.record_open(false);
     }
 
     /// Returns true if the send stream concurrency can be incremented
@@ -134,6 +155,7 @@ impl Counts {
         // Increment the number of remote initiated streams
         self.num_send_streams += 1;
         stream.is_counted = true;
+        self.stats.record_open(true);
     }
 
     /// Returns true if the number of pending reset streams can be incremented.
@@ -264,10 +286,12 @@ impl Counts {
             assert!(self.num_send_streams > 0);
             self.num_send_streams -= 1;
             stream.is_counted = false;
+            self.stats.record_close(true);
         } else {
             assert!(self.num_recv_streams > 0);
             self.num_recv_streams -= 1;
             stream.is_counted = false;
+            self.stats.record_close(false);
         }
     }
 
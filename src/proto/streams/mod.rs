@@ -2,6 +2,7 @@ mod buffer;
 mod counts;
 mod flow_control;
 mod prioritize;
+mod priority_tree;
 mod recv;
 mod send;
 mod state;
@@ -12,9 +13,11 @@ mod streams;
 mod sync;
 
 pub(crate) use self::prioritize::Prioritized;
+pub use self::prioritize::SchedulingStrategy;
 pub(crate) use self::recv::Open;
 pub(crate) use self::send::PollReset;
 pub(crate) use self::streams::{DynStreams, OpaqueStreamRef, StreamRef, Streams};
+pub(crate) use self::sync::Mutex;
 
 use self::buffer::Buffer;
 use self::counts::Counts;
@@ -26,7 +29,10 @@ use self::state::State;
 use self::store::Store;
 use self::stream::Stream;
 
-use crate::frame::{Priorities, PseudoOrder, StreamDependency, StreamId, StreamIdOverflow};
+use crate::frame::{
+    HeaderOrder, PaddingPolicy, Priorities, PseudoOrder, StreamDependency, StreamId,
+    StreamIdOverflow,
+};
 use crate::proto::*;
 
 use bytes::Bytes;
@@ -80,8 +86,20 @@ pub struct Config {
     /// Pseudo order of the headers stream
     pub headers_pseudo_order: Option<PseudoOrder>,
 
+    /// Regular field order of the headers stream
+    pub headers_order: Option<HeaderOrder>,
+
     /// Priorities stream
     pub priorities: Option<Priorities>,
+
+    /// How sent DATA frames are padded
+    pub padding: PaddingPolicy,
+
+    /// If streams are scheduled along the peer's RFC 7540 dependency tree
+    pub rfc7540_priorities: bool,
+
+    /// How streams take turns sending DATA frames
+    pub scheduling_strategy: SchedulingStrategy,
 }
 
 trait DebugStructExt<'a, 'b> {
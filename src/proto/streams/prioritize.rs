@@ -1,7 +1,8 @@
-use super::store::Resolve;
+use super::priority_tree::PriorityTree;
+use super::store::{Next, Resolve};
 use super::*;
 
-use crate::frame::Reason;
+use crate::frame::{Padder, Reason};
 
 use crate::codec::UserError;
 use crate::codec::UserError::*;
@@ -13,8 +14,61 @@ use std::{
     cmp::{self, Ordering},
     fmt, io, mem,
     task::{Context, Poll, Waker},
+    time::Instant,
 };
 
+/// How the DATA frames of concurrent streams share the connection.
+///
+/// Whatever the strategy, streams only send DATA they have window capacity
+/// for, and the priorities signalled by the peer decide which streams are
+/// served first. The strategy decides how streams that are served together
+/// take turns.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum SchedulingStrategy {
+    /// Each stream sends one frame, as large as the peer allows, and then
+    /// waits for the other streams to send theirs.
+    #[default]
+    Fifo,
+
+    /// Each stream sends up to `quantum` bytes of DATA, in as many frames as
+    /// needed, and then waits for the other streams to send theirs.
+    ///
+    /// A small quantum gets the first bytes of every response out sooner, at
+    /// the cost of smaller frames.
+    RoundRobin {
+        /// Bytes of DATA a stream sends per turn, at least 1.
+        quantum: u32,
+    },
+
+    /// Deficit weighted round robin: like [`RoundRobin`], but the bytes a
+    /// stream sends per turn are scaled by its weight, `quantum` being the
+    /// share of a stream of the default weight of 16.
+    ///
+    /// Weights range from 1 to 256, and are taken from the RFC 7540 priority
+    /// the peer gives the stream in its HEADERS or PRIORITY frames, or set
+    /// locally with [`SendStream::set_priority`]. This is the only strategy
+    /// in which the weight of a stream decides how much DATA it sends.
+    ///
+    /// [`RoundRobin`]: SchedulingStrategy::RoundRobin
+    /// [`SendStream::set_priority`]: crate::SendStream::set_priority
+    WeightedRoundRobin {
+        /// Bytes of DATA a stream of weight 16 sends per turn, at least 1.
+        quantum: u32,
+    },
+}
+
+impl SchedulingStrategy {
+    pub(crate) fn assert_valid(&self) {
+        match *self {
+            SchedulingStrategy::Fifo => {}
+            SchedulingStrategy::RoundRobin { quantum }
+            | SchedulingStrategy::WeightedRoundRobin { quantum } => {
+                assert!(quantum > 0, "scheduling quantum must be at least 1");
+            }
+        }
+    }
+}
+
 /// # Warning
 ///
 /// Queued streams are ordered by stream ID, as we need to ensure that
@@ -26,11 +80,11 @@ use std::{
 /// idle stream is opened first.
 #[derive(Debug)]
 pub(super) struct Prioritize {
-    /// Queue of streams waiting for socket capacity to send a frame.
-    pending_send: store::Queue<stream::NextSend>,
+    /// Queues of streams waiting for socket capacity to send a frame.
+    pending_send: PendingSend,
 
-    /// Queue of streams waiting for window capacity to produce data.
-    pending_capacity: store::Queue<stream::NextSendCapacity>,
+    /// Queues of streams waiting for window capacity to produce data.
+    pending_capacity: PendingCapacity,
 
     /// Streams waiting for capacity due to max concurrency
     ///
@@ -58,6 +112,47 @@ pub(super) struct Prioritize {
 
     /// The maximum amount of bytes a stream should buffer.
     max_buffer_size: usize,
+
+    /// Pads sent `DATA` frames.
+    padder: Padder,
+
+    /// How streams take turns sending `DATA` frames.
+    strategy: SchedulingStrategy,
+
+    /// Streams whose first `HEADERS` frame was written to the codec but not
+    /// flushed yet.
+    unflushed_headers: Vec<StreamId>,
+}
+
+/// Streams waiting for socket capacity, grouped by the RFC 9218 priority of
+/// their response.
+///
+/// Queues are served from the most urgent to the least urgent. Within an
+/// urgency, streams that are not incremental are served one at a time, before
+/// incremental streams, which take turns. Streams without a priority signal
+/// are treated as incremental streams of the default urgency, so that they
+/// are served round robin as before.
+///
+/// When RFC 7540 priorities are enabled, the dependency tree built from the
+/// peer's PRIORITY frames decides instead, and the queues stay empty.
+#[derive(Debug)]
+struct PendingSend {
+    /// Indexed by urgency, then by whether the stream is incremental.
+    queues: [[store::Queue<stream::NextSend>; 2]; 8],
+
+    tree: Option<PriorityTree>,
+}
+
+/// Streams waiting for connection capacity, grouped by their weight.
+///
+/// Streams of a higher weight are assigned capacity first, and streams of
+/// the same weight in the order they asked for it. A stream is queued with
+/// the weight it had when it asked for capacity.
+#[derive(Debug)]
+struct PendingCapacity {
+    /// Sorted by decreasing weight. Nearly always holds a single queue, for
+    /// the default weight.
+    queues: Vec<(u16, store::Queue<stream::NextSendCapacity>)>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -96,13 +191,16 @@ impl Prioritize {
         tracing::trace!("Prioritize::new; flow={:?}", flow);
 
         Prioritize {
-            pending_send: store::Queue::new(),
-            pending_capacity: store::Queue::new(),
+            pending_send: PendingSend::new(config.rfc7540_priorities),
+            pending_capacity: PendingCapacity::new(),
             pending_open: store::Queue::new(),
             flow,
             last_opened_id: StreamId::ZERO,
             in_flight_data_frame: InFlightData::Nothing,
             max_buffer_size: config.local_max_buffer_size,
+            padder: Padder::new(config.padding),
+            strategy: config.scheduling_strategy,
+            unflushed_headers: Vec::new(),
         }
     }
 
@@ -110,6 +208,11 @@ impl Prioritize {
         self.max_buffer_size
     }
 
+    /// Returns the connection level window for sending DATA.
+    pub fn connection_window_sz(&self) -> WindowSize {
+        self.flow.window_size()
+    }
+
     /// Queue a frame to be sent to the remote
     pub fn queue_frame<B>(
         &mut self,
@@ -124,6 +227,33 @@ impl Prioritize {
         self.schedule_send(stream, task);
     }
 
+    /// Queue a frame to be sent ahead of the frames already queued on the
+    /// stream, but after its HEADERS if they have not been sent yet.
+    pub fn queue_frame_front<B>(
+        &mut self,
+        frame: Frame<B>,
+        buffer: &mut Buffer<Frame<B>>,
+        stream: &mut store::Ptr,
+        task: &mut Option<Waker>,
+    ) {
+        let headers = match stream.pending_send.pop_front(buffer) {
+            Some(Frame::Headers(headers)) => Some(headers),
+            Some(frame) => {
+                stream.pending_send.push_front(buffer, frame);
+                None
+            }
+            None => None,
+        };
+
+        stream.pending_send.push_front(buffer, frame);
+
+        if let Some(headers) = headers {
+            stream.pending_send.push_front(buffer, headers.into());
+        }
+
+        self.schedule_send(stream, task);
+    }
+
     pub fn schedule_send(&mut self, stream: &mut store::Ptr, task: &mut Option<Waker>) {
         // If the stream is waiting to be opened, nothing more to do.
         if stream.is_send_ready() {
@@ -268,6 +398,8 @@ impl Prioritize {
 
                     self.assign_connection_capacity(diff, stream, counts);
                 }
+
+                stream.update_send_blocked();
             }
             Ordering::Greater => {
                 // If trying to *add* capacity, but the stream send side is closed,
@@ -434,6 +566,7 @@ impl Prioritize {
 
         if additional == 0 {
             // Nothing more to do
+            stream.update_send_blocked();
             return;
         }
 
@@ -485,6 +618,8 @@ impl Prioritize {
             self.pending_capacity.push(stream);
         }
 
+        stream.update_send_blocked();
+
         // If data is buffered and the stream is send ready, then
         // schedule the stream for execution
         if stream.buffered_send_data > 0 && stream.is_send_ready() {
@@ -552,6 +687,7 @@ impl Prioritize {
                 None => {
                     // Try to flush the codec.
                     ready!(dst.flush(cx))?;
+                    self.record_headers_flushed(store);
 
                     // This might release a data frame...
                     if !self.reclaim_frame(buffer, store, dst) {
@@ -565,6 +701,15 @@ impl Prioritize {
         }
     }
 
+    fn record_headers_flushed(&mut self, store: &mut Store) {
+        let now = Instant::now();
+        for id in self.unflushed_headers.drain(..) {
+            if let Some(mut stream) = store.find_mut(&id) {
+                stream.stats.record_headers_flushed(now);
+            }
+        }
+    }
+
     /// Tries to reclaim a pending data frame from the codec.
     ///
     /// Returns true if a frame was reclaimed.
@@ -655,7 +800,7 @@ impl Prioritize {
         // If needed, schedule the sender
         if stream.send_flow.available() > 0 {
             debug_assert!(!stream.pending_send.is_empty());
-            self.pending_send.push(stream);
+            self.pending_send.requeue(stream);
         }
     }
 
@@ -669,6 +814,7 @@ impl Prioritize {
 
         stream.buffered_send_data = 0;
         stream.requested_send_capacity = 0;
+        stream.update_send_blocked();
         if let InFlightData::DataFrame(key) = self.in_flight_data_frame {
             if stream.key() == key {
                 // This stream could get cleaned up now - don't allow the buffered frame to get reclaimed.
@@ -687,6 +833,13 @@ impl Prioritize {
         }
     }
 
+    /// Moves a stream in the dependency tree, if RFC 7540 priorities are
+    /// enabled. Without a dependency, a stream that is not in the tree yet is
+    /// added with the default priority.
+    pub fn reprioritize(&mut self, id: StreamId, dependency: Option<StreamDependency>) {
+        self.pending_send.reprioritize(id, dependency);
+    }
+
     pub fn clear_pending_open(&mut self, store: &mut Store, counts: &mut Counts) {
         while let Some(stream) = self.pending_open.pop(store) {
             let is_pending_reset = stream.is_pending_reset_expiration();
@@ -720,6 +873,9 @@ impl Prioritize {
 
                     tracing::trace!(is_pending_reset);
 
+                    // Bytes of flow controlled data the frame carries.
+                    let mut sent = 0;
+
                     let frame = match stream.pending_send.pop_front(buffer) {
                         Some(Frame::Data(mut frame)) => {
                             // Get the amount of capacity remaining for stream's
@@ -760,6 +916,12 @@ impl Prioritize {
                             // Only send up to the max frame length
                             let len = cmp::min(sz, max_len);
 
+                            // Only send what is left of the stream's turn
+                            let len = match self.turn_remaining(&mut stream) {
+                                Some(remaining) => cmp::min(len, remaining),
+                                None => len,
+                            };
+
                             // Only send up to the stream's window capacity
                             let len =
                                 cmp::min(len, stream_capacity.as_size() as usize) as WindowSize;
@@ -776,7 +938,35 @@ impl Prioritize {
                                 continue;
                             }
 
-                            tracing::trace!(len, "sending data frame");
+                            // Padding counts against flow control too, but it
+                            // is not part of the data the stream was assigned
+                            // capacity for. It may only use the frame space and
+                            // the windows that nobody else has a claim on.
+                            let padding = if self.padder.is_enabled() {
+                                let stream_room = stream
+                                    .send_flow
+                                    .window_size()
+                                    .saturating_sub(stream.send_flow.available().as_size());
+                                let conn_room = cmp::min(
+                                    self.flow.available().as_size(),
+                                    self.flow.window_size().saturating_sub(len),
+                                );
+                                let room = cmp::min(
+                                    max_len - len as usize,
+                                    cmp::min(stream_room, conn_room) as usize,
+                                );
+
+                                let pad_len = self.padder.pad_len(len as usize, room);
+                                frame.set_pad_len(pad_len);
+                                frame.padding_len() as WindowSize
+                            } else {
+                                0
+                            };
+
+                            tracing::trace!(len, padding, "sending data frame");
+                            sent = (len + padding) as usize;
+                            stream.stats.record_data_sent(len as usize);
+                            stream.send_deficit = stream.send_deficit.saturating_sub(len as usize);
 
                             // Update the flow control
                             {
@@ -799,6 +989,13 @@ impl Prioritize {
                                 let _res = self.flow.send_data(len);
                                 debug_assert!(_res.is_ok());
 
+                                if padding > 0 {
+                                    let _res = stream.send_flow.dec_send_window(padding);
+                                    debug_assert!(_res.is_ok());
+                                    let _res = self.flow.send_data(padding);
+                                    debug_assert!(_res.is_ok());
+                                }
+
                                 // Wrap the frame's data payload to ensure that the
                                 // correct amount of data gets written.
 
@@ -862,6 +1059,14 @@ impl Prioritize {
 
                     tracing::trace!("pop_frame; frame={:?}", frame);
 
+                    if let Frame::Headers(_) = frame {
+                        if !stream.stats.is_headers_flushed() {
+                            self.unflushed_headers.push(stream.id);
+                        }
+                    }
+
+                    self.pending_send.charge(&stream, sent);
+
                     if cfg!(debug_assertions) && stream.state.is_idle() {
                         debug_assert!(stream.id > self.last_opened_id);
                         self.last_opened_id = stream.id;
@@ -872,7 +1077,7 @@ impl Prioritize {
                         // the next frame. i.e. don't requeue it if the next
                         // frame is a data frame and the stream does not have
                         // any more capacity.
-                        self.pending_send.push(&mut stream);
+                        self.pending_send.requeue(&mut stream);
                     }
 
                     counts.transition_after(stream, is_pending_reset);
@@ -884,6 +1089,25 @@ impl Prioritize {
         }
     }
 
+    /// Returns how many bytes of `DATA` the stream may still send in its
+    /// turn, starting a new turn if the last one is over. Returns `None` if
+    /// streams do not take turns by bytes.
+    fn turn_remaining(&self, stream: &mut store::Ptr) -> Option<usize> {
+        let quantum = match self.strategy {
+            SchedulingStrategy::Fifo => return None,
+            SchedulingStrategy::RoundRobin { quantum } => quantum as usize,
+            SchedulingStrategy::WeightedRoundRobin { quantum } => {
+                quantum as usize * stream.send_weight as usize / stream::DEFAULT_WEIGHT as usize
+            }
+        };
+
+        if stream.send_deficit == 0 {
+            stream.send_deficit = cmp::max(quantum, 1);
+        }
+
+        Some(stream.send_deficit)
+    }
+
     fn pop_pending_open<'s>(
         &mut self,
         store: &'s mut Store,
@@ -905,6 +1129,152 @@ impl Prioritize {
     }
 }
 
+// ===== impl PendingSend =====
+
+impl PendingSend {
+    fn new(rfc7540_priorities: bool) -> PendingSend {
+        PendingSend {
+            queues: std::array::from_fn(|_| [store::Queue::new(), store::Queue::new()]),
+            tree: if rfc7540_priorities {
+                Some(PriorityTree::new())
+            } else {
+                None
+            },
+        }
+    }
+
+    fn queue(&mut self, stream: &store::Ptr) -> &mut store::Queue<stream::NextSend> {
+        let (urgency, incremental) = match stream.extensible_priority {
+            Some(priority) => (priority.urgency(), priority.is_incremental()),
+            None => (frame::ExtensiblePriority::default().urgency(), true),
+        };
+
+        &mut self.queues[urgency as usize][incremental as usize]
+    }
+
+    /// Queue a stream that is not waiting for its turn.
+    ///
+    /// A stream that left the queues in the middle of its round robin turn,
+    /// because it ran out of data or window, starts a new turn.
+    fn push(&mut self, stream: &mut store::Ptr) -> bool {
+        if !stream::NextSend::is_queued(stream) {
+            stream.send_deficit = 0;
+        }
+
+        self.enqueue(stream)
+    }
+
+    fn enqueue(&mut self, stream: &mut store::Ptr) -> bool {
+        if let Some(ref mut tree) = self.tree {
+            if stream::NextSend::is_queued(stream) {
+                return false;
+            }
+
+            stream::NextSend::set_queued(stream, true);
+            tree.push(stream.id, stream.key());
+            return true;
+        }
+
+        self.queue(stream).push(stream)
+    }
+
+    fn push_front(&mut self, stream: &mut store::Ptr) -> bool {
+        if self.tree.is_some() {
+            return self.enqueue(stream);
+        }
+
+        self.queue(stream).push_front(stream)
+    }
+
+    /// Queue a stream again after one of its frames was sent.
+    ///
+    /// A stream that is not incremental keeps its place at the head of its
+    /// queue, so that its response is sent before the next one starts, and
+    /// so does a stream that has not used up its round robin turn.
+    fn requeue(&mut self, stream: &mut store::Ptr) -> bool {
+        if stream.send_deficit > 0 {
+            return self.push_front(stream);
+        }
+
+        match stream.extensible_priority {
+            Some(priority) if !priority.is_incremental() => self.push_front(stream),
+            _ => self.enqueue(stream),
+        }
+    }
+
+    fn pop<'a, R>(&mut self, store: &'a mut R) -> Option<store::Ptr<'a>>
+    where
+        R: Resolve,
+    {
+        if let Some(ref mut tree) = self.tree {
+            let mut stream = store.resolve(tree.pop()?);
+            stream::NextSend::set_queued(&mut stream, false);
+            return Some(stream);
+        }
+
+        let queue = self
+            .queues
+            .iter_mut()
+            .flatten()
+            .find(|queue| !queue.is_empty())?;
+
+        queue.pop(store)
+    }
+
+    /// Accounts for the bytes of a frame the stream just sent.
+    fn charge(&mut self, stream: &store::Ptr, len: usize) {
+        if let Some(ref mut tree) = self.tree {
+            tree.charge(stream.id, len);
+        }
+    }
+
+    fn reprioritize(&mut self, id: StreamId, dependency: Option<StreamDependency>) {
+        if let Some(ref mut tree) = self.tree {
+            match dependency {
+                Some(dependency) => tree.reprioritize(id, dependency),
+                None => tree.insert(id),
+            }
+        }
+    }
+}
+
+// ===== impl PendingCapacity =====
+
+impl PendingCapacity {
+    fn new() -> PendingCapacity {
+        PendingCapacity { queues: Vec::new() }
+    }
+
+    fn push(&mut self, stream: &mut store::Ptr) -> bool {
+        let weight = stream.send_weight;
+        let idx = match self.queues.iter().position(|&(w, _)| w <= weight) {
+            Some(idx) if self.queues[idx].0 == weight => idx,
+            Some(idx) => {
+                self.queues.insert(idx, (weight, store::Queue::new()));
+                idx
+            }
+            None => {
+                self.queues.push((weight, store::Queue::new()));
+                self.queues.len() - 1
+            }
+        };
+
+        self.queues[idx].1.push(stream)
+    }
+
+    fn pop<'a, R>(&mut self, store: &'a mut R) -> Option<store::Ptr<'a>>
+    where
+        R: Resolve,
+    {
+        let (_, queue) = self
+            .queues
+            .iter_mut()
+            .find(|(_, queue)| !queue.is_empty())?;
+
+        queue.pop(store)
+    }
+}
+
 // ===== impl Prioritized =====
 
 impl<B> Buf for Prioritized<B>
@@ -0,0 +1,297 @@
+use super::store::Key;
+use super::stream::DEFAULT_WEIGHT;
+use super::*;
+
+use std::cmp;
+use std::collections::BTreeMap;
+
+/// The number of nodes kept in the tree before the oldest ones that are not
+/// waiting to send are dropped.
+const MAX_NODES: usize = 1024;
+
+/// Virtual time charged per byte to a stream of weight 1. Weights go up to
+/// 256, so every byte advances the virtual time of any stream.
+const STRIDE: u64 = 256;
+
+/// The RFC 7540 dependency tree of the streams of a connection.
+///
+/// Nodes are kept for the streams the peer opened or prioritized, including
+/// idle streams used as grouping nodes and streams that have since closed, as
+/// well as for every stream that queued a frame to send.
+///
+/// Streams waiting to send are picked by walking down from the root: a stream
+/// that can send goes before its dependents, and otherwise its children share
+/// the connection in proportion to their weights, using start-time fair
+/// queuing on the bytes they send.
+#[derive(Debug)]
+pub(super) struct PriorityTree {
+    nodes: BTreeMap<StreamId, Node>,
+}
+
+#[derive(Debug)]
+struct Node {
+    parent: StreamId,
+
+    /// Weight between 1 and 256.
+    weight: u16,
+
+    children: Vec<StreamId>,
+
+    /// Set while the stream waits to send a frame.
+    ready: Option<Key>,
+
+    /// Number of streams waiting to send in the subtree, this one included.
+    num_ready: usize,
+
+    /// Virtual time at which the last frame sent from the subtree finished,
+    /// as seen by the siblings of the node.
+    finish: u64,
+
+    /// Virtual time of the children of the node.
+    vtime: u64,
+}
+
+impl PriorityTree {
+    pub fn new() -> Self {
+        let mut nodes = BTreeMap::new();
+        nodes.insert(StreamId::ZERO, Node::new(StreamId::ZERO, 0));
+
+        PriorityTree { nodes }
+    }
+
+    /// Adds a stream to the tree with the default priority, unless it is
+    /// already there.
+    pub fn insert(&mut self, id: StreamId) {
+        self.ensure(id);
+        self.prune();
+    }
+
+    /// Moves a stream in the tree, as a PRIORITY frame or the priority fields
+    /// of a HEADERS frame ask, see RFC 7540 section 5.3.
+    pub fn reprioritize(&mut self, id: StreamId, dependency: StreamDependency) {
+        let parent = dependency.dependency_id();
+
+        if id.is_zero() || id == parent {
+            return;
+        }
+
+        self.ensure(id);
+        self.ensure(parent);
+
+        // A stream made dependent on one of its own dependencies swaps places
+        // with it (RFC 7540 section 5.3.3).
+        if self.is_descendant(parent, id) {
+            let former_parent = self.nodes[&id].parent;
+            self.move_node(parent, former_parent);
+        }
+
+        self.move_node(id, parent);
+        self.node_mut(id).weight = dependency.weight() as u16 + 1;
+
+        if dependency.is_exclusive() {
+            let siblings: Vec<_> = self
+                .node_mut(parent)
+                .children
+                .iter()
+                .copied()
+                .filter(|&child| child != id)
+                .collect();
+            self.node_mut(parent).children = vec![id];
+
+            // The subtree of `parent` keeps the same streams, so only `id`
+            // needs its count updated.
+            let mut num_ready = 0;
+            for &child in &siblings {
+                let child = self.node_mut(child);
+                child.parent = id;
+                num_ready += child.num_ready;
+            }
+
+            let node = self.node_mut(id);
+            node.children.extend(siblings);
+            node.num_ready += num_ready;
+        }
+
+        self.prune();
+    }
+
+    /// Marks a stream as waiting to send a frame.
+    pub fn push(&mut self, id: StreamId, key: Key) {
+        self.ensure(id);
+
+        let node = self.node_mut(id);
+        if node.ready.is_some() {
+            return;
+        }
+        node.ready = Some(key);
+
+        self.add_ready(id, 1);
+    }
+
+    /// Takes the stream that should send the next frame.
+    pub fn pop(&mut self) -> Option<Key> {
+        let mut id = StreamId::ZERO;
+
+        loop {
+            let node = &self.nodes[&id];
+
+            if node.num_ready == 0 {
+                return None;
+            }
+
+            if let Some(key) = node.ready {
+                self.node_mut(id).ready = None;
+                self.add_ready(id, -1);
+                return Some(key);
+            }
+
+            let vtime = node.vtime;
+            id = node
+                .children
+                .iter()
+                .copied()
+                .filter(|child| self.nodes[child].num_ready > 0)
+                .min_by_key(|child| cmp::max(self.nodes[child].finish, vtime))?;
+        }
+    }
+
+    /// Accounts for `len` bytes sent by the stream, and so by each of the
+    /// streams it depends on.
+    pub fn charge(&mut self, id: StreamId, len: usize) {
+        let len = cmp::max(len, 1) as u64;
+        let mut id = id;
+
+        while !id.is_zero() {
+            let (parent, weight, finish) = match self.nodes.get(&id) {
+                Some(node) => (node.parent, node.weight, node.finish),
+                None => return,
+            };
+
+            let start = cmp::max(finish, self.nodes[&parent].vtime);
+            self.node_mut(parent).vtime = start;
+            self.node_mut(id).finish = start + len * STRIDE / weight as u64;
+
+            id = parent;
+        }
+    }
+
+    fn ensure(&mut self, id: StreamId) {
+        if self.nodes.contains_key(&id) {
+            return;
+        }
+
+        let root = self.node_mut(StreamId::ZERO);
+        root.children.push(id);
+        let mut node = Node::new(StreamId::ZERO, DEFAULT_WEIGHT);
+        node.finish = root.vtime;
+
+        self.nodes.insert(id, node);
+    }
+
+    fn is_descendant(&self, id: StreamId, ancestor: StreamId) -> bool {
+        let mut id = id;
+
+        while !id.is_zero() {
+            id = self.nodes[&id].parent;
+            if id == ancestor {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn move_node(&mut self, id: StreamId, parent: StreamId) {
+        let (former_parent, num_ready) = {
+            let node = &self.nodes[&id];
+            (node.parent, node.num_ready)
+        };
+
+        self.add_ready(former_parent, -(num_ready as isize));
+        self.node_mut(former_parent)
+            .children
+            .retain(|&child| child != id);
+
+        let vtime = {
+            let parent = self.node_mut(parent);
+            parent.children.push(id);
+            parent.vtime
+        };
+        let node = self.node_mut(id);
+        node.parent = parent;
+        node.finish = vtime;
+        self.add_ready(parent, num_ready as isize);
+    }
+
+    /// Adds `n` to the ready count of `id` and all the streams it depends on.
+    fn add_ready(&mut self, id: StreamId, n: isize) {
+        let mut id = id;
+
+        loop {
+            let node = self.node_mut(id);
+            node.num_ready = (node.num_ready as isize + n) as usize;
+
+            if id.is_zero() {
+                return;
+            }
+            id = node.parent;
+        }
+    }
+
+    /// Drops the oldest nodes not waiting to send once the tree is too large.
+    fn prune(&mut self) {
+        while self.nodes.len() > MAX_NODES {
+            let id = match self
+                .nodes
+                .iter()
+                .find(|(id, node)| !id.is_zero() && node.ready.is_none())
+            {
+                Some((&id, _)) => id,
+                None => return,
+            };
+
+            self.remove(id);
+        }
+    }
+
+    /// Removes a node, giving its children to its parent with a share of its
+    /// weight (RFC 7540 section 5.3.4).
+    fn remove(&mut self, id: StreamId) {
+        let node = self.nodes.remove(&id).expect("node to remove");
+
+        let total: u32 = node
+            .children
+            .iter()
+            .map(|child| self.nodes[child].weight as u32)
+            .sum();
+
+        for &child in &node.children {
+            let child = self.node_mut(child);
+            child.parent = node.parent;
+            let weight = node.weight as u32 * child.weight as u32 / total;
+            child.weight = cmp::max(weight, 1) as u16;
+        }
+
+        let parent = self.node_mut(node.parent);
+        parent.children.retain(|&child| child != id);
+        parent.children.extend(node.children);
+    }
+
+    fn node_mut(&mut self, id: StreamId) -> &mut Node {
+        self.nodes.get_mut(&id).expect("stream in priority tree")
+    }
+}
+
+impl Node {
+    fn new(parent: StreamId, weight: u16) -> Self {
+        Node {
+            parent,
+            weight,
+            children: Vec::new(),
+            ready: None,
+            num_ready: 0,
+            finish: 0,
+            vtime: 0,
+        }
+    }
+}
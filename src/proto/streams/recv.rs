@@ -7,10 +7,18 @@ use crate::tracing;
 use http::{HeaderMap, Request, Response};
 
 use std::cmp::Ordering;
+use std::collections::VecDeque;
 use std::io;
 use std::task::{Context, Poll, Waker};
 use std::time::Instant;
 
+/// The maximum number of origins kept from the ORIGIN frames of a peer.
+const MAX_ORIGIN_SET_LEN: usize = 1024;
+
+/// The maximum number of PRIORITY_UPDATE frames kept for streams the peer has
+/// not opened yet.
+const MAX_PENDING_PRIORITY_UPDATES: usize = 16;
+
 #[derive(Debug)]
 pub(super) struct Recv {
     /// Initial window size of remote initiated streams
@@ -60,6 +68,14 @@ pub(super) struct Recv {
 
     /// If extended connect protocol is enabled.
     is_extended_connect_protocol_enabled: bool,
+
+    /// The origins advertised by the peer in ORIGIN frames, `None` until the
+    /// first one is received.
+    origin_set: Option<Vec<String>>,
+
+    /// Priorities received in PRIORITY_UPDATE frames for streams that are
+    /// still idle, applied once the streams are opened.
+    pending_priority_updates: VecDeque<(StreamId, frame::ExtensiblePriority)>,
 }
 
 #[derive(Debug)]
@@ -108,6 +124,8 @@ impl Recv {
             refused: None,
             is_push_enabled: config.local_push_enabled,
             is_extended_connect_protocol_enabled: config.extended_connect_protocol_enabled,
+            origin_set: None,
+            pending_priority_updates: VecDeque::new(),
         }
     }
 
@@ -116,6 +134,11 @@ impl Recv {
         self.init_window_sz
     }
 
+    /// Returns the connection level receive window size
+    pub fn connection_window_sz(&self) -> WindowSize {
+        self.flow.window_size()
+    }
+
     /// Returns the ID of the last processed stream
     pub fn last_processed_id(&self) -> StreamId {
         self.last_processed_id
@@ -161,6 +184,7 @@ impl Recv {
     ) -> Result<(), RecvHeaderBlockError<Option<frame::Headers>>> {
         tracing::trace!("opening stream; init_window={}", self.init_window_sz);
         let is_initial = stream.state.recv_open(&frame)?;
+        stream.stats.record_headers_received();
 
         if is_initial {
             // TODO: be smarter about this logic
@@ -234,6 +258,17 @@ impl Recv {
         let stream_id = frame.stream_id();
         let (pseudo, fields) = frame.into_parts();
 
+        // A PRIORITY_UPDATE received before the request takes precedence
+        // over the `priority` header field (RFC 9218 section 7).
+        if is_initial && counts.peer().is_server() {
+            stream.extensible_priority =
+                self.take_pending_priority_update(stream_id).or_else(|| {
+                    fields
+                        .get("priority")
+                        .map(|value| frame::ExtensiblePriority::parse(value.as_bytes()))
+                });
+        }
+
         if pseudo.protocol.is_some()
             && counts.peer().is_server()
             && !self.is_extended_connect_protocol_enabled
@@ -248,10 +283,18 @@ impl Recv {
         }
 
         if !pseudo.is_informational() {
-            let message = counts
+            let mut message = counts
                 .peer()
                 .convert_poll_message(pseudo, fields, stream_id)?;
 
+            // Hand the alternative services advertised for the stream so far
+            // to the user along with the response.
+            if let (peer::PollMessage::Client(ref mut response), Some(alt_svc)) =
+                (&mut message, &stream.alt_svc)
+            {
+                response.extensions_mut().insert(alt_svc.clone());
+            }
+
             // Push the frame onto the stream's recv buffer
             stream
                 .pending_recv
@@ -499,6 +542,84 @@ impl Recv {
         Ok(())
     }
 
+    /// Raises the target connection window to `target`, leaving it as is if
+    /// it is already larger.
+    pub fn grow_target_connection_window(
+        &mut self,
+        target: WindowSize,
+        task: &mut Option<Waker>,
+    ) -> Result<(), Reason> {
+        let current = self
+            .flow
+            .available()
+            .add(self.in_flight_data)?
+            .checked_size();
+        if target <= current {
+            return Ok(());
+        }
+
+        self.set_target_connection_window(target, task)
+    }
+
+    /// Grows the connection window by `size`, for a WINDOW_UPDATE frame that
+    /// was sent without going through `send_connection_window_update`.
+    pub fn inc_connection_window(&mut self, size: WindowSize) -> Result<(), Reason> {
+        tracing::trace!(
+            "inc_connection_window; size={}; window={}",
+            size,
+            self.flow.window_size(),
+        );
+
+        self.flow.inc_window(size)?;
+        self.flow.assign_capacity(size)
+    }
+
+    /// Adds the origins of a received ORIGIN frame to the origin set.
+    pub(crate) fn recv_origin(&mut self, frame: frame::Origin) {
+        let origin_set = self.origin_set.get_or_insert_with(Vec::new);
+
+        for origin in frame.into_origins() {
+            if origin_set.len() >= MAX_ORIGIN_SET_LEN {
+                tracing::debug!("origin set is full; ignoring {}", origin);
+                continue;
+            }
+
+            if !origin_set.contains(&origin) {
+                origin_set.push(origin);
+            }
+        }
+    }
+
+    pub(crate) fn origin_set(&self) -> Option<&[String]> {
+        self.origin_set.as_deref()
+    }
+
+    /// Keeps the priority of a PRIORITY_UPDATE frame for a stream the peer
+    /// has not opened yet.
+    pub(crate) fn buffer_priority_update(&mut self, frame: frame::PriorityUpdate) {
+        let id = frame.prioritized_id();
+        self.pending_priority_updates
+            .retain(|&(pending, _)| pending != id);
+
+        if self.pending_priority_updates.len() >= MAX_PENDING_PRIORITY_UPDATES {
+            tracing::debug!("too many pending PRIORITY_UPDATE frames; dropping oldest");
+            self.pending_priority_updates.pop_front();
+        }
+
+        self.pending_priority_updates
+            .push_back((id, frame.priority()));
+    }
+
+    fn take_pending_priority_update(&mut self, id: StreamId) -> Option<frame::ExtensiblePriority> {
+        let pos = self
+            .pending_priority_updates
+            .iter()
+            .position(|&(pending, _)| pending == id)?;
+        self.pending_priority_updates
+            .remove(pos)
+            .map(|(_, priority)| priority)
+    }
+
     pub(crate) fn apply_local_settings(
         &mut self,
         settings: &frame::Settings,
@@ -654,6 +775,8 @@ impl Recv {
             }
         }
 
+        stream.stats.record_data_received(frame.payload().len());
+
         // Received a frame, but no one cared about it. fix issue#648
         if !stream.is_recv {
             tracing::trace!(
@@ -823,6 +946,11 @@ impl Recv {
             }
         }
 
+        // A closed stream with nothing left to send ignores the reset.
+        if !stream.state.is_closed() || stream.is_pending_send {
+            counts.record_reset(Initiator::Remote, frame.reason());
+        }
+
         // Notify the stream
         stream.state.recv_reset(frame, stream.is_pending_send);
 
@@ -66,6 +66,11 @@ impl Send {
         self.init_window_sz
     }
 
+    /// Returns the connection level send window size
+    pub fn connection_window_sz(&self) -> WindowSize {
+        self.prioritize.connection_window_sz()
+    }
+
     pub fn open(&mut self) -> Result<StreamId, UserError> {
         let stream_id = self.ensure_next_stream_id()?;
         self.next_stream_id = stream_id.next_id();
@@ -182,6 +187,7 @@ impl Send {
         // streams won't be pushed on pending_send.
         self.prioritize
             .queue_frame(headers_frame.into(), buffer, stream, task);
+        stream.stats.record_headers_queued();
 
         // Need to notify the connection when pushing onto pending_open since
         // queue_frame only notifies for pending_send.
@@ -235,6 +241,7 @@ impl Send {
 
         // Transition the state to reset no matter what.
         stream.set_reset(reason, initiator);
+        counts.record_reset(initiator, reason);
 
         // If closed AND the send queue is flushed, then the stream cannot be
         // reset explicitly, either. Implicit resets can still be queued.
@@ -319,6 +326,88 @@ impl Send {
         Ok(())
     }
 
+    pub fn send_extension_frame<B>(
+        &mut self,
+        frame: Frame<B>,
+        buffer: &mut Buffer<Frame<B>>,
+        stream: &mut store::Ptr,
+        task: &mut Option<Waker>,
+    ) -> Result<(), UserError> {
+        if stream.state.is_closed() {
+            return Err(UserError::InactiveStreamId);
+        }
+
+        tracing::trace!("send_extension_frame -- queuing; frame={:?}", frame);
+        self.prioritize.queue_frame(frame, buffer, stream, task);
+
+        Ok(())
+    }
+
+    pub fn reprioritize(&mut self, id: StreamId, dependency: Option<frame::StreamDependency>) {
+        self.prioritize.reprioritize(id, dependency);
+    }
+
+    /// Changes the local scheduling weight of the stream, and its place in
+    /// the dependency tree if responses are scheduled along it.
+    pub fn set_priority(
+        &mut self,
+        dependency: frame::StreamDependency,
+        stream: &mut store::Ptr,
+    ) -> Result<(), UserError> {
+        if stream.state.is_closed() {
+            return Err(UserError::InactiveStreamId);
+        }
+
+        if dependency.dependency_id() == stream.id {
+            return Err(UserError::InvalidStreamDependency);
+        }
+
+        stream.send_weight = dependency.weight() as u16 + 1;
+        self.prioritize.reprioritize(stream.id, Some(dependency));
+
+        Ok(())
+    }
+
+    pub fn send_priority<B>(
+        &mut self,
+        dependency: frame::StreamDependency,
+        buffer: &mut Buffer<Frame<B>>,
+        stream: &mut store::Ptr,
+        task: &mut Option<Waker>,
+    ) -> Result<(), UserError> {
+        self.set_priority(dependency, stream)?;
+
+        let frame = frame::Priority::new(stream.id, dependency);
+        tracing::trace!("send_priority -- queuing; frame={:?}", frame);
+
+        self.prioritize
+            .queue_frame_front(frame.into(), buffer, stream, task);
+
+        Ok(())
+    }
+
+    pub fn send_priority_update<B>(
+        &mut self,
+        priority: frame::ExtensiblePriority,
+        buffer: &mut Buffer<Frame<B>>,
+        stream: &mut store::Ptr,
+        task: &mut Option<Waker>,
+    ) -> Result<(), UserError> {
+        if stream.state.is_closed() {
+            return Err(UserError::InactiveStreamId);
+        }
+
+        let frame = frame::PriorityUpdate::new(stream.id, priority);
+        tracing::trace!("send_priority_update -- queuing; frame={:?}", frame);
+
+        // The new priority matters most while the stream still has frames
+        // to receive, so it skips the request body queued so far.
+        self.prioritize
+            .queue_frame_front(frame.into(), buffer, stream, task);
+
+        Ok(())
+    }
+
     pub fn poll_complete<T, B>(
         &mut self,
         cx: &mut Context,
@@ -1,3 +1,4 @@
+use crate::stats::StreamStats;
 use crate::{tracing, Reason};
 
 use super::*;
@@ -6,6 +7,9 @@ use std::fmt;
 use std::task::{Context, Waker};
 use std::time::Instant;
 
+/// The weight of a stream without a priority, see RFC 7540 section 5.3.5.
+pub(super) const DEFAULT_WEIGHT: u16 = 16;
+
 /// Tracks Stream related state
 ///
 /// # Reference counting
@@ -114,6 +118,22 @@ pub(super) struct Stream {
 
     /// Validate content-length headers
     pub content_length: ContentLength,
+
+    /// The last ALTSVC frame received on this stream
+    pub alt_svc: Option<frame::AltSvc>,
+
+    /// The RFC 9218 priority signalled by the peer for the response, if any
+    pub extensible_priority: Option<frame::ExtensiblePriority>,
+
+    /// Weight of the stream when streams take turns sending DATA, from 1 to
+    /// 256
+    pub send_weight: u16,
+
+    /// Bytes of DATA the stream may still send in its current turn
+    pub send_deficit: usize,
+
+    /// Timings and byte counts reported by the stream handles
+    pub stats: StreamStats,
 }
 
 /// State related to validating a stream's content-length
@@ -194,6 +214,11 @@ impl Stream {
             push_task: None,
             pending_push_promises: store::Queue::new(),
             content_length: ContentLength::Omitted,
+            alt_svc: None,
+            extensible_priority: None,
+            send_weight: DEFAULT_WEIGHT,
+            send_deficit: 0,
+            stats: StreamStats::default(),
         }
     }
 
@@ -300,6 +325,13 @@ impl Stream {
         }
     }
 
+    /// Updates the time the stream waits for send capacity, after the
+    /// requested or assigned capacity changed.
+    pub fn update_send_blocked(&mut self) {
+        let blocked = self.send_flow.available() < self.requested_send_capacity as usize;
+        self.stats.set_send_blocked(blocked);
+    }
+
     pub fn send_data(&mut self, len: WindowSize, max_buffer_size: usize) {
         let prev_capacity = self.capacity(max_buffer_size);
 
@@ -440,6 +472,8 @@ impl fmt::Debug for Stream {
                 &self.pending_push_promises,
             )
             .field("content_length", &self.content_length)
+            .h2_field_some("extensible_priority", &self.extensible_priority)
+            .field("send_weight", &self.send_weight)
             .finish()
     }
 }
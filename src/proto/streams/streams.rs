@@ -9,7 +9,7 @@ use http::{HeaderMap, Request, Response};
 use tokio::io::AsyncWrite;
 
 use super::{
-    frame::{Priorities, PseudoOrder, StreamDependency},
+    frame::{HeaderOrder, Priorities, PseudoOrder, StreamDependency},
     recv::RecvHeaderBlockError,
     store::{self, Entry, Resolve, Store},
     sync::Mutex,
@@ -22,7 +22,9 @@ use crate::{
     frame::{self, Frame, Reason},
     proto,
     proto::{peer, Error, Initiator, Open, Peer, WindowSize},
-    server, tracing,
+    server,
+    stats::{ConnectionStats, StreamStats},
+    tracing,
 };
 
 #[derive(Debug)]
@@ -95,6 +97,9 @@ struct Inner {
     /// Pseudo order of the headers stream
     headers_pseudo_order: Option<PseudoOrder>,
 
+    /// Regular field order of the headers stream
+    headers_order: Option<HeaderOrder>,
+
     /// Priority of the headers stream
     priorities: Option<Priorities>,
 }
@@ -147,6 +152,20 @@ where
             .set_target_connection_window(size, &mut me.actions.task)
     }
 
+    pub fn grow_target_connection_window_size(&mut self, size: WindowSize) -> Result<(), Reason> {
+        let mut me = self.inner.lock();
+        let me = &mut *me;
+
+        me.actions
+            .recv
+            .grow_target_connection_window(size, &mut me.actions.task)
+    }
+
+    pub fn inc_connection_window(&mut self, size: WindowSize) -> Result<(), Reason> {
+        let mut me = self.inner.lock();
+        me.actions.recv.inc_connection_window(size)
+    }
+
     pub fn next_incoming(&mut self) -> Option<StreamRef<B>> {
         let mut me = self.inner.lock();
         let me = &mut *me;
@@ -246,6 +265,10 @@ where
         use super::stream::ContentLength;
 
         let protocol = request.extensions_mut().remove::<Protocol>();
+        let headers_order = request.extensions_mut().remove::<HeaderOrder>();
+        let pseudo_order = request.extensions_mut().remove::<PseudoOrder>();
+        let stream_dependency = request.extensions_mut().remove::<StreamDependency>();
+        let priorities = request.extensions_mut().remove::<Priorities>();
 
         // Clear before taking lock, incase extensions contain a StreamRef.
         request.extensions_mut().clear();
@@ -293,8 +316,13 @@ where
             stream.content_length = ContentLength::Head;
         }
 
+        // Per-request settings take precedence over the connection defaults.
+        let pseudo_order = pseudo_order.or_else(|| me.headers_pseudo_order.clone());
+        let stream_dependency = stream_dependency.or(me.headers_stream_dependency);
+        let priorities = priorities.or_else(|| me.priorities.clone());
+
         // Priorities frame check before sending the request.
-        if let Some(priorities) = &me.priorities {
+        if let Some(priorities) = &priorities {
             let next_id = priorities
                 .max_stream_id()
                 .next_id()
@@ -311,14 +339,15 @@ where
             request,
             protocol,
             end_of_stream,
-            me.headers_pseudo_order.clone(),
-            me.headers_stream_dependency,
+            pseudo_order,
+            headers_order.as_ref().or(me.headers_order.as_ref()),
+            stream_dependency,
         )?;
 
         let mut stream = me.store.insert(stream.id, stream);
 
         let sent = me.actions.send.send_priority_and_headers(
-            me.priorities.clone(),
+            priorities,
             headers,
             send_buffer,
             &mut stream,
@@ -360,6 +389,15 @@ where
             .is_extended_connect_protocol_enabled()
     }
 
+    pub(crate) fn origin_set(&self) -> Option<Vec<String>> {
+        self.inner
+            .lock()
+            .actions
+            .recv
+            .origin_set()
+            .map(<[_]>::to_vec)
+    }
+
     pub fn current_max_send_streams(&self) -> usize {
         let me = self.inner.lock();
         me.counts.max_send_streams()
@@ -408,6 +446,60 @@ impl<B> DynStreams<'_, B> {
         me.recv_go_away(self.send_buffer, frame)
     }
 
+    pub fn recv_origin(&mut self, frame: frame::Origin) {
+        self.inner.lock().actions.recv.recv_origin(frame)
+    }
+
+    /// Attaches a received ALTSVC frame to the stream it was sent on.
+    pub fn recv_alt_svc(&mut self, frame: frame::AltSvc) {
+        let mut me = self.inner.lock();
+
+        if let Some(mut stream) = me.store.find_mut(&frame.stream_id()) {
+            stream.alt_svc = Some(frame);
+        } else {
+            tracing::trace!("ignoring ALTSVC for unknown stream; frame={:?}", frame);
+        }
+    }
+
+    pub fn recv_priority(&mut self, frame: frame::Priority) {
+        let mut me = self.inner.lock();
+        let me = &mut *me;
+        let dependency = frame.dependency();
+
+        if let Some(mut stream) = me.store.find_mut(&frame.stream_id()) {
+            stream.send_weight = dependency.weight() as u16 + 1;
+        }
+
+        me.actions
+            .send
+            .reprioritize(frame.stream_id(), Some(dependency));
+    }
+
+    /// Applies a received PRIORITY_UPDATE frame to the stream it refers to.
+    pub fn recv_priority_update(&mut self, frame: frame::PriorityUpdate) -> Result<(), Error> {
+        if !self.peer.is_server() {
+            proto_err!(conn: "recv_priority_update: client received PRIORITY_UPDATE");
+            return Err(Error::library_go_away(Reason::PROTOCOL_ERROR));
+        }
+
+        let mut me = self.inner.lock();
+        let me = &mut *me;
+        let id = frame.prioritized_id();
+
+        if let Some(mut stream) = me.store.find_mut(&id) {
+            stream.extensible_priority = Some(frame.priority());
+        } else if !self.peer.is_local_init(id) && !me.actions.recv.may_have_created_stream(id) {
+            me.actions.recv.buffer_priority_update(frame);
+        } else {
+            tracing::trace!(
+                "ignoring PRIORITY_UPDATE for closed stream; frame={:?}",
+                frame
+            );
+        }
+
+        Ok(())
+    }
+
     pub fn last_processed_id(&self) -> StreamId {
         self.inner.lock().actions.recv.last_processed_id()
     }
@@ -456,6 +548,7 @@ impl Inner {
             refs: 1,
             headers_stream_dependency: config.headers_stream_dependency,
             headers_pseudo_order: config.headers_pseudo_order,
+            headers_order: config.headers_order,
             priorities: config.priorities,
         }))
     }
@@ -518,7 +611,14 @@ impl Inner {
             }
         };
 
-        let stream = self.store.resolve(key);
+        let dependency = frame.stream_dependency();
+        self.actions.send.reprioritize(id, dependency);
+
+        let mut stream = self.store.resolve(key);
+
+        if let Some(dependency) = dependency {
+            stream.send_weight = dependency.weight() as u16 + 1;
+        }
 
         if stream.state.is_local_error() {
             // Locally reset streams must ignore frames "for some time".
@@ -1072,6 +1172,13 @@ where
         me.store.num_active_streams()
     }
 
+    pub(crate) fn fill_stats(&self, stats: &mut ConnectionStats) {
+        let me = self.inner.lock();
+        me.counts.fill_stats(stats);
+        stats.send_window = me.actions.send.connection_window_sz();
+        stats.recv_window = me.actions.recv.connection_window_sz();
+    }
+
     pub fn has_streams(&self) -> bool {
         let me = self.inner.lock();
         me.counts.has_streams()
@@ -1166,6 +1273,96 @@ impl<B> StreamRef<B> {
         })
     }
 
+    pub fn send_extension_frame(
+        &mut self,
+        kind: u8,
+        flags: u8,
+        payload: Bytes,
+    ) -> Result<(), UserError> {
+        let mut me = self.opaque.inner.lock();
+        let me = &mut *me;
+
+        let mut stream = me.store.resolve(self.opaque.key);
+        let actions = &mut me.actions;
+        let mut send_buffer = self.send_buffer.inner.lock();
+        let send_buffer = &mut *send_buffer;
+
+        let frame = frame::ExtensionFrame::new(kind, flags, stream.id, payload);
+        frame.validate()?;
+
+        actions
+            .send
+            .send_extension_frame(frame.into(), send_buffer, &mut stream, &mut actions.task)
+    }
+
+    pub fn send_alt_svc(&mut self, field_value: Bytes) -> Result<(), UserError> {
+        let mut me = self.opaque.inner.lock();
+        let me = &mut *me;
+
+        let mut stream = me.store.resolve(self.opaque.key);
+        let actions = &mut me.actions;
+        let mut send_buffer = self.send_buffer.inner.lock();
+        let send_buffer = &mut *send_buffer;
+
+        let frame = frame::AltSvc::new(stream.id, Bytes::new(), field_value);
+        if frame.payload_len() > frame::DEFAULT_MAX_FRAME_SIZE as usize {
+            return Err(UserError::PayloadTooBig);
+        }
+
+        actions
+            .send
+            .send_extension_frame(frame.into(), send_buffer, &mut stream, &mut actions.task)
+    }
+
+    pub fn set_priority(&mut self, dependency: frame::StreamDependency) -> Result<(), UserError> {
+        let mut me = self.opaque.inner.lock();
+        let me = &mut *me;
+
+        let mut stream = me.store.resolve(self.opaque.key);
+        me.actions.send.set_priority(dependency, &mut stream)
+    }
+
+    pub fn send_priority(&mut self, dependency: frame::StreamDependency) -> Result<(), UserError> {
+        let mut me = self.opaque.inner.lock();
+        let me = &mut *me;
+
+        // Only clients tell their peer about the priority of their streams.
+        if me.counts.peer().is_server() {
+            return Err(UserError::UnexpectedFrameType);
+        }
+
+        let mut stream = me.store.resolve(self.opaque.key);
+        let actions = &mut me.actions;
+        let mut send_buffer = self.send_buffer.inner.lock();
+        let send_buffer = &mut *send_buffer;
+
+        actions
+            .send
+            .send_priority(dependency, send_buffer, &mut stream, &mut actions.task)
+    }
+
+    pub fn send_priority_update(
+        &mut self,
+        priority: frame::ExtensiblePriority,
+    ) -> Result<(), UserError> {
+        let mut me = self.opaque.inner.lock();
+        let me = &mut *me;
+
+        // Only clients send PRIORITY_UPDATE frames (RFC 9218 section 7).
+        if me.counts.peer().is_server() {
+            return Err(UserError::UnexpectedFrameType);
+        }
+
+        let mut stream = me.store.resolve(self.opaque.key);
+        let actions = &mut me.actions;
+        let mut send_buffer = self.send_buffer.inner.lock();
+        let send_buffer = &mut *send_buffer;
+
+        actions
+            .send
+            .send_priority_update(priority, send_buffer, &mut stream, &mut actions.task)
+    }
+
     pub fn send_reset(&mut self, reason: Reason) {
         let mut me = self.opaque.inner.lock();
         let me = &mut *me;
@@ -1206,7 +1403,13 @@ impl<B> StreamRef<B> {
         let send_buffer = &mut *send_buffer;
 
         me.counts.transition(stream, |counts, stream| {
-            let frame = server::Peer::convert_send_message(stream.id, response, end_of_stream);
+            let frame = server::Peer::convert_send_message(
+                stream.id,
+                response,
+                end_of_stream,
+                me.headers_pseudo_order.clone(),
+                me.headers_order.as_ref(),
+            );
 
             actions
                 .send
@@ -1246,7 +1449,13 @@ impl<B> StreamRef<B> {
         let pushed = {
             let mut stream = me.store.resolve(self.opaque.key);
 
-            let frame = crate::server::Peer::convert_push_message(stream.id, promised_id, request)?;
+            let frame = crate::server::Peer::convert_push_message(
+                stream.id,
+                promised_id,
+                request,
+                me.headers_pseudo_order.clone(),
+                me.headers_order.as_ref(),
+            )?;
 
             actions
                 .send
@@ -1344,6 +1553,10 @@ impl<B> StreamRef<B> {
     pub fn stream_id(&self) -> StreamId {
         self.opaque.stream_id()
     }
+
+    pub fn stats(&self) -> StreamStats {
+        self.opaque.stats()
+    }
 }
 
 impl<B> Clone for StreamRef<B> {
@@ -1463,6 +1676,16 @@ impl OpaqueStreamRef {
     pub fn stream_id(&self) -> StreamId {
         self.inner.lock().store[self.key].id
     }
+
+    /// Returns the last ALTSVC frame received on the stream.
+    pub fn alt_svc(&self) -> Option<frame::AltSvc> {
+        self.inner.lock().store[self.key].alt_svc.clone()
+    }
+
+    /// Returns a snapshot of the stream statistics.
+    pub fn stats(&self) -> StreamStats {
+        self.inner.lock().store[self.key].stats.snapshot()
+    }
 }
 
 impl fmt::Debug for OpaqueStreamRef {
@@ -116,14 +116,22 @@
 //! [`TcpListener`]: https://docs.rs/tokio-core/0.1/tokio_core/net/struct.TcpListener.html
 
 use crate::codec::{Codec, UserError};
-use crate::frame::{self, Pseudo, PushPromiseHeaderError, Reason, Settings, StreamId};
-use crate::proto::{self, Config, Error, Prioritized};
-use crate::{tracing, FlowControl, PingPong, RecvStream, SendStream};
+#[cfg(feature = "unstable")]
+use crate::frame::ExperimentalSettings;
+use crate::frame::{
+    self, ExtensionFrame, HeaderOrder, PaddingPolicy, Pseudo, PseudoOrder, PushPromiseHeaderError,
+    Reason, Settings, SettingsOrder, StreamDependency, StreamId,
+};
+use crate::hpack::{HuffmanPolicy, IndexingPolicy, SharedIndexingPolicy};
+use crate::observer::{FrameObserver, SharedFrameObserver};
+use crate::proto::{self, Config, Error, Prioritized, SchedulingStrategy};
+use crate::stats::{ConnectionStats, StreamStats};
+use crate::{tracing, ExtensionFrames, Fingerprint, FlowControl, PingPong, RecvStream, SendStream};
 
 #[cfg(feature = "tracing")]
 use ::tracing::instrument::{Instrument, Instrumented};
 use bytes::{Buf, Bytes};
-use http::{HeaderMap, Method, Request, Response};
+use http::{HeaderMap, HeaderValue, Method, Request, Response};
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
@@ -252,6 +260,21 @@ pub struct Builder {
     /// Initial target window size for new connections.
     initial_target_connection_window_size: Option<u32>,
 
+    /// Whether receive windows grow with the bandwidth-delay product.
+    adaptive_window: bool,
+
+    /// The largest size adaptive receive windows grow to.
+    max_adaptive_window_size: u32,
+
+    /// How long to wait without receiving a frame before sending a PING.
+    keep_alive_interval: Option<Duration>,
+
+    /// How long to wait for a keep-alive PING to be acknowledged.
+    keep_alive_timeout: Duration,
+
+    /// Whether keep-alive PINGs are sent while there are no streams.
+    keep_alive_while_idle: bool,
+
     /// Maximum amount of bytes to "buffer" for writing per stream.
     max_send_buffer_size: usize,
 
@@ -260,6 +283,33 @@ pub struct Builder {
     ///
     /// When this gets exceeded, we issue GOAWAYs.
     local_max_error_reset_streams: Option<usize>,
+
+    /// The order of pseudo-header fields in outgoing HEADERS and PUSH_PROMISE
+    /// frames.
+    headers_pseudo_order: Option<PseudoOrder>,
+
+    /// The order of regular header fields in outgoing HEADERS and
+    /// PUSH_PROMISE frames.
+    headers_order: Option<HeaderOrder>,
+
+    /// Decides how outgoing header fields are indexed by HPACK.
+    indexing_policy: Option<SharedIndexingPolicy>,
+
+    /// Decides when outgoing header literals are Huffman encoded.
+    huffman_policy: Option<HuffmanPolicy>,
+
+    /// How outgoing DATA, HEADERS and PUSH_PROMISE frames are padded.
+    padding_policy: PaddingPolicy,
+
+    /// Called with every frame read and written.
+    frame_observer: Option<SharedFrameObserver>,
+
+    /// Whether responses are scheduled along the client's RFC 7540
+    /// dependency tree.
+    rfc7540_priorities: bool,
+
+    /// How the DATA frames of concurrent streams share the connection.
+    scheduling_strategy: SchedulingStrategy,
 }
 
 /// Send a response back to the client
@@ -403,6 +453,22 @@ where
             codec.set_max_recv_header_list_size(max as usize);
         }
 
+        if let Some(policy) = builder.indexing_policy.clone() {
+            codec.set_send_indexing_policy(policy);
+        }
+
+        if let Some(policy) = builder.huffman_policy {
+            codec.set_send_huffman_policy(policy);
+        }
+
+        if builder.padding_policy != PaddingPolicy::None {
+            codec.set_send_padding_policy(builder.padding_policy);
+        }
+
+        if let Some(observer) = builder.frame_observer.clone() {
+            codec.set_frame_observer(observer);
+        }
+
         // Send initial settings frame.
         codec
             .buffer(builder.settings.clone().into())
@@ -570,6 +636,121 @@ where
         self.connection.take_user_pings().map(PingPong::new)
     }
 
+    /// Returns the smoothed round trip time of the connection, or `None` if
+    /// no PING was acknowledged yet.
+    ///
+    /// The round trip time is measured from the PINGs sent with a
+    /// [`PingPong`], for keep-alive, and to size adaptive windows. It is
+    /// smoothed as TCP does, see [RFC 6298][1].
+    ///
+    /// [1]: https://datatracker.ietf.org/doc/html/rfc6298#section-2
+    pub fn smoothed_rtt(&self) -> Option<Duration> {
+        self.connection.smoothed_rtt()
+    }
+
+    /// Returns the smallest round trip time measured on the connection, or
+    /// `None` if no PING was acknowledged yet.
+    pub fn min_rtt(&self) -> Option<Duration> {
+        self.connection.min_rtt()
+    }
+
+    /// Returns a snapshot of the connection statistics: the frames and bytes
+    /// sent and received, the streams opened, closed and reset, the
+    /// connection level flow control windows, and the HPACK dynamic tables.
+    ///
+    /// The snapshot is not updated afterwards, call `stats` again to see the
+    /// connection progress.
+    pub fn stats(&self) -> ConnectionStats {
+        self.connection.stats()
+    }
+
+    /// Takes an `ExtensionFrames` instance from the connection, to receive
+    /// the extension frames sent by the peer.
+    ///
+    /// Extension frames received before this is called are dropped.
+    ///
+    /// # Note
+    ///
+    /// This may only be called once. Calling multiple times will return `None`.
+    pub fn extension_frames(&mut self) -> Option<ExtensionFrames> {
+        self.connection
+            .take_user_extensions()
+            .map(ExtensionFrames::new)
+    }
+
+    /// Sends an extension frame to the peer.
+    ///
+    /// The frame is written the next time the connection is polled, ahead of
+    /// any queued stream frame. Use [`SendStream::send_extension_frame`] for
+    /// frames that must follow the data already sent on a stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the type of `frame` is known to the library or if
+    /// its payload is larger than 16,384 bytes.
+    pub fn send_extension_frame(&mut self, frame: ExtensionFrame) -> Result<(), crate::Error> {
+        frame.validate()?;
+        self.connection.send_extension_frame(frame);
+        Ok(())
+    }
+
+    /// Sends an [ORIGIN frame][1], advertising the origins the server is
+    /// authoritative for.
+    ///
+    /// Each origin is the ASCII serialization of an origin, such as
+    /// `https://example.com` or `https://example.com:8443`. Clients add the
+    /// origins of every ORIGIN frame they receive to the connection's origin
+    /// set, so calling this again advertises more origins; it cannot take
+    /// back origins already sent.
+    ///
+    /// The frame is written the next time the connection is polled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if one of `origins` is not an origin or if the frame
+    /// would be larger than 16,384 bytes. No frame is sent in that case.
+    ///
+    /// [1]: https://datatracker.ietf.org/doc/html/rfc8336
+    pub fn send_origin_set<I>(&mut self, origins: I) -> Result<(), crate::Error>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut frame = frame::Origin::new();
+        for origin in origins {
+            frame.push(origin.as_ref())?;
+        }
+
+        self.connection.send_origin(frame);
+        Ok(())
+    }
+
+    /// Sends an [ALTSVC frame][1] advertising alternative services for
+    /// `origin`, such as `https://example.com`.
+    ///
+    /// `field_value` uses the syntax of the `Alt-Svc` header field, for
+    /// instance `h3=":443"; ma=3600`. Use [`SendResponse::send_alt_svc`] to
+    /// advertise alternative services for the origin of a request instead.
+    ///
+    /// The frame is written the next time the connection is polled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `origin` is not an origin or if the frame would
+    /// be larger than 16,384 bytes.
+    ///
+    /// [1]: https://datatracker.ietf.org/doc/html/rfc7838#section-4
+    pub fn send_alt_svc(
+        &mut self,
+        origin: &str,
+        field_value: HeaderValue,
+    ) -> Result<(), crate::Error> {
+        let value = Bytes::copy_from_slice(field_value.as_bytes());
+        let frame = frame::AltSvc::for_origin(origin, value)?;
+        self.connection.send_alt_svc(frame);
+        Ok(())
+    }
+
     /// Checks if there are any streams
     pub fn has_streams(&self) -> bool {
         self.connection.has_streams()
@@ -603,6 +784,37 @@ where
         self.connection.max_recv_streams()
     }
 
+    /// Returns the HTTP/2 fingerprint of the client.
+    ///
+    /// The fingerprint is filled in as the corresponding frames are received:
+    /// the client's first SETTINGS frame and connection-level WINDOW_UPDATE,
+    /// the PRIORITY frames received before the first request, and the
+    /// pseudo-header order of the first request. It is complete once the
+    /// first request has been accepted.
+    ///
+    /// Use `to_string()` to render it in the Akamai fingerprint format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::server;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T) {
+    /// let mut connection = server::handshake(my_io).await.unwrap();
+    ///
+    /// if let Some(Ok(_request)) = connection.accept().await {
+    ///     // e.g. "1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p"
+    ///     println!("{}", connection.peer_fingerprint());
+    /// }
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn peer_fingerprint(&self) -> &Fingerprint {
+        self.connection.peer_fingerprint()
+    }
+
     // Could disappear at anytime.
     #[doc(hidden)]
     #[cfg(feature = "unstable")]
@@ -671,9 +883,22 @@ impl Builder {
             pending_accept_reset_stream_max: proto::DEFAULT_REMOTE_RESET_STREAM_MAX,
             settings: Settings::default(),
             initial_target_connection_window_size: None,
+            adaptive_window: false,
+            max_adaptive_window_size: proto::DEFAULT_MAX_ADAPTIVE_WINDOW_SIZE,
+            keep_alive_interval: None,
+            keep_alive_timeout: Duration::from_secs(proto::DEFAULT_KEEP_ALIVE_TIMEOUT_SECS),
+            keep_alive_while_idle: false,
             max_send_buffer_size: proto::DEFAULT_MAX_SEND_BUFFER_SIZE,
 
             local_max_error_reset_streams: Some(proto::DEFAULT_LOCAL_RESET_COUNT_MAX),
+            headers_pseudo_order: None,
+            headers_order: None,
+            indexing_policy: None,
+            huffman_policy: None,
+            padding_policy: PaddingPolicy::None,
+            frame_observer: None,
+            rfc7540_priorities: false,
+            scheduling_strategy: SchedulingStrategy::Fifo,
         }
     }
 
@@ -745,6 +970,128 @@ impl Builder {
         self
     }
 
+    /// Enables adaptive flow control.
+    ///
+    /// The connection sends a PING while DATA is being received, and
+    /// estimates the bandwidth-delay product of the link from the DATA received
+    /// before the PING is acknowledged. When the estimate shows that the
+    /// receive windows limit throughput, the initial window size of streams
+    /// and the target connection window grow to twice the bytes received, up
+    /// to [`max_adaptive_window_size`]. Windows never shrink below the
+    /// configured sizes.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::server::*;
+    /// #
+    /// # fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Handshake<T>
+    /// # {
+    /// // `server_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let server_fut = Builder::new()
+    ///     .adaptive_window(true)
+    ///     .max_adaptive_window_size(8 * 1024 * 1024)
+    ///     .handshake(my_io);
+    /// # server_fut
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`max_adaptive_window_size`]: Builder::max_adaptive_window_size
+    pub fn adaptive_window(&mut self, enabled: bool) -> &mut Self {
+        self.adaptive_window = enabled;
+        self
+    }
+
+    /// Sets the largest size (in octets) receive windows grow to when
+    /// [`adaptive_window`] is enabled.
+    ///
+    /// The default value is 16 MB.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `max` is larger than 2^31-1.
+    ///
+    /// [`adaptive_window`]: Builder::adaptive_window
+    pub fn max_adaptive_window_size(&mut self, max: u32) -> &mut Self {
+        assert!(max <= proto::MAX_WINDOW_SIZE);
+        self.max_adaptive_window_size = max;
+        self
+    }
+
+    /// Sets the interval at which keep-alive PINGs are sent.
+    ///
+    /// Once `interval` passes without any frame received, the connection
+    /// sends a PING. If it is not acknowledged within the
+    /// [`keep_alive_timeout`], the connection is considered dead: it is
+    /// closed, and it and its streams fail with an error for which
+    /// [`Error::is_keep_alive_timeout`] returns true.
+    ///
+    /// The connection must be polled within a Tokio runtime that has the
+    /// time driver enabled.
+    ///
+    /// Keep-alive is disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::server::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Handshake<T>
+    /// # {
+    /// // `server_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let server_fut = Builder::new()
+    ///     .keep_alive_interval(Duration::from_secs(30))
+    ///     .keep_alive_timeout(Duration::from_secs(10))
+    ///     .handshake(my_io);
+    /// # server_fut
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`keep_alive_timeout`]: Builder::keep_alive_timeout
+    /// [`Error::is_keep_alive_timeout`]: crate::Error::is_keep_alive_timeout
+    pub fn keep_alive_interval(&mut self, interval: impl Into<Option<Duration>>) -> &mut Self {
+        self.keep_alive_interval = interval.into();
+        self
+    }
+
+    /// Sets how long to wait for a keep-alive PING to be acknowledged before
+    /// closing the connection.
+    ///
+    /// Only used if [`keep_alive_interval`] is set. The default value is 20
+    /// seconds.
+    ///
+    /// [`keep_alive_interval`]: Builder::keep_alive_interval
+    pub fn keep_alive_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.keep_alive_timeout = timeout;
+        self
+    }
+
+    /// Sets whether keep-alive PINGs are sent while the connection has no
+    /// open streams.
+    ///
+    /// When disabled, an idle connection is only checked once a new stream
+    /// is opened. Only used if [`keep_alive_interval`] is set. The default
+    /// value is `false`.
+    ///
+    /// [`keep_alive_interval`]: Builder::keep_alive_interval
+    pub fn keep_alive_while_idle(&mut self, enabled: bool) -> &mut Self {
+        self.keep_alive_while_idle = enabled;
+        self
+    }
+
     /// Indicates the size (in octets) of the largest HTTP/2 frame payload that the
     /// configured server is able to accept.
     ///
@@ -1045,6 +1392,333 @@ impl Builder {
         self
     }
 
+    /// Sets the header table size.
+    ///
+    /// This setting informs the peer of the maximum size of the header compression
+    /// table used to encode header blocks, in octets. The encoder may select any value
+    /// equal to or less than the header table size specified by the sender.
+    ///
+    /// The default value is 4,096.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::server::*;
+    /// #
+    /// # fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Handshake<T>
+    /// # {
+    /// // `server_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let server_fut = Builder::new()
+    ///     .header_table_size(65_536)
+    ///     .handshake(my_io);
+    /// # server_fut
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn header_table_size(&mut self, size: u32) -> &mut Self {
+        self.settings.set_header_table_size(Some(size));
+        self
+    }
+
+    /// Disable RFC 7540 Stream Priorities (set to `true` to disable).
+    /// [RFC 9218]: <https://www.rfc-editor.org/rfc/rfc9218.html#section-2.1>
+    pub fn no_rfc7540_priorities(&mut self, enabled: bool) -> &mut Self {
+        self.settings.set_no_rfc7540_priorities(enabled);
+        self
+    }
+
+    /// Schedules responses according to the priorities the client signals
+    /// with [RFC 7540] PRIORITY frames and HEADERS priority fields.
+    ///
+    /// The server keeps the dependency tree the client builds. A response is
+    /// sent before the responses of the streams that depend on it, and
+    /// streams that depend on the same stream share the connection in
+    /// proportion to their weights. This replaces the scheduling by
+    /// extensible priorities (RFC 9218), so the `priority` header field and
+    /// PRIORITY_UPDATE frames have no effect on the order of frames.
+    ///
+    /// By default, PRIORITY frames are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::server::*;
+    /// #
+    /// # fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Handshake<T>
+    /// # {
+    /// // `server_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let server_fut = Builder::new()
+    ///     .enable_rfc7540_priorities()
+    ///     .handshake(my_io);
+    /// # server_fut
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [RFC 7540]: https://datatracker.ietf.org/doc/html/rfc7540#section-5.3
+    pub fn enable_rfc7540_priorities(&mut self) -> &mut Self {
+        self.rfc7540_priorities = true;
+        self
+    }
+
+    /// Configures custom experimental HTTP/2 setting.
+    ///
+    /// This setting is reserved for future use or experimental purposes.
+    /// Enabling or disabling it may have no effect unless explicitly supported
+    /// by the server or client implementation.
+    //
+    // - Experimental feature – subject to removal without notice
+    #[cfg(feature = "unstable")]
+    pub fn experimental_settings(
+        &mut self,
+        experimental_settings: ExperimentalSettings,
+    ) -> &mut Self {
+        self.settings
+            .set_experimental_settings(experimental_settings);
+        self
+    }
+
+    /// Sets the order of settings parameters in the initial SETTINGS frame.
+    ///
+    /// This determines the order in which settings are sent during the HTTP/2 handshake.
+    /// Customizing the order may be useful for testing or protocol compliance.
+    pub fn settings_order(&mut self, order: SettingsOrder) -> &mut Self {
+        self.settings.set_settings_order(order);
+        self
+    }
+
+    /// Sets the HTTP/2 pseudo-header field order for outgoing HEADERS and
+    /// PUSH_PROMISE frames.
+    ///
+    /// Responses only carry `:status`, so this mostly affects the promised
+    /// requests of PUSH_PROMISE frames.
+    pub fn headers_pseudo_order(&mut self, order: PseudoOrder) -> &mut Self {
+        self.headers_pseudo_order = Some(order);
+        self
+    }
+
+    /// Sets the order of regular header fields for outgoing HEADERS and
+    /// PUSH_PROMISE frames.
+    ///
+    /// Fields named in `order` are encoded first, in the given order, and any
+    /// remaining fields follow in their `HeaderMap` iteration order. Trailers
+    /// are not affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::server::*;
+    /// # use http2::ext::HeaderOrder;
+    /// # use http::header;
+    /// #
+    /// # fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Handshake<T>
+    /// # {
+    /// // `content-type` is always encoded before `content-length`.
+    /// let server_fut = Builder::new()
+    ///     .headers_order(
+    ///         HeaderOrder::builder()
+    ///             .push(header::CONTENT_TYPE)
+    ///             .push(header::CONTENT_LENGTH)
+    ///             .build(),
+    ///     )
+    ///     .handshake(my_io);
+    /// # server_fut
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn headers_order(&mut self, order: HeaderOrder) -> &mut Self {
+        self.headers_order = Some(order);
+        self
+    }
+
+    /// Sets the policy deciding how outgoing header fields are represented by
+    /// HPACK.
+    ///
+    /// For every field of a HEADERS or PUSH_PROMISE frame, pseudo-headers
+    /// included, the policy picks between incremental indexing, no indexing
+    /// and never indexing. By default the encoder decides on its own. See
+    /// [`IndexingPolicy`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::server::*;
+    /// # use http2::ext::Indexing;
+    /// #
+    /// # fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Handshake<T>
+    /// # {
+    /// // Cookies are never indexed.
+    /// let server_fut = Builder::new()
+    ///     .indexing_policy(|name: &str, _: &[u8]| match name {
+    ///         "set-cookie" => Indexing::NeverIndexed,
+    ///         _ => Indexing::Auto,
+    ///     })
+    ///     .handshake(my_io);
+    /// # server_fut
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`IndexingPolicy`]: crate::ext::IndexingPolicy
+    pub fn indexing_policy<P: IndexingPolicy>(&mut self, policy: P) -> &mut Self {
+        self.indexing_policy = Some(SharedIndexingPolicy::new(policy));
+        self
+    }
+
+    /// Sets when string literals in outgoing header blocks are Huffman
+    /// encoded.
+    ///
+    /// By default every literal is Huffman encoded, even when that makes it
+    /// longer than the raw bytes. [`HuffmanPolicy::Shortest`] picks whichever
+    /// form is smaller, as nghttp2 does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::server::*;
+    /// # use http2::ext::HuffmanPolicy;
+    /// #
+    /// # fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Handshake<T>
+    /// # {
+    /// let server_fut = Builder::new()
+    ///     .huffman_policy(HuffmanPolicy::Shortest)
+    ///     .handshake(my_io);
+    /// # server_fut
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`HuffmanPolicy::Shortest`]: crate::ext::HuffmanPolicy::Shortest
+    pub fn huffman_policy(&mut self, policy: HuffmanPolicy) -> &mut Self {
+        self.huffman_policy = Some(policy);
+        self
+    }
+
+    /// Sets how outgoing HEADERS, PUSH_PROMISE and DATA frames are padded.
+    ///
+    /// Frames are not padded by default. Padding sent in DATA frames counts
+    /// against flow control, and is reduced when the windows or the peer's
+    /// maximum frame size leave no room for it. See [`PaddingPolicy`] for
+    /// details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::server::*;
+    /// # use http2::ext::PaddingPolicy;
+    /// #
+    /// # fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Handshake<T>
+    /// # {
+    /// // Pad every frame payload to a multiple of 64 bytes.
+    /// let server_fut = Builder::new()
+    ///     .padding_policy(PaddingPolicy::Block(64))
+    ///     .handshake(my_io);
+    /// # server_fut
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`PaddingPolicy`]: crate::ext::PaddingPolicy
+    pub fn padding_policy(&mut self, policy: PaddingPolicy) -> &mut Self {
+        self.padding_policy = policy;
+        self
+    }
+
+    /// Sets an observer called with every frame read from and written to the
+    /// connection.
+    ///
+    /// See [`FrameObserver`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::server::*;
+    /// # use http2::ext::{FrameObserver, ObservedFrame};
+    /// #
+    /// # fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Handshake<T>
+    /// # {
+    /// struct Trace;
+    ///
+    /// impl FrameObserver for Trace {
+    ///     fn frame_received(&self, frame: &ObservedFrame<'_>) {
+    ///         println!("received {:?}", frame);
+    ///     }
+    /// }
+    ///
+    /// let server_fut = Builder::new()
+    ///     .frame_observer(Trace)
+    ///     .handshake(my_io);
+    /// # server_fut
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`FrameObserver`]: crate::ext::FrameObserver
+    pub fn frame_observer<O: FrameObserver>(&mut self, observer: O) -> &mut Self {
+        self.frame_observer = Some(SharedFrameObserver::new(observer));
+        self
+    }
+
+    /// Sets how the DATA frames of concurrent responses share the connection.
+    ///
+    /// By default, each stream sends one frame as large as the client allows
+    /// before the next stream gets a turn. See [`SchedulingStrategy`] for the
+    /// other strategies.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the strategy has a quantum of zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use http2::server::*;
+    /// # use http2::ext::SchedulingStrategy;
+    /// #
+    /// # fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Handshake<T>
+    /// # {
+    /// // Share the connection by the weights the client gives its requests.
+    /// let server_fut = Builder::new()
+    ///     .scheduling_strategy(SchedulingStrategy::WeightedRoundRobin { quantum: 4096 })
+    ///     .handshake(my_io);
+    /// # server_fut
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`SchedulingStrategy`]: crate::ext::SchedulingStrategy
+    pub fn scheduling_strategy(&mut self, strategy: SchedulingStrategy) -> &mut Self {
+        strategy.assert_valid();
+        self.scheduling_strategy = strategy;
+        self
+    }
+
     /// Creates a new configured HTTP/2 server backed by `io`.
     ///
     /// It is expected that `io` already be in an appropriate state to commence
@@ -1198,6 +1872,39 @@ impl<B: Buf> SendResponse<B> {
         self.inner.poll_reset(cx, proto::PollReset::AwaitingHeaders)
     }
 
+    /// Sends an [ALTSVC frame][1] on this stream, advertising alternative
+    /// services for the origin of the request.
+    ///
+    /// `field_value` uses the syntax of the `Alt-Svc` header field, for
+    /// instance `h3=":443"; ma=3600`. The frame is queued behind the frames
+    /// already sent on the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream is closed or if the frame would be
+    /// larger than 16,384 bytes.
+    ///
+    /// [1]: https://datatracker.ietf.org/doc/html/rfc7838#section-4
+    pub fn send_alt_svc(&mut self, field_value: HeaderValue) -> Result<(), crate::Error> {
+        let value = Bytes::copy_from_slice(field_value.as_bytes());
+        self.inner.send_alt_svc(value).map_err(Into::into)
+    }
+
+    /// Changes the local scheduling weight of the response.
+    ///
+    /// This can be called before the response is sent. See
+    /// [`SendStream::set_priority`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `dependency` names the stream itself or if the
+    /// stream is closed.
+    ///
+    /// [`SendStream::set_priority`]: crate::SendStream::set_priority
+    pub fn set_priority(&mut self, dependency: StreamDependency) -> Result<(), crate::Error> {
+        self.inner.set_priority(dependency).map_err(Into::into)
+    }
+
     /// Returns the stream ID of the response stream.
     ///
     /// # Panics
@@ -1206,6 +1913,16 @@ impl<B: Buf> SendResponse<B> {
     pub fn stream_id(&self) -> crate::StreamId {
         crate::StreamId::from_internal(self.inner.stream_id())
     }
+
+    /// Returns a snapshot of the statistics of the response stream, such as
+    /// when the response HEADERS were queued and flushed.
+    ///
+    /// # Panics
+    ///
+    /// If the lock on the stream store has been poisoned.
+    pub fn stats(&self) -> StreamStats {
+        self.inner.stats()
+    }
 }
 
 // ===== impl SendPushedResponse =====
@@ -1411,8 +2128,19 @@ where
                                 .local_max_error_reset_streams,
                             settings: self.builder.settings.clone(),
                             headers_stream_dependency: None,
-                            headers_pseudo_order: None,
+                            headers_pseudo_order: self.builder.headers_pseudo_order.clone(),
+                            headers_order: self.builder.headers_order.clone(),
+                            padding: self.builder.padding_policy,
                             priorities: None,
+                            rfc7540_priorities: self.builder.rfc7540_priorities,
+                            scheduling_strategy: self.builder.scheduling_strategy,
+                            adaptive_window: self
+                                .builder
+                                .adaptive_window
+                                .then_some(self.builder.max_adaptive_window_size),
+                            keep_alive_interval: self.builder.keep_alive_interval,
+                            keep_alive_timeout: self.builder.keep_alive_timeout,
+                            keep_alive_while_idle: self.builder.keep_alive_while_idle,
                         },
                     );
 
@@ -1447,6 +2175,8 @@ impl Peer {
         id: StreamId,
         response: Response<()>,
         end_of_stream: bool,
+        headers_pseudo_order: Option<PseudoOrder>,
+        headers_order: Option<&HeaderOrder>,
     ) -> frame::Headers {
         use http::response::Parts;
 
@@ -1460,11 +2190,18 @@ impl Peer {
 
         // Build the set pseudo header set. All requests will include `method`
         // and `path`.
-        let pseudo = Pseudo::response(status);
+        let mut pseudo = Pseudo::response(status);
+        if let Some(order) = headers_pseudo_order {
+            pseudo.set_pseudo_order(order);
+        }
 
         // Create the HEADERS frame
         let mut frame = frame::Headers::new(id, pseudo, headers);
 
+        if let Some(order) = headers_order {
+            frame.set_header_order(order);
+        }
+
         if end_of_stream {
             frame.set_end_stream()
         }
@@ -1476,6 +2213,8 @@ impl Peer {
         stream_id: StreamId,
         promised_id: StreamId,
         request: Request<()>,
+        headers_pseudo_order: Option<PseudoOrder>,
+        headers_order: Option<&HeaderOrder>,
     ) -> Result<frame::PushPromise, UserError> {
         use http::request::Parts;
 
@@ -1507,14 +2246,17 @@ impl Peer {
             _,
         ) = request.into_parts();
 
-        let pseudo = Pseudo::request(method, uri, None);
+        let mut pseudo = Pseudo::request(method, uri, None);
+        if let Some(order) = headers_pseudo_order {
+            pseudo.set_pseudo_order(order);
+        }
+
+        let mut frame = frame::PushPromise::new(stream_id, promised_id, pseudo, headers);
+        if let Some(order) = headers_order {
+            frame.set_header_order(order);
+        }
 
-        Ok(frame::PushPromise::new(
-            stream_id,
-            promised_id,
-            pseudo,
-            headers,
-        ))
+        Ok(frame)
     }
 }
 
@@ -1645,6 +2387,7 @@ impl proto::Peer for Peer {
         };
 
         *request.headers_mut() = fields;
+        request.extensions_mut().insert(pseudo.order);
 
         Ok(request)
     }
@@ -1,6 +1,7 @@
 use crate::codec::UserError;
-use crate::frame::Reason;
+use crate::frame::{ExtensiblePriority, ExtensionFrame, Reason, StreamDependency};
 use crate::proto::{self, WindowSize};
+use crate::stats::StreamStats;
 
 use bytes::{Buf, Bytes};
 use http::HeaderMap;
@@ -9,6 +10,7 @@ use std::fmt;
 #[cfg(feature = "stream")]
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 /// Sends the body stream and trailers to the remote peer.
 ///
@@ -197,9 +199,28 @@ pub struct FlowControl {
 }
 
 /// A handle to send and receive PING frames with the peer.
+///
+/// Each acknowledged PING measures the round trip time of the connection.
 // NOT Clone on purpose
 pub struct PingPong {
     inner: proto::UserPings,
+    /// The PING sent by `send_ping`, until `poll_pong` returns its pong.
+    pending: Option<proto::UserPing>,
+}
+
+/// A stream of the extension frames received from the peer.
+///
+/// Extension frames are frames of a type the library does not know, see
+/// [`ExtensionFrame`]. Frames received before an `ExtensionFrames` handle was
+/// taken from the connection are dropped, and so are frames received while
+/// too many are waiting to be read.
+///
+/// The stream ends once the connection is closed.
+///
+/// [`ExtensionFrame`]: crate::ext::ExtensionFrame
+// NOT Clone on purpose
+pub struct ExtensionFrames {
+    inner: proto::UserExtensions,
 }
 
 /// Sent via [`PingPong`][] to send a PING frame to a peer.
@@ -214,7 +235,7 @@ pub struct Ping {
 /// [`PingPong`]: struct.PingPong.html
 /// [`Ping`]: struct.Ping.html
 pub struct Pong {
-    _p: (),
+    rtt: Duration,
 }
 
 // ===== impl SendStream =====
@@ -372,6 +393,96 @@ impl<B: Buf> SendStream<B> {
         self.inner.poll_reset(cx, proto::PollReset::Streaming)
     }
 
+    /// Sends an extension frame on this stream.
+    ///
+    /// The frame is queued behind any frame already queued on the stream, so
+    /// it is written after all the data sent so far. Extension frames are not
+    /// subject to flow control.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `kind` is a frame type known to the library, if the
+    /// payload is larger than 16,384 bytes or if the stream is closed.
+    pub fn send_extension_frame(
+        &mut self,
+        kind: u8,
+        flags: u8,
+        payload: Bytes,
+    ) -> Result<(), crate::Error> {
+        self.inner
+            .send_extension_frame(kind, flags, payload)
+            .map_err(Into::into)
+    }
+
+    /// Changes the local scheduling weight of the stream.
+    ///
+    /// The weight is `dependency.weight() + 1`, from 1 to 256, as in HTTP/2
+    /// priority fields. When the connection window is exhausted, streams of a
+    /// higher weight are assigned connection capacity first as it is
+    /// released.
+    ///
+    /// Which stream sends the next DATA frame only depends on the weight with
+    /// [`SchedulingStrategy::WeightedRoundRobin`], where it scales the bytes
+    /// the stream sends per turn. With the default
+    /// [`SchedulingStrategy::Fifo`] and with
+    /// [`SchedulingStrategy::RoundRobin`], streams take equal turns whatever
+    /// their weight. On a server scheduling responses along the RFC 7540
+    /// dependency tree, the stream is moved in the tree as `dependency` says.
+    ///
+    /// Nothing is sent to the peer, see [`send_priority`] for that.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `dependency` names the stream itself or if the
+    /// stream is closed.
+    ///
+    /// [`SchedulingStrategy::WeightedRoundRobin`]: crate::ext::SchedulingStrategy::WeightedRoundRobin
+    /// [`SchedulingStrategy::Fifo`]: crate::ext::SchedulingStrategy::Fifo
+    /// [`SchedulingStrategy::RoundRobin`]: crate::ext::SchedulingStrategy::RoundRobin
+    /// [`send_priority`]: SendStream::send_priority
+    pub fn set_priority(&mut self, dependency: StreamDependency) -> Result<(), crate::Error> {
+        self.inner.set_priority(dependency).map_err(Into::into)
+    }
+
+    /// Changes the local scheduling weight of the stream, like
+    /// [`set_priority`], and sends a PRIORITY frame so that the server
+    /// reprioritizes the response too.
+    ///
+    /// The frame is sent ahead of any request data still queued on the
+    /// stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if called on a server stream, if `dependency` names
+    /// the stream itself or if the stream is closed.
+    ///
+    /// [`set_priority`]: SendStream::set_priority
+    pub fn send_priority(&mut self, dependency: StreamDependency) -> Result<(), crate::Error> {
+        self.inner.send_priority(dependency).map_err(Into::into)
+    }
+
+    /// Changes the priority of the response to this request.
+    ///
+    /// A PRIORITY_UPDATE frame is sent to the server, ahead of any request
+    /// data still queued on the stream. The initial priority of a response is
+    /// signalled with the `priority` request header field instead, see
+    /// [`ExtensiblePriority::to_header_value`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if called on a server stream or if the stream is
+    /// closed.
+    ///
+    /// [`ExtensiblePriority::to_header_value`]: crate::ext::ExtensiblePriority::to_header_value
+    pub fn send_priority_update(
+        &mut self,
+        priority: ExtensiblePriority,
+    ) -> Result<(), crate::Error> {
+        self.inner
+            .send_priority_update(priority)
+            .map_err(Into::into)
+    }
+
     /// Returns the stream ID of this `SendStream`.
     ///
     /// # Panics
@@ -380,6 +491,16 @@ impl<B: Buf> SendStream<B> {
     pub fn stream_id(&self) -> StreamId {
         StreamId::from_internal(self.inner.stream_id())
     }
+
+    /// Returns a snapshot of the stream statistics, such as how many bytes
+    /// were sent and how long the stream waited for send capacity.
+    ///
+    /// # Panics
+    ///
+    /// If the lock on the stream store has been poisoned.
+    pub fn stats(&self) -> StreamStats {
+        self.inner.stats()
+    }
 }
 
 // ===== impl StreamId =====
@@ -458,6 +579,16 @@ impl RecvStream {
     pub fn stream_id(&self) -> StreamId {
         self.inner.stream_id()
     }
+
+    /// Returns a snapshot of the stream statistics, such as when the first
+    /// DATA frame arrived and how many bytes were received.
+    ///
+    /// # Panics
+    ///
+    /// If the lock on the stream store has been poisoned.
+    pub fn stats(&self) -> StreamStats {
+        self.inner.inner.stats()
+    }
 }
 
 #[cfg(feature = "stream")]
@@ -543,32 +674,52 @@ impl FlowControl {
 
 impl PingPong {
     pub(crate) fn new(inner: proto::UserPings) -> Self {
-        PingPong { inner }
+        PingPong {
+            inner,
+            pending: None,
+        }
     }
 
-    /// Send a PING frame and wait for the peer to send the pong.
-    pub async fn ping(&mut self, ping: Ping) -> Result<Pong, crate::Error> {
-        self.send_ping(ping)?;
-        crate::poll_fn(|cx| self.poll_pong(cx)).await
+    /// Send a PING frame and wait for the peer to send the pong, returning
+    /// the round trip time.
+    ///
+    /// Several PINGs may be in flight at once: each is sent with a distinct
+    /// payload, so that the pongs are told apart. Dropping the future before
+    /// it completes forgets the PING.
+    pub async fn ping(&self, ping: Ping) -> Result<Duration, crate::Error> {
+        // Passing a `Ping` here is just to be forwards-compatible with
+        // eventually allowing choosing a ping payload. For now, we can
+        // just ignore it.
+        let _ = ping;
+
+        let ping = self.inner.send_ping()?;
+        crate::poll_fn(|cx| ping.poll_pong(cx))
+            .await
+            .map_err(Into::into)
     }
 
     #[doc(hidden)]
     pub fn send_ping(&mut self, ping: Ping) -> Result<(), crate::Error> {
-        // Passing a `Ping` here is just to be forwards-compatible with
-        // eventually allowing choosing a ping payload. For now, we can
-        // just ignore it.
         let _ = ping;
 
-        self.inner.send_ping().map_err(|err| match err {
-            Some(err) => err.into(),
-            None => UserError::SendPingWhilePending.into(),
-        })
+        if self.pending.is_some() {
+            return Err(UserError::SendPingWhilePending.into());
+        }
+
+        self.pending = Some(self.inner.send_ping()?);
+        Ok(())
     }
 
     #[doc(hidden)]
     pub fn poll_pong(&mut self, cx: &mut Context) -> Poll<Result<Pong, crate::Error>> {
-        ready!(self.inner.poll_pong(cx))?;
-        Poll::Ready(Ok(Pong { _p: () }))
+        let rtt = match self.pending {
+            Some(ref ping) => ready!(ping.poll_pong(cx)),
+            None => Err(ready!(self.inner.poll_closed(cx))),
+        };
+
+        // The PING is done with, whether it was acknowledged or not.
+        self.pending = None;
+        Poll::Ready(Ok(Pong { rtt: rtt? }))
     }
 }
 
@@ -578,6 +729,41 @@ impl fmt::Debug for PingPong {
     }
 }
 
+// ===== impl ExtensionFrames =====
+
+impl ExtensionFrames {
+    pub(crate) fn new(inner: proto::UserExtensions) -> Self {
+        ExtensionFrames { inner }
+    }
+
+    /// Waits for the next extension frame.
+    ///
+    /// Returns `None` once the connection is closed.
+    pub async fn frame(&mut self) -> Option<ExtensionFrame> {
+        crate::poll_fn(|cx| self.poll_frame(cx)).await
+    }
+
+    #[doc(hidden)]
+    pub fn poll_frame(&mut self, cx: &mut Context) -> Poll<Option<ExtensionFrame>> {
+        self.inner.poll_frame(cx)
+    }
+}
+
+#[cfg(feature = "stream")]
+impl futures_core::Stream for ExtensionFrames {
+    type Item = ExtensionFrame;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_frame(cx)
+    }
+}
+
+impl fmt::Debug for ExtensionFrames {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("ExtensionFrames").finish()
+    }
+}
+
 // ===== impl Ping =====
 
 impl Ping {
@@ -599,8 +785,15 @@ impl fmt::Debug for Ping {
 
 // ===== impl Pong =====
 
+impl Pong {
+    /// Returns the time between sending the PING and receiving the pong.
+    pub fn rtt(&self) -> Duration {
+        self.rtt
+    }
+}
+
 impl fmt::Debug for Pong {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        fmt.debug_struct("Pong").finish()
+        fmt.debug_struct("Pong").field("rtt", &self.rtt).finish()
     }
 }
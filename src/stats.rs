@@ -0,0 +1,399 @@
+use crate::frame::{Kind, Reason};
+use crate::proto::Initiator;
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// The frame types counted separately, the others count as `Unknown`.
+const KINDS: [Kind; 14] = [
+    Kind::Data,
+    Kind::Headers,
+    Kind::Priority,
+    Kind::Reset,
+    Kind::Settings,
+    Kind::PushPromise,
+    Kind::Ping,
+    Kind::GoAway,
+    Kind::WindowUpdate,
+    Kind::Continuation,
+    Kind::AltSvc,
+    Kind::Origin,
+    Kind::PriorityUpdate,
+    Kind::Unknown,
+];
+
+/// A snapshot of what a connection has done since it was established.
+///
+/// Returned by `client::Connection::stats` and `server::Connection::stats`.
+#[derive(Clone, Debug, Default)]
+pub struct ConnectionStats {
+    pub(crate) frames_sent: FrameStats,
+    pub(crate) frames_received: FrameStats,
+    pub(crate) streams: StreamCounts,
+    pub(crate) send_window: u32,
+    pub(crate) recv_window: u32,
+    pub(crate) encoder_table: HeaderTableStats,
+    pub(crate) decoder_table: HeaderTableStats,
+    pub(crate) local_reset_streams: usize,
+    pub(crate) remote_reset_streams: usize,
+    pub(crate) local_error_reset_streams: usize,
+}
+
+/// The number of frames, and of bytes, sent or received for each frame type.
+///
+/// Bytes include the 9 byte frame header, and any padding.
+#[derive(Clone, Debug, Default)]
+pub struct FrameStats {
+    frames: [u64; KINDS.len()],
+    bytes: [u64; KINDS.len()],
+}
+
+/// The streams opened, closed and reset on a connection.
+#[derive(Clone, Debug, Default)]
+pub struct StreamCounts {
+    local_opened: u64,
+    remote_opened: u64,
+    local_closed: u64,
+    remote_closed: u64,
+    resets: BTreeMap<(Initiator, u32), u64>,
+}
+
+/// A snapshot of what a stream has done since it was opened.
+///
+/// Returned by the `stats` method of the stream handles, such as
+/// [`SendStream::stats`] and [`RecvStream::stats`]. Byte counts are of the
+/// DATA payloads, without the frame headers and padding.
+///
+/// [`SendStream::stats`]: crate::SendStream::stats
+/// [`RecvStream::stats`]: crate::RecvStream::stats
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StreamStats {
+    headers_queued: Option<Instant>,
+    headers_flushed: Option<Instant>,
+    headers_received: Option<Instant>,
+    data_received: Option<Instant>,
+    bytes_sent: u64,
+    bytes_received: u64,
+    data_frames_sent: u64,
+    data_frames_received: u64,
+    send_blocked: Duration,
+    send_blocked_since: Option<Instant>,
+}
+
+/// The state of an HPACK dynamic table.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HeaderTableStats {
+    pub(crate) size: usize,
+    pub(crate) max_size: usize,
+    pub(crate) entries: usize,
+}
+
+// ===== impl ConnectionStats =====
+
+impl ConnectionStats {
+    /// Returns the frames written to the connection.
+    pub fn frames_sent(&self) -> &FrameStats {
+        &self.frames_sent
+    }
+
+    /// Returns the frames read from the connection.
+    pub fn frames_received(&self) -> &FrameStats {
+        &self.frames_received
+    }
+
+    /// Returns the streams opened, closed and reset on the connection.
+    pub fn streams(&self) -> &StreamCounts {
+        &self.streams
+    }
+
+    /// Returns the connection level window for sending DATA.
+    pub fn send_window(&self) -> u32 {
+        self.send_window
+    }
+
+    /// Returns the connection level window the peer has for sending DATA.
+    pub fn recv_window(&self) -> u32 {
+        self.recv_window
+    }
+
+    /// Returns the dynamic table of the HPACK encoder, for the headers sent.
+    pub fn encoder_table(&self) -> HeaderTableStats {
+        self.encoder_table
+    }
+
+    /// Returns the dynamic table of the HPACK decoder, for the headers
+    /// received.
+    pub fn decoder_table(&self) -> HeaderTableStats {
+        self.decoder_table
+    }
+
+    /// Returns the number of locally reset streams that are kept around for
+    /// a while, to ignore the frames the peer sent before it saw the reset.
+    ///
+    /// See [`max_concurrent_reset_streams`].
+    ///
+    /// [`max_concurrent_reset_streams`]: crate::client::Builder::max_concurrent_reset_streams
+    pub fn local_reset_streams(&self) -> usize {
+        self.local_reset_streams
+    }
+
+    /// Returns the number of streams that the peer reset before they were
+    /// accepted.
+    ///
+    /// See [`max_pending_accept_reset_streams`].
+    ///
+    /// [`max_pending_accept_reset_streams`]: crate::client::Builder::max_pending_accept_reset_streams
+    pub fn remote_reset_streams(&self) -> usize {
+        self.remote_reset_streams
+    }
+
+    /// Returns the number of streams reset because the peer made a protocol
+    /// error, since the connection was established.
+    ///
+    /// See [`max_local_error_reset_streams`].
+    ///
+    /// [`max_local_error_reset_streams`]: crate::client::Builder::max_local_error_reset_streams
+    pub fn local_error_reset_streams(&self) -> usize {
+        self.local_error_reset_streams
+    }
+}
+
+// ===== impl FrameStats =====
+
+impl FrameStats {
+    /// Returns the number of frames of the given type.
+    pub fn frames(&self, kind: Kind) -> u64 {
+        self.frames[index(kind)]
+    }
+
+    /// Returns the number of bytes taken by the frames of the given type.
+    pub fn bytes(&self, kind: Kind) -> u64 {
+        self.bytes[index(kind)]
+    }
+
+    /// Returns the number of frames of all types.
+    pub fn total_frames(&self) -> u64 {
+        self.frames.iter().sum()
+    }
+
+    /// Returns the number of bytes taken by the frames of all types.
+    pub fn total_bytes(&self) -> u64 {
+        self.bytes.iter().sum()
+    }
+
+    /// Returns the number of frames and bytes of each frame type, skipping
+    /// the types without any frame.
+    pub fn iter(&self) -> impl Iterator<Item = (Kind, u64, u64)> + '_ {
+        KINDS
+            .iter()
+            .zip(self.frames.iter().zip(&self.bytes))
+            .filter(|(_, (&frames, _))| frames > 0)
+            .map(|(&kind, (&frames, &bytes))| (kind, frames, bytes))
+    }
+
+    pub(crate) fn record(&mut self, kind: Kind, len: usize) {
+        let index = index(kind);
+        self.frames[index] += 1;
+        self.bytes[index] += len as u64;
+    }
+}
+
+fn index(kind: Kind) -> usize {
+    KINDS
+        .iter()
+        .position(|&k| k == kind)
+        .unwrap_or(KINDS.len() - 1)
+}
+
+// ===== impl StreamCounts =====
+
+impl StreamCounts {
+    /// Returns the number of streams opened by this endpoint.
+    pub fn local_opened(&self) -> u64 {
+        self.local_opened
+    }
+
+    /// Returns the number of streams opened by the peer.
+    pub fn remote_opened(&self) -> u64 {
+        self.remote_opened
+    }
+
+    /// Returns the number of streams opened by this endpoint that have since
+    /// closed, whether they completed or were reset.
+    pub fn local_closed(&self) -> u64 {
+        self.local_closed
+    }
+
+    /// Returns the number of streams opened by the peer that have since
+    /// closed, whether they completed or were reset.
+    pub fn remote_closed(&self) -> u64 {
+        self.remote_closed
+    }
+
+    /// Returns the number of streams reset by `initiator` with `reason`.
+    pub fn reset(&self, initiator: Initiator, reason: Reason) -> u64 {
+        self.resets
+            .get(&(initiator, reason.into()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns the number of streams reset, for every initiator and reason.
+    pub fn total_reset(&self) -> u64 {
+        self.resets.values().sum()
+    }
+
+    /// Returns the number of streams reset by each initiator and reason,
+    /// skipping the pairs without any reset.
+    pub fn resets(&self) -> impl Iterator<Item = (Initiator, Reason, u64)> + '_ {
+        self.resets
+            .iter()
+            .map(|(&(initiator, reason), &count)| (initiator, reason.into(), count))
+    }
+
+    pub(crate) fn record_open(&mut self, is_local: bool) {
+        if is_local {
+            self.local_opened += 1;
+        } else {
+            self.remote_opened += 1;
+        }
+    }
+
+    pub(crate) fn record_close(&mut self, is_local: bool) {
+        if is_local {
+            self.local_closed += 1;
+        } else {
+            self.remote_closed += 1;
+        }
+    }
+
+    pub(crate) fn record_reset(&mut self, initiator: Initiator, reason: Reason) {
+        *self.resets.entry((initiator, reason.into())).or_insert(0) += 1;
+    }
+}
+
+// ===== impl StreamStats =====
+
+impl StreamStats {
+    /// Returns when the first HEADERS frame of the stream was queued to be
+    /// sent, for instance by `send_request` or `send_response`.
+    pub fn headers_queued_at(&self) -> Option<Instant> {
+        self.headers_queued
+    }
+
+    /// Returns when the first HEADERS frame of the stream was flushed to the
+    /// connection.
+    pub fn headers_flushed_at(&self) -> Option<Instant> {
+        self.headers_flushed
+    }
+
+    /// Returns when the first HEADERS frame was received on the stream, the
+    /// response headers for a client, which may be informational, and the
+    /// request headers for a server.
+    pub fn headers_received_at(&self) -> Option<Instant> {
+        self.headers_received
+    }
+
+    /// Returns when the first DATA frame was received on the stream.
+    pub fn data_received_at(&self) -> Option<Instant> {
+        self.data_received
+    }
+
+    /// Returns the number of bytes of DATA sent on the stream.
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    /// Returns the number of bytes of DATA received on the stream.
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received
+    }
+
+    /// Returns the number of DATA frames sent on the stream.
+    pub fn data_frames_sent(&self) -> u64 {
+        self.data_frames_sent
+    }
+
+    /// Returns the number of DATA frames received on the stream.
+    pub fn data_frames_received(&self) -> u64 {
+        self.data_frames_received
+    }
+
+    /// Returns how long the stream waited for send capacity, that is how long
+    /// it had requested more capacity, or buffered more data, than the flow
+    /// control windows allowed it to send.
+    pub fn flow_control_blocked(&self) -> Duration {
+        self.send_blocked
+    }
+
+    /// Returns a copy of the stats, counting a wait for send capacity that is
+    /// still going on up to now.
+    pub(crate) fn snapshot(&self) -> Self {
+        let mut stats = *self;
+        if let Some(since) = stats.send_blocked_since.take() {
+            stats.send_blocked += since.elapsed();
+        }
+        stats
+    }
+
+    pub(crate) fn record_headers_queued(&mut self) {
+        self.headers_queued.get_or_insert_with(Instant::now);
+    }
+
+    pub(crate) fn record_headers_flushed(&mut self, now: Instant) {
+        self.headers_flushed.get_or_insert(now);
+    }
+
+    pub(crate) fn is_headers_flushed(&self) -> bool {
+        self.headers_flushed.is_some()
+    }
+
+    pub(crate) fn record_headers_received(&mut self) {
+        self.headers_received.get_or_insert_with(Instant::now);
+    }
+
+    pub(crate) fn record_data_sent(&mut self, len: usize) {
+        self.data_frames_sent += 1;
+        self.bytes_sent += len as u64;
+    }
+
+    pub(crate) fn record_data_received(&mut self, len: usize) {
+        self.data_received.get_or_insert_with(Instant::now);
+        self.data_frames_received += 1;
+        self.bytes_received += len as u64;
+    }
+
+    /// Starts or stops the clock on the time the stream waits for send
+    /// capacity.
+    pub(crate) fn set_send_blocked(&mut self, blocked: bool) {
+        match (blocked, self.send_blocked_since) {
+            (true, None) => self.send_blocked_since = Some(Instant::now()),
+            (false, Some(since)) => {
+                self.send_blocked += since.elapsed();
+                self.send_blocked_since = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+// ===== impl HeaderTableStats =====
+
+impl HeaderTableStats {
+    /// Returns the size of the table, as defined by [RFC 7541 section 4.1].
+    ///
+    /// [RFC 7541 section 4.1]: https://datatracker.ietf.org/doc/html/rfc7541#section-4.1
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the size the table is allowed to grow to.
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Returns the number of entries in the table.
+    pub fn entries(&self) -> usize {
+        self.entries
+    }
+}
@@ -78,6 +78,50 @@ pub fn ping(payload: [u8; 8]) -> Mock<frame::Ping> {
     Mock(frame::Ping::new(payload))
 }
 
+pub fn extension<T>(kind: u8, flags: u8, id: T, payload: &'static [u8]) -> frame::ExtensionFrame
+where
+    T: Into<StreamId>,
+{
+    frame::ExtensionFrame::new(kind, flags, id.into(), Bytes::from_static(payload))
+}
+
+pub fn origin(origins: &[&str]) -> frame::Origin {
+    let mut frame = frame::Origin::new();
+    for origin in origins {
+        frame.push(origin).unwrap();
+    }
+    frame
+}
+
+pub fn alt_svc<T>(id: T, origin: &'static str, value: &'static str) -> frame::AltSvc
+where
+    T: Into<StreamId>,
+{
+    frame::AltSvc::new(
+        id.into(),
+        Bytes::from_static(origin.as_bytes()),
+        Bytes::from_static(value.as_bytes()),
+    )
+}
+
+pub fn priority_update<T>(id: T, urgency: u8, incremental: bool) -> frame::PriorityUpdate
+where
+    T: Into<StreamId>,
+{
+    frame::PriorityUpdate::new(
+        id.into(),
+        frame::ExtensiblePriority::new(urgency, incremental),
+    )
+}
+
+pub fn priority<T>(id: T, dependency: u32, weight: u8, exclusive: bool) -> frame::Priority
+where
+    T: Into<StreamId>,
+{
+    let dependency = frame::StreamDependency::new(dependency.into(), weight, exclusive);
+    frame::Priority::new(id.into(), dependency)
+}
+
 // === Generic helpers of all frame types
 
 pub struct Mock<T>(T);
@@ -192,6 +236,13 @@ impl Mock<frame::Headers> {
         self
     }
 
+    /// Sets the priority fields; must be called before `eos`.
+    pub fn priority(mut self, dependency: u32, weight: u8, exclusive: bool) -> Self {
+        let dependency = frame::StreamDependency::new(dependency.into(), weight, exclusive);
+        self.0.set_stream_dependency(dependency);
+        self
+    }
+
     pub fn into_fields(self) -> HeaderMap {
         self.0.into_parts().1
     }
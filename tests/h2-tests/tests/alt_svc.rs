@@ -0,0 +1,182 @@
+use futures::future::join;
+use h2_support::prelude::*;
+
+#[tokio::test]
+async fn client_recv_connection_alt_svc() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::alt_svc(0, "https://example.com", "h2=\":8443\""))
+            .await;
+        srv.send_frame(frames::alt_svc(0, "https://example.net", "h3=\":443\""))
+            .await;
+        // Replaces the first frame, same origin.
+        srv.send_frame(frames::alt_svc(0, "https://example.com", "h3=\":443\""))
+            .await;
+        // Ignored, frames on stream zero must have an origin.
+        srv.send_frame(frames::alt_svc(0, "", "h3=\":443\"")).await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut conn) = client::handshake(io).await.unwrap();
+        assert!(conn.alt_svc().is_empty());
+
+        let request = Request::builder()
+            .uri("https://example.com/")
+            .body(())
+            .unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+        let response = conn.drive(response).await.unwrap();
+        assert!(response.extensions().get::<h2::ext::AltSvc>().is_none());
+
+        assert_eq!(
+            conn.alt_svc(),
+            [
+                frames::alt_svc(0, "https://example.com", "h3=\":443\""),
+                frames::alt_svc(0, "https://example.net", "h3=\":443\""),
+            ]
+        );
+        conn.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn client_recv_stream_alt_svc() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .eos(),
+        )
+        .await;
+        // Ignored, frames on a stream must not have an origin.
+        srv.send_frame(frames::alt_svc(1, "https://example.net", "h2=\":8443\""))
+            .await;
+        srv.send_frame(frames::alt_svc(1, "", "h3=\":443\"")).await;
+        srv.send_frame(frames::headers(1).response(200)).await;
+        srv.send_frame(frames::alt_svc(1, "", "clear")).await;
+        srv.send_frame(frames::data(1, "").eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut conn) = client::handshake(io).await.unwrap();
+
+        let request = Request::builder()
+            .uri("https://example.com/")
+            .body(())
+            .unwrap();
+        let (mut response, _) = client.send_request(request, true).unwrap();
+        assert_eq!(response.alt_svc(), None);
+
+        let alt = conn.drive(&mut response).await.unwrap();
+        let frame = alt.extensions().get::<h2::ext::AltSvc>().unwrap();
+        assert_eq!(frame, &frames::alt_svc(1, "", "h3=\":443\""));
+
+        let body = alt.into_body();
+        conn.drive(util::concat(body)).await.unwrap();
+        assert_eq!(response.alt_svc(), Some(frames::alt_svc(1, "", "clear")));
+
+        assert!(conn.alt_svc().is_empty());
+        conn.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn server_send_alt_svc() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/")
+                    .eos(),
+            )
+            .await;
+        client
+            .recv_frame(frames::alt_svc(0, "https://example.net", "h3=\":443\""))
+            .await;
+        client
+            .recv_frame(frames::alt_svc(1, "", "h2=\":8443\""))
+            .await;
+        client
+            .recv_frame(frames::headers(1).response(200).eos())
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+        let (_, mut stream) = srv.accept().await.unwrap().unwrap();
+
+        let value = http::HeaderValue::from_static("h3=\":443\"");
+        assert!(srv.send_alt_svc("example.net", value.clone()).is_err());
+        srv.send_alt_svc("https://example.net", value).unwrap();
+
+        let value = http::HeaderValue::from_static("h2=\":8443\"");
+        stream.send_alt_svc(value).unwrap();
+        let rsp = http::Response::builder().status(200).body(()).unwrap();
+        stream.send_response(rsp, true).unwrap();
+
+        let value = http::HeaderValue::from_static("clear");
+        assert!(stream.send_alt_svc(value).is_err());
+        assert!(srv.accept().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn server_ignores_recv_alt_svc() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(frames::alt_svc(0, "https://example.com", "h3=\":443\""))
+            .await;
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/")
+                    .eos(),
+            )
+            .await;
+        client
+            .recv_frame(frames::headers(1).response(200).eos())
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+        let (_, mut stream) = srv.accept().await.unwrap().unwrap();
+        let rsp = http::Response::builder().status(200).body(()).unwrap();
+        stream.send_response(rsp, true).unwrap();
+        assert!(srv.accept().await.is_none());
+    };
+
+    join(client, srv).await;
+}
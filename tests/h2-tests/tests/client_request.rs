@@ -1633,6 +1633,242 @@ async fn extended_connect_request() {
     join(srv, h2).await;
 }
 
+#[tokio::test]
+async fn headers_order() {
+    use h2::ext::HeaderOrder;
+    use http::header;
+
+    h2_support::trace_init!();
+
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+
+        // The connection level order.
+        let headers = assert_headers!(srv.next().await.unwrap().unwrap());
+        let names: Vec<_> = headers.fields().keys().cloned().collect();
+        assert_eq!(
+            names,
+            [header::USER_AGENT, header::ACCEPT, header::CONTENT_TYPE]
+        );
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+
+        // The per-request override.
+        let headers = assert_headers!(srv.next().await.unwrap().unwrap());
+        let names: Vec<_> = headers.fields().keys().cloned().collect();
+        assert_eq!(
+            names,
+            [header::CONTENT_TYPE, header::USER_AGENT, header::ACCEPT]
+        );
+        srv.send_frame(frames::headers(3).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::Builder::new()
+            .headers_order(
+                HeaderOrder::builder()
+                    .push(header::USER_AGENT)
+                    .push(header::ACCEPT)
+                    .build(),
+            )
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+
+        let request = || {
+            Request::get("https://example.com/")
+                .header(header::CONTENT_TYPE, "text/plain")
+                .header(header::ACCEPT, "*/*")
+                .header(header::USER_AGENT, "h2")
+        };
+
+        let (response, _) = client
+            .send_request(request().body(()).unwrap(), true)
+            .unwrap();
+        h2.drive(response).await.unwrap();
+
+        let order = HeaderOrder::builder()
+            .push(header::CONTENT_TYPE)
+            .push(header::USER_AGENT)
+            .build();
+        let (response, _) = client
+            .send_request(request().extension(order).body(()).unwrap(), true)
+            .unwrap();
+        h2.drive(response).await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn request_priority_overrides() {
+    use h2::ext::{Priorities, Priority, PseudoId, PseudoOrder, StreamDependency};
+
+    h2_support::trace_init!();
+
+    let (io, mut srv) = mock::new();
+
+    fn assert_priority(frame: frame::Frame, stream_id: u32, dependency: StreamDependency) {
+        match frame {
+            frame::Frame::Priority(priority) => {
+                assert_eq!(priority.stream_id(), stream_id);
+                assert_eq!(priority.dependency(), dependency);
+            }
+            frame => panic!("expected PRIORITY; actual={:?}", frame),
+        }
+    }
+
+    fn pseudo_order(headers: frame::Headers) -> Vec<PseudoId> {
+        let (pseudo, _) = headers.into_parts();
+        pseudo.order.into_iter().copied().take(4).collect()
+    }
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+
+        // The connection defaults.
+        let frame = srv.next().await.unwrap().unwrap();
+        assert_priority(frame, 3, StreamDependency::new(StreamId::ZERO, 200, false));
+        let headers = assert_headers!(srv.next().await.unwrap().unwrap());
+        assert_eq!(headers.stream_id(), 5);
+        assert_eq!(
+            headers.stream_dependency(),
+            Some(StreamDependency::new(StreamId::ZERO, 255, true))
+        );
+        assert_eq!(
+            pseudo_order(headers),
+            [
+                PseudoId::Method,
+                PseudoId::Authority,
+                PseudoId::Scheme,
+                PseudoId::Path,
+            ]
+        );
+        srv.send_frame(frames::headers(5).response(200).eos()).await;
+
+        // The per-request overrides.
+        let frame = srv.next().await.unwrap().unwrap();
+        assert_priority(frame, 1, StreamDependency::new(StreamId::ZERO, 10, true));
+        let headers = assert_headers!(srv.next().await.unwrap().unwrap());
+        assert_eq!(headers.stream_id(), 7);
+        assert_eq!(
+            headers.stream_dependency(),
+            Some(StreamDependency::new(StreamId::from(5), 100, false))
+        );
+        assert_eq!(
+            pseudo_order(headers),
+            [
+                PseudoId::Method,
+                PseudoId::Path,
+                PseudoId::Authority,
+                PseudoId::Scheme,
+            ]
+        );
+        srv.send_frame(frames::headers(7).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::Builder::new()
+            .initial_stream_id(5)
+            .headers_pseudo_order(
+                PseudoOrder::builder()
+                    .extend([
+                        PseudoId::Method,
+                        PseudoId::Authority,
+                        PseudoId::Scheme,
+                        PseudoId::Path,
+                    ])
+                    .build(),
+            )
+            .headers_stream_dependency(StreamDependency::new(StreamId::ZERO, 255, true))
+            .priorities(
+                Priorities::builder()
+                    .push(Priority::new(
+                        StreamId::from(3),
+                        StreamDependency::new(StreamId::ZERO, 200, false),
+                    ))
+                    .build(),
+            )
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+
+        let request = Request::get("https://example.com/").body(()).unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+        h2.drive(response).await.unwrap();
+
+        let request = Request::get("https://example.com/")
+            .extension(
+                PseudoOrder::builder()
+                    .extend([
+                        PseudoId::Method,
+                        PseudoId::Path,
+                        PseudoId::Authority,
+                        PseudoId::Scheme,
+                    ])
+                    .build(),
+            )
+            .extension(StreamDependency::new(StreamId::from(5), 100, false))
+            .extension(
+                Priorities::builder()
+                    .push(Priority::new(
+                        StreamId::from(1),
+                        StreamDependency::new(StreamId::ZERO, 10, true),
+                    ))
+                    .build(),
+            )
+            .body(())
+            .unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+        h2.drive(response).await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn recv_response_pseudo_order() {
+    use h2::ext::{PseudoId, PseudoOrder};
+
+    h2_support::trace_init!();
+
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::handshake(io).await.unwrap();
+
+        let request = Request::get("https://example.com/").body(()).unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+        let response = h2.drive(response).await.unwrap();
+
+        let order: Vec<_> = response
+            .extensions()
+            .get::<PseudoOrder>()
+            .unwrap()
+            .into_iter()
+            .copied()
+            .collect();
+        assert_eq!(order, [PseudoId::Status]);
+    };
+
+    join(srv, h2).await;
+}
+
 #[tokio::test]
 async fn rogue_server_odd_headers() {
     h2_support::trace_init!();
@@ -137,3 +137,125 @@ async fn server_settings_header_table_size() {
 
     assert!(srv.accept().await.is_none());
 }
+
+#[tokio::test]
+async fn client_padding_fixed() {
+    use h2::ext::PaddingPolicy;
+
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        srv.read_preface().await.unwrap();
+
+        let (head, _) = read_raw_frame(&mut srv).await;
+        assert_eq!(head[3], 4, "SETTINGS");
+
+        // HEADERS: END_HEADERS | PADDED, 4 bytes of padding
+        let (head, payload) = read_raw_frame(&mut srv).await;
+        assert_eq!(head, [0, 0, 21, 1, 0xc, 0, 0, 0, 1]);
+        assert_eq!(
+            payload,
+            [
+                4, 0x83, 0x87, 0x41, 0x8B, 0x9D, 0x29, 0xAC, 0x4B, 0x8F, 0xA8, 0xE9, 0x19, 0x97,
+                0x21, 0xE9, 0x84, 0, 0, 0, 0,
+            ]
+        );
+
+        // DATA: END_STREAM | PADDED, 4 bytes of padding
+        let (head, payload) = read_raw_frame(&mut srv).await;
+        assert_eq!(head, [0, 0, 10, 0, 0x9, 0, 0, 0, 1]);
+        assert_eq!(payload, b"\x04hello\0\0\0\0");
+
+        srv.send_frame(frames::settings()).await;
+        srv.recv_frame(frames::settings_ack()).await;
+        srv.send_frame(frames::headers(1).response(204).eos()).await;
+    };
+
+    let client = async move {
+        let (mut client, mut conn) = client::Builder::new()
+            .padding_policy(PaddingPolicy::Fixed(4))
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+
+        let request = Request::post("https://http2.akamai.com/").body(()).unwrap();
+
+        let (response, mut stream) = client.send_request(request, false).unwrap();
+        stream.send_data("hello".into(), true).unwrap();
+
+        let response = conn.drive(response).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        conn.await.unwrap();
+    };
+
+    join(srv, client).await;
+}
+
+#[tokio::test]
+async fn server_padding_block() {
+    use h2::ext::PaddingPolicy;
+
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/")
+                    .eos(),
+            )
+            .await;
+
+        // HEADERS: END_HEADERS | PADDED, the payload is rounded up to 16
+        // bytes
+        let (head, payload) = read_raw_frame(&mut client).await;
+        assert_eq!(head, [0, 0, 16, 1, 0xc, 0, 0, 0, 1]);
+        assert_eq!(
+            payload,
+            [14, 0x88, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+
+        // DATA: END_STREAM | PADDED, the payload is rounded up to 16 bytes
+        let (head, payload) = read_raw_frame(&mut client).await;
+        assert_eq!(head, [0, 0, 16, 0, 0x9, 0, 0, 0, 1]);
+        assert_eq!(payload, b"\x0ahello\0\0\0\0\0\0\0\0\0\0");
+    };
+
+    let srv = async move {
+        let mut srv = server::Builder::new()
+            .padding_policy(PaddingPolicy::Block(16))
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+
+        let (_, mut stream) = srv.accept().await.unwrap().unwrap();
+        let rsp = Response::builder().status(200).body(()).unwrap();
+        let mut body = stream.send_response(rsp, false).unwrap();
+        body.send_data("hello".into(), true).unwrap();
+
+        assert!(srv.accept().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+/// Reads the next frame written by the peer without decoding it, returning
+/// its head and its payload.
+async fn read_raw_frame(handle: &mut mock::Handle) -> (Vec<u8>, Vec<u8>) {
+    use tokio::io::AsyncReadExt;
+
+    let mut head = vec![0; 9];
+    handle.read_exact(&mut head).await.unwrap();
+
+    let len = (head[0] as usize) << 16 | (head[1] as usize) << 8 | head[2] as usize;
+    let mut payload = vec![0; len];
+    handle.read_exact(&mut payload).await.unwrap();
+
+    (head, payload)
+}
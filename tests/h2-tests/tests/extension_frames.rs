@@ -0,0 +1,224 @@
+use futures::future::join;
+use h2_support::prelude::*;
+
+#[tokio::test]
+async fn recv_extension_frames() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.send_frame(frames::extension(0xb0, 0x1, 0, b"hello"))
+            .await;
+        srv.send_frame(frames::extension(0xb1, 0x0, 3, b"")).await;
+        srv.send_frame(frames::ping([1; 8])).await;
+        srv.recv_frame(frames::ping([1; 8]).pong()).await;
+    };
+
+    let h2 = async move {
+        let (_client, mut conn) = client::handshake(io).await.unwrap();
+        let mut frames = conn.extension_frames().expect("extension_frames");
+        assert!(conn.extension_frames().is_none());
+
+        let frame = conn.drive(frames.frame()).await.unwrap();
+        assert_eq!(frame.kind(), 0xb0);
+        assert_eq!(frame.flags(), 0x1);
+        assert_eq!(frame.stream_id(), 0);
+        assert_eq!(frame.payload(), &b"hello"[..]);
+
+        let frame = conn.drive(frames.frame()).await.unwrap();
+        assert_eq!(frame.kind(), 0xb1);
+        assert_eq!(frame.stream_id(), 3);
+        assert!(frame.payload().is_empty());
+
+        conn.await.unwrap();
+        assert!(frames.frame().await.is_none());
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn recv_extension_frames_without_subscriber_are_ignored() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(frames::extension(0xb0, 0x0, 0, b"ignored"))
+            .await;
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/")
+                    .eos(),
+            )
+            .await;
+        client
+            .recv_frame(frames::headers(1).response(200).eos())
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+        let (req, mut stream) = srv.accept().await.unwrap().unwrap();
+        assert_eq!(req.method(), &http::Method::GET);
+
+        let rsp = http::Response::builder().status(200).body(()).unwrap();
+        stream.send_response(rsp, true).unwrap();
+
+        assert!(srv.accept().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn server_recv_extension_frames() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(frames::extension(0xfe, 0x4, 0, b"abc"))
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+        let mut frames = srv.extension_frames().expect("extension_frames");
+
+        let conn = async {
+            assert!(srv.accept().await.is_none());
+        };
+        let (frame, _) = join(frames.frame(), conn).await;
+        assert_eq!(frame.unwrap(), frames::extension(0xfe, 0x4, 0, b"abc"));
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn send_connection_extension_frame() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(frames::extension(0xb0, 0x1, 0, b"hello"))
+            .await;
+    };
+
+    let h2 = async move {
+        let (_client, mut conn) = client::handshake(io).await.unwrap();
+        // yield once so we can ack server settings
+        conn.drive(util::yield_once()).await;
+
+        conn.send_extension_frame(frames::extension(0xb0, 0x1, 0, b"hello"))
+            .unwrap();
+        conn.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn send_stream_extension_frame_after_data() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(frames::headers(1).request("POST", "https://example.com/"))
+            .await;
+        srv.recv_frame(frames::data(1, "hello")).await;
+        srv.recv_frame(frames::extension(0xb0, 0x2, 1, b"trailer"))
+            .await;
+        srv.recv_frame(frames::data(1, "").eos()).await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut conn) = client::handshake(io).await.unwrap();
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("https://example.com/")
+            .body(())
+            .unwrap();
+
+        let (response, mut stream) = client.send_request(request, false).unwrap();
+        stream.send_data("hello".into(), false).unwrap();
+        stream
+            .send_extension_frame(0xb0, 0x2, Bytes::from_static(b"trailer"))
+            .unwrap();
+        stream.send_data("".into(), true).unwrap();
+
+        let response = conn.drive(response).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The stream is closed, frames can no longer be sent on it.
+        assert!(stream
+            .send_extension_frame(0xb0, 0x0, Bytes::new())
+            .is_err());
+        conn.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn send_invalid_extension_frame() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut conn) = client::handshake(io).await.unwrap();
+
+        // DATA is defined by RFC 9113.
+        assert!(conn
+            .send_extension_frame(frames::extension(0x0, 0x0, 0, b""))
+            .is_err());
+        // CONTINUATION is too.
+        assert!(conn
+            .send_extension_frame(frames::extension(0x9, 0x0, 0, b""))
+            .is_err());
+
+        let big = Bytes::from(vec![0; 16_385]);
+        assert!(conn
+            .send_extension_frame(frame::ExtensionFrame::new(0xb0, 0, 0.into(), big.clone()))
+            .is_err());
+
+        let request = Request::builder()
+            .uri("https://example.com/")
+            .body(())
+            .unwrap();
+        let (response, mut stream) = client.send_request(request, true).unwrap();
+        assert!(stream.send_extension_frame(0x1, 0x0, Bytes::new()).is_err());
+        assert!(stream.send_extension_frame(0xb0, 0x0, big).is_err());
+
+        let response = conn.drive(response).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        conn.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
@@ -1989,3 +1989,252 @@ async fn reclaim_reserved_capacity() {
 
     join(mock, h2).await;
 }
+
+#[tokio::test]
+async fn send_padding_is_limited_by_window() {
+    use h2::ext::PaddingPolicy;
+
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv
+            .assert_client_handshake_with_settings(frames::settings().initial_window_size(10))
+            .await;
+        assert_default_settings!(settings);
+        let headers = assert_headers!(srv.next().await.unwrap().unwrap());
+        assert_eq!(headers.stream_id(), 1);
+
+        // Only 5 bytes of window are left once the data is accounted for,
+        // one of which goes to the Pad Length field.
+        let data = assert_data!(srv.next().await.unwrap().unwrap());
+        assert_eq!(data.payload(), &b"hello"[..]);
+        assert_eq!(data.pad_len(), Some(4));
+
+        srv.send_frame(frames::headers(1).response(200)).await;
+
+        srv.send_frame(frames::window_update(1, 20)).await;
+
+        let data = assert_data!(srv.next().await.unwrap().unwrap());
+        assert_eq!(data.payload(), &b"world"[..]);
+        assert_eq!(data.pad_len(), Some(8));
+        assert!(data.is_end_stream());
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::Builder::new()
+            .padding_policy(PaddingPolicy::Fixed(8))
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("https://www.example.com/")
+            .body(())
+            .unwrap();
+
+        let (response, mut stream) = client.send_request(request, false).unwrap();
+        stream.send_data("hello".into(), false).unwrap();
+
+        let response = h2.drive(response).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The padding used up the rest of the window, wait for more.
+        stream.reserve_capacity(5);
+
+        let mut stream = h2.drive(util::wait_for_capacity(stream, 5)).await;
+        stream.send_data("world".into(), true).unwrap();
+
+        h2.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn preface_script_window_update_grows_connection_window() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        srv.read_preface().await.unwrap();
+        let settings = assert_settings!(srv.next().await.unwrap().unwrap());
+        assert_eq!(settings.initial_window_size(), Some(200_000));
+        srv.recv_frame(frames::window_update(0, 134_465)).await;
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://www.example.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::settings()).await;
+        srv.send_frame(frames::settings_ack()).await;
+        srv.recv_frame(frames::settings_ack()).await;
+        srv.send_frame(frames::headers(1).response(200)).await;
+
+        // More than the default connection window, but within the window
+        // advertised by the preface.
+        for _ in 0..5 {
+            srv.send_frame(frames::data(1, vec![0; 16_384])).await;
+        }
+        srv.send_frame(frames::data(1, &b""[..]).eos()).await;
+    };
+
+    let h2 = async move {
+        let script = client::PrefaceScript::builder()
+            .window_update(134_465)
+            .build();
+        let (mut client, h2) = client::Builder::new()
+            .initial_window_size(200_000)
+            .preface(script)
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("https://www.example.com/")
+            .body(())
+            .unwrap();
+
+        let (response, _) = client.send_request(request, true).unwrap();
+        let fut = async move {
+            let response = response.await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = util::concat(response.into_body()).await.unwrap();
+            assert_eq!(body.len(), 5 * 16_384);
+        };
+        join(async move { h2.await.unwrap() }, fut).await;
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn client_adaptive_window_grows_windows() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200)).await;
+        srv.send_frame(frames::data(1, vec![b'a'; 16_384])).await;
+        srv.recv_frame(frames::ping(frame::Ping::BDP)).await;
+        srv.send_frame(frames::data(1, vec![b'b'; 16_384])).await;
+        srv.send_frame(frames::data(1, vec![b'c'; 16_384])).await;
+        srv.send_frame(frames::ping(frame::Ping::BDP).pong()).await;
+
+        // Most of the window was received in a round trip, so the windows
+        // grow to twice what was received.
+        srv.recv_frame(frames::settings().initial_window_size(98_304))
+            .await;
+        srv.recv_frame(frames::window_update(0, 32_769)).await;
+        srv.send_frame(frames::settings_ack()).await;
+
+        // More than the default windows, without any WINDOW_UPDATE for the
+        // stream.
+        srv.send_frame(frames::data(1, vec![b'd'; 16_384])).await;
+        srv.send_frame(frames::data(1, vec![b'e'; 16_384]).eos())
+            .await;
+    };
+
+    let client = async move {
+        let (mut client, conn) = client::Builder::new()
+            .adaptive_window(true)
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+        let request = Request::builder()
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+
+        let (response, _) = client.send_request(request, true).unwrap();
+        let fut = async move {
+            let response = response.await.unwrap();
+            // Capacity is never released.
+            let mut body = response.into_body();
+            let mut len = 0;
+            while let Some(data) = body.data().await {
+                len += data.unwrap().len();
+            }
+            assert_eq!(len, 5 * 16_384);
+        };
+        join(async move { conn.await.expect("client") }, fut).await;
+    };
+
+    join(srv, client).await;
+}
+
+#[tokio::test]
+async fn server_adaptive_window_is_limited_by_max() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(frames::headers(1).request("POST", "https://example.com/"))
+            .await;
+        client.send_frame(frames::data(1, vec![b'a'; 16_384])).await;
+        client.recv_frame(frames::ping(frame::Ping::BDP)).await;
+        for _ in 0..2 {
+            client.send_frame(frames::data(1, vec![b'a'; 16_384])).await;
+        }
+        client
+            .send_frame(frames::ping(frame::Ping::BDP).pong())
+            .await;
+
+        client
+            .recv_frame(frames::settings().initial_window_size(80_000))
+            .await;
+        client.recv_frame(frames::window_update(0, 14_465)).await;
+        client.send_frame(frames::settings_ack()).await;
+
+        // The window no longer grows, so no more PING is sent.
+        client
+            .send_frame(frames::data(1, vec![b'a'; 16_384]).eos())
+            .await;
+        // Capacity released as the request body is dropped.
+        client.recv_frame(frames::window_update(0, 65_536)).await;
+        client
+            .recv_frame(frames::headers(1).response(200).eos())
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::Builder::new()
+            .adaptive_window(true)
+            .max_adaptive_window_size(80_000)
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+
+        let (req, mut stream) = srv.accept().await.unwrap().unwrap();
+        let respond = async move {
+            // Capacity is never released.
+            let mut body = req.into_body();
+            let mut len = 0;
+            while let Some(data) = body.data().await {
+                len += data.unwrap().len();
+            }
+            assert_eq!(len, 4 * 16_384);
+
+            let rsp = http::Response::builder().status(200).body(()).unwrap();
+            stream.send_response(rsp, true).unwrap();
+        };
+        let srv_fut = async move {
+            assert!(srv.accept().await.is_none());
+        };
+        join(respond, srv_fut).await;
+    };
+
+    join(client, srv).await;
+}
@@ -0,0 +1,223 @@
+use futures::future::join;
+use futures::StreamExt;
+use h2::ext::{FrameKind, FrameObserver, ObservedFrame, PaddingPolicy};
+use h2_support::prelude::*;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// The HPACK encoding of a `GET https://example.com/` request.
+const REQUEST_BLOCK: [u8; 13] = [
+    0x82, 0x87, 0x41, 0x88, 0x2f, 0x91, 0xd3, 0x5d, 0x05, 0x5c, 0x87, 0xa7, 0x84,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+struct Observed {
+    kind: FrameKind,
+    stream_id: u32,
+    flags: u8,
+    payload_len: usize,
+    payload: Option<Vec<u8>>,
+    header_block: Option<Vec<u8>>,
+    timestamp: Instant,
+}
+
+#[derive(Default)]
+struct Recorder {
+    sent: Mutex<Vec<Observed>>,
+    received: Mutex<Vec<Observed>>,
+}
+
+impl Observed {
+    fn new(frame: &ObservedFrame<'_>) -> Self {
+        Observed {
+            kind: frame.kind(),
+            stream_id: frame.stream_id().as_u32(),
+            flags: frame.flags(),
+            payload_len: frame.payload_len(),
+            payload: frame.payload().map(<[u8]>::to_vec),
+            header_block: frame.header_block().map(<[u8]>::to_vec),
+            timestamp: frame.timestamp(),
+        }
+    }
+}
+
+impl FrameObserver for Recorder {
+    fn frame_received(&self, frame: &ObservedFrame<'_>) {
+        self.received.lock().unwrap().push(Observed::new(frame));
+    }
+
+    fn frame_sent(&self, frame: &ObservedFrame<'_>) {
+        self.sent.lock().unwrap().push(Observed::new(frame));
+    }
+}
+
+impl Recorder {
+    fn sent(&self) -> Vec<Observed> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    fn received(&self) -> Vec<Observed> {
+        self.received.lock().unwrap().clone()
+    }
+}
+
+/// Returns the type, stream and flags of the frames, checking that they were
+/// observed in order.
+fn heads(frames: &[Observed]) -> Vec<(FrameKind, u32, u8)> {
+    assert!(frames
+        .windows(2)
+        .all(|pair| pair[0].timestamp <= pair[1].timestamp));
+
+    frames
+        .iter()
+        .map(|frame| (frame.kind, frame.stream_id, frame.flags))
+        .collect()
+}
+
+#[tokio::test]
+async fn client_observes_frames() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200)).await;
+        srv.send_frame(frames::data(1, "hello").eos()).await;
+    };
+
+    let recorder = Arc::new(Recorder::default());
+
+    let h2 = async {
+        let (mut client, mut conn) = client::Builder::new()
+            .frame_observer(recorder.clone())
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+
+        let request = Request::builder()
+            .uri("https://example.com/")
+            .body(())
+            .unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+        let response = conn.drive(response).await.unwrap();
+        let body = conn.drive(util::concat(response.into_body())).await;
+        assert_eq!(body.unwrap(), "hello");
+
+        conn.await.unwrap();
+    };
+
+    join(srv, h2).await;
+
+    let sent = recorder.sent();
+    assert_eq!(
+        heads(&sent),
+        [
+            (FrameKind::Settings, 0, 0),
+            (FrameKind::Settings, 0, 0x1),
+            (FrameKind::Headers, 1, 0x5),
+        ]
+    );
+    assert_eq!(sent[2].payload_len, REQUEST_BLOCK.len());
+    assert_eq!(sent[2].header_block.as_deref(), Some(&REQUEST_BLOCK[..]));
+    assert_eq!(sent[0].header_block, None);
+
+    let received = recorder.received();
+    assert_eq!(
+        heads(&received),
+        [
+            (FrameKind::Settings, 0, 0),
+            (FrameKind::Settings, 0, 0x1),
+            (FrameKind::Headers, 1, 0x4),
+            (FrameKind::Data, 1, 0x1),
+        ]
+    );
+    assert_eq!(received[2].header_block.as_deref(), Some(&[0x88][..]));
+    assert_eq!(received[3].payload.as_deref(), Some(&b"hello"[..]));
+    assert_eq!(received[3].header_block, None);
+    assert!(sent[2].timestamp <= received[2].timestamp);
+}
+
+#[tokio::test]
+async fn server_observes_header_blocks() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/")
+                    .priority(0, 15, false)
+                    .eos(),
+            )
+            .await;
+        // Padded frames do not compare equal to the frames the mock builds.
+        let frame = client.next().await.unwrap().unwrap();
+        assert!(matches!(frame, frame::Frame::Headers(_)), "{:?}", frame);
+        let frame = client.next().await.unwrap().unwrap();
+        assert!(matches!(frame, frame::Frame::Data(_)), "{:?}", frame);
+    };
+
+    let recorder = Arc::new(Recorder::default());
+
+    let srv = async {
+        let mut srv = server::Builder::new()
+            .padding_policy(PaddingPolicy::Block(16))
+            .frame_observer(recorder.clone())
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+
+        let (_, mut stream) = srv.accept().await.unwrap().unwrap();
+        let rsp = Response::builder().status(200).body(()).unwrap();
+        let mut body = stream.send_response(rsp, false).unwrap();
+        body.send_data("hello".into(), true).unwrap();
+
+        assert!(srv.accept().await.is_none());
+    };
+
+    join(client, srv).await;
+
+    // The priority fields are not part of the header block.
+    let received = recorder.received();
+    assert_eq!(
+        heads(&received),
+        [
+            (FrameKind::Settings, 0, 0),
+            (FrameKind::Settings, 0, 0x1),
+            (FrameKind::Headers, 1, 0x25),
+        ]
+    );
+    assert_eq!(received[2].payload_len, 5 + REQUEST_BLOCK.len());
+    assert_eq!(
+        received[2].header_block.as_deref(),
+        Some(&REQUEST_BLOCK[..])
+    );
+
+    // Neither is the padding.
+    let sent = recorder.sent();
+    assert_eq!(
+        heads(&sent),
+        [
+            (FrameKind::Settings, 0, 0),
+            (FrameKind::Settings, 0, 0x1),
+            (FrameKind::Headers, 1, 0xc),
+            (FrameKind::Data, 1, 0x9),
+        ]
+    );
+    assert_eq!(sent[2].payload_len, 16);
+    assert_eq!(sent[2].header_block.as_deref(), Some(&[0x88][..]));
+
+    // The payload of sent DATA frames is not copied.
+    assert_eq!(sent[3].payload_len, 16);
+    assert_eq!(sent[3].payload, None);
+}
@@ -0,0 +1,221 @@
+use futures::future::join;
+use h2_support::prelude::*;
+
+#[tokio::test]
+async fn client_recv_origin_set() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::origin(&[
+            "https://example.com",
+            "https://example.net",
+        ]))
+        .await;
+        srv.send_frame(frames::origin(&[
+            "https://example.net",
+            "https://example.org:8443",
+        ]))
+        .await;
+        // ORIGIN frames on a stream other than zero are ignored.
+        let entry = b"https://evil.example";
+        let mut raw = vec![0, 0, entry.len() as u8 + 2, 0xc, 0, 0, 0, 0, 1, 0];
+        raw.push(entry.len() as u8);
+        raw.extend_from_slice(entry);
+        srv.send_bytes(&raw).await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut conn) = client::handshake(io).await.unwrap();
+        assert_eq!(client.origin_set(), None);
+
+        let request = Request::builder()
+            .uri("https://example.com/")
+            .body(())
+            .unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+        let response = conn.drive(response).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        assert_eq!(
+            client.origin_set().unwrap(),
+            [
+                "https://example.com",
+                "https://example.net",
+                "https://example.org:8443",
+            ]
+        );
+        conn.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn client_recv_empty_origin_set() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::origin(&[])).await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut conn) = client::handshake(io).await.unwrap();
+
+        let request = Request::builder()
+            .uri("https://example.com/")
+            .body(())
+            .unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+        conn.drive(response).await.unwrap();
+
+        // An empty ORIGIN frame still initializes the origin set.
+        assert_eq!(client.origin_set(), Some(vec![]));
+        conn.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn server_send_origin_set() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/")
+                    .eos(),
+            )
+            .await;
+        client
+            .recv_frame(frames::origin(&[
+                "https://example.com",
+                "https://example.com:8443",
+            ]))
+            .await;
+        client
+            .recv_frame(frames::headers(1).response(200).eos())
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+        let (_, mut stream) = srv.accept().await.unwrap().unwrap();
+        srv.send_origin_set(["https://example.com", "https://example.com:8443"])
+            .unwrap();
+        let rsp = http::Response::builder().status(200).body(()).unwrap();
+        stream.send_response(rsp, true).unwrap();
+        assert!(srv.accept().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn server_send_invalid_origin_set() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/")
+                    .eos(),
+            )
+            .await;
+        client
+            .recv_frame(frames::origin(&["https://example.com"]))
+            .await;
+        client
+            .recv_frame(frames::headers(1).response(200).eos())
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+        let (_, mut stream) = srv.accept().await.unwrap().unwrap();
+
+        let err = srv
+            .send_origin_set(["https://example.com", "https://example.com/path"])
+            .unwrap_err();
+        assert_eq!(err.to_string(), "user error: invalid origin");
+
+        let long = format!("https://{}.example", "a".repeat(16_384));
+        assert!(srv.send_origin_set([long]).is_err());
+
+        // Nothing was sent by the failed calls.
+        srv.send_origin_set(["https://example.com"]).unwrap();
+        let rsp = http::Response::builder().status(200).body(()).unwrap();
+        stream.send_response(rsp, true).unwrap();
+        assert!(srv.accept().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn server_ignores_recv_origin() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(frames::origin(&["https://example.com"]))
+            .await;
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/")
+                    .eos(),
+            )
+            .await;
+        client
+            .recv_frame(frames::headers(1).response(200).eos())
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+        let mut frames = srv.extension_frames().unwrap();
+
+        let (_, mut stream) = srv.accept().await.unwrap().unwrap();
+        let rsp = http::Response::builder().status(200).body(()).unwrap();
+        stream.send_response(rsp, true).unwrap();
+        assert!(srv.accept().await.is_none());
+
+        // ORIGIN is not handed out as an unknown extension frame.
+        drop(srv);
+        assert!(frames.frame().await.is_none());
+    };
+
+    join(client, srv).await;
+}
@@ -134,6 +134,85 @@ async fn user_ping_pong() {
     join(srv, client).await;
 }
 
+#[tokio::test]
+async fn user_ping_measures_rtt() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(frames::ping(frame::Ping::USER)).await;
+        idle_ms(10).await;
+        srv.send_frame(frames::ping(frame::Ping::USER).pong()).await;
+        srv.recv_frame(frames::go_away(0)).await;
+        srv.recv_eof().await;
+    };
+
+    let client = async move {
+        let (client, mut conn) = client::handshake(io).await.expect("client handshake");
+        assert_eq!(conn.smoothed_rtt(), None);
+        assert_eq!(conn.min_rtt(), None);
+
+        // yield once so we can ack server settings
+        conn.drive(util::yield_once()).await;
+        // `ping_pong()` method conflict with mock future ext trait.
+        let ping_pong = client::Connection::ping_pong(&mut conn).expect("taking ping_pong");
+        let rtt = conn.drive(ping_pong.ping(Ping::opaque())).await.unwrap();
+        assert!(rtt >= Duration::from_millis(10));
+        assert_eq!(conn.smoothed_rtt(), Some(rtt));
+        assert_eq!(conn.min_rtt(), Some(rtt));
+
+        drop(client);
+        conn.await.expect("client");
+    };
+
+    join(srv, client).await;
+}
+
+#[tokio::test]
+async fn user_pings_in_flight() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+    let second = (u64::from_be_bytes(frame::Ping::USER) + 1).to_be_bytes();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(frames::ping(frame::Ping::USER)).await;
+        srv.recv_frame(frames::ping(second)).await;
+        srv.send_frame(frames::ping(second).pong()).await;
+        idle_ms(10).await;
+        srv.send_frame(frames::ping(frame::Ping::USER).pong()).await;
+        srv.recv_frame(frames::go_away(0)).await;
+        srv.recv_eof().await;
+    };
+
+    let client = async move {
+        let (client, mut conn) = client::handshake(io).await.expect("client handshake");
+        // yield once so we can ack server settings
+        conn.drive(util::yield_once()).await;
+        // `ping_pong()` method conflict with mock future ext trait.
+        let ping_pong = client::Connection::ping_pong(&mut conn).expect("taking ping_pong");
+
+        let (first, second) = conn
+            .drive(join(
+                ping_pong.ping(Ping::opaque()),
+                ping_pong.ping(Ping::opaque()),
+            ))
+            .await;
+        let (first, second) = (first.unwrap(), second.unwrap());
+        assert!(first > second);
+        assert_eq!(conn.min_rtt(), Some(second));
+        assert!(conn.smoothed_rtt().unwrap() > second);
+
+        drop(client);
+        conn.await.expect("client");
+    };
+
+    join(srv, client).await;
+}
+
 #[tokio::test]
 async fn user_notifies_when_connection_closes() {
     h2_support::trace_init!();
@@ -183,3 +262,170 @@ async fn user_notifies_when_connection_closes() {
         "broken pipe",
     );
 }
+
+#[tokio::test]
+async fn client_keep_alive_ping_acked() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+        srv.recv_frame(frames::ping(frame::Ping::KEEP_ALIVE)).await;
+        srv.send_frame(frames::ping(frame::Ping::KEEP_ALIVE).pong())
+            .await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+        srv.recv_frame(frames::go_away(0)).await;
+        srv.recv_eof().await;
+    };
+
+    let client = async move {
+        let (mut client, mut conn) = client::Builder::new()
+            .keep_alive_interval(Duration::from_millis(20))
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("client handshake");
+
+        let request = Request::builder()
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+        let response = conn.drive(response).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        drop(response);
+        drop(client);
+        conn.await.expect("client");
+    };
+
+    join(srv, client).await;
+}
+
+#[tokio::test]
+async fn client_keep_alive_timeout() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+        srv.recv_frame(frames::ping(frame::Ping::KEEP_ALIVE)).await;
+        // No pong, the connection is closed without a GOAWAY.
+        srv.recv_eof().await;
+    };
+
+    let client = async move {
+        let (mut client, conn) = client::Builder::new()
+            .keep_alive_interval(Duration::from_millis(20))
+            .keep_alive_timeout(Duration::from_millis(20))
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("client handshake");
+
+        let request = Request::builder()
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+
+        let (conn, response) = join(conn, response).await;
+        assert!(conn.unwrap_err().is_keep_alive_timeout());
+        assert!(response.unwrap_err().is_keep_alive_timeout());
+    };
+
+    join(srv, client).await;
+}
+
+#[tokio::test]
+async fn server_keep_alive_waits_for_streams() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+
+        // The connection is idle, so no keep-alive PING is sent before the
+        // pong.
+        idle_ms(60).await;
+        client.send_frame(frames::ping([1; 8])).await;
+        client.recv_frame(frames::ping([1; 8]).pong()).await;
+
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://http2.akamai.com/")
+                    .eos(),
+            )
+            .await;
+        client
+            .recv_frame(frames::ping(frame::Ping::KEEP_ALIVE))
+            .await;
+        client.recv_eof().await;
+    };
+
+    let srv = async move {
+        let mut srv = server::Builder::new()
+            .keep_alive_interval(Duration::from_millis(20))
+            .keep_alive_timeout(Duration::from_millis(20))
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+
+        let (_req, _stream) = srv.accept().await.unwrap().unwrap();
+        let err = srv.accept().await.unwrap().unwrap_err();
+        assert!(err.is_keep_alive_timeout());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn server_keep_alive_while_idle() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .recv_frame(frames::ping(frame::Ping::KEEP_ALIVE))
+            .await;
+        client
+            .send_frame(frames::ping(frame::Ping::KEEP_ALIVE).pong())
+            .await;
+        // The acknowledgement restarts the interval.
+        client
+            .recv_frame(frames::ping(frame::Ping::KEEP_ALIVE))
+            .await;
+        client.recv_eof().await;
+    };
+
+    let srv = async move {
+        let mut srv = server::Builder::new()
+            .keep_alive_interval(Duration::from_millis(20))
+            .keep_alive_timeout(Duration::from_millis(20))
+            .keep_alive_while_idle(true)
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+
+        let err = srv.accept().await.unwrap().unwrap_err();
+        assert!(err.is_keep_alive_timeout());
+    };
+
+    join(client, srv).await;
+}
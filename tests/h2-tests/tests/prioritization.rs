@@ -1,5 +1,6 @@
 use futures::{pin_mut, FutureExt, StreamExt};
 
+use h2::ext::{SchedulingStrategy, StreamDependency};
 use h2_support::prelude::*;
 use h2_support::DEFAULT_WINDOW_SIZE;
 use std::task::Context;
@@ -500,3 +501,894 @@ async fn stream_count_over_max_stream_limit_does_not_starve_capacity() {
 
     select(task, t).await;
 }
+
+#[tokio::test]
+async fn server_sends_urgent_responses_first() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/")
+                    .field("priority", "u=5")
+                    .eos(),
+            )
+            .await;
+        client
+            .send_frame(
+                frames::headers(3)
+                    .request("GET", "https://example.com/")
+                    .field("priority", "u=1")
+                    .eos(),
+            )
+            .await;
+        client
+            .send_frame(
+                frames::headers(5)
+                    .request("GET", "https://example.com/")
+                    .eos(),
+            )
+            .await;
+        client.recv_frame(frames::headers(3).response(200)).await;
+        client.recv_frame(frames::data(3, "three").eos()).await;
+        client.recv_frame(frames::headers(5).response(200)).await;
+        client.recv_frame(frames::data(5, "five").eos()).await;
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.recv_frame(frames::data(1, "one").eos()).await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+
+        let mut streams = Vec::new();
+        for _ in 0..3 {
+            let (_, stream) = srv.accept().await.unwrap().unwrap();
+            streams.push(stream);
+        }
+
+        // Queue all the responses before the connection writes any of them.
+        for (mut stream, body) in streams.into_iter().zip(["one", "three", "five"]) {
+            let rsp = http::Response::builder().status(200).body(()).unwrap();
+            let mut stream = stream.send_response(rsp, false).unwrap();
+            stream.send_data(body.into(), true).unwrap();
+
+            // Only clients send PRIORITY_UPDATE frames.
+            assert!(stream
+                .send_priority_update(frame::ExtensiblePriority::default())
+                .is_err());
+        }
+
+        assert!(srv.accept().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn server_interleaves_incremental_responses() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        for id in [1, 3, 5, 7] {
+            let priority = if id < 5 { "u=2, i" } else { "u=4" };
+            client
+                .send_frame(
+                    frames::headers(id)
+                        .request("GET", "https://example.com/")
+                        .field("priority", priority)
+                        .eos(),
+                )
+                .await;
+        }
+
+        // Incremental responses take turns.
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.recv_frame(frames::headers(3).response(200)).await;
+        client.recv_frame(frames::data(1, "one")).await;
+        client.recv_frame(frames::data(3, "three")).await;
+        client.recv_frame(frames::data(1, "one").eos()).await;
+        client.recv_frame(frames::data(3, "three").eos()).await;
+
+        // The others are sent one after the other.
+        client.recv_frame(frames::headers(5).response(200)).await;
+        client.recv_frame(frames::data(5, "five")).await;
+        client.recv_frame(frames::data(5, "five").eos()).await;
+        client.recv_frame(frames::headers(7).response(200)).await;
+        client.recv_frame(frames::data(7, "seven")).await;
+        client.recv_frame(frames::data(7, "seven").eos()).await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+
+        let mut streams = Vec::new();
+        for _ in 0..4 {
+            let (_, stream) = srv.accept().await.unwrap().unwrap();
+            streams.push(stream);
+        }
+
+        let bodies = ["one", "three", "five", "seven"];
+        for (mut stream, body) in streams.into_iter().zip(bodies) {
+            let rsp = http::Response::builder().status(200).body(()).unwrap();
+            let mut stream = stream.send_response(rsp, false).unwrap();
+            stream.send_data(body.into(), false).unwrap();
+            stream.send_data(body.into(), true).unwrap();
+        }
+
+        assert!(srv.accept().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn server_recv_priority_update() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/")
+                    .eos(),
+            )
+            .await;
+        client
+            .send_frame(
+                frames::headers(3)
+                    .request("GET", "https://example.com/")
+                    .field("priority", "u=4")
+                    .eos(),
+            )
+            .await;
+        client
+            .send_frame(frames::priority_update(3, 0, false))
+            .await;
+        // Received before the request, and takes precedence over its header.
+        client
+            .send_frame(frames::priority_update(5, 0, false))
+            .await;
+        client
+            .send_frame(
+                frames::headers(5)
+                    .request("GET", "https://example.com/")
+                    .field("priority", "u=7")
+                    .eos(),
+            )
+            .await;
+        client.recv_frame(frames::headers(3).response(200)).await;
+        client.recv_frame(frames::data(3, "three").eos()).await;
+        client.recv_frame(frames::headers(5).response(200)).await;
+        client.recv_frame(frames::data(5, "five").eos()).await;
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.recv_frame(frames::data(1, "one").eos()).await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+
+        let mut streams = Vec::new();
+        for _ in 0..3 {
+            let (_, stream) = srv.accept().await.unwrap().unwrap();
+            streams.push(stream);
+        }
+
+        for (mut stream, body) in streams.into_iter().zip(["one", "three", "five"]) {
+            let rsp = http::Response::builder().status(200).body(()).unwrap();
+            let mut stream = stream.send_response(rsp, false).unwrap();
+            stream.send_data(body.into(), true).unwrap();
+        }
+
+        assert!(srv.accept().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn client_send_priority_update() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("POST", "https://example.com/")
+                .field("priority", "u=5"),
+        )
+        .await;
+        srv.recv_frame(frames::priority_update(1, 1, true)).await;
+        srv.recv_frame(frames::data(1, "hello").eos()).await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut conn) = client::handshake(io).await.unwrap();
+
+        let priority = frame::ExtensiblePriority::new(5, false);
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("https://example.com/")
+            .header("priority", priority.to_header_value())
+            .body(())
+            .unwrap();
+        let (response, mut stream) = client.send_request(request, false).unwrap();
+        stream.send_data("hello".into(), true).unwrap();
+
+        // Sent ahead of the queued body, but after the request headers.
+        stream
+            .send_priority_update(frame::ExtensiblePriority::new(1, true))
+            .unwrap();
+
+        let response = conn.drive(response).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let err = stream
+            .send_priority_update(frame::ExtensiblePriority::default())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "user error: inactive stream");
+        conn.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn client_recv_priority_update_is_connection_error() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.send_frame(frames::priority_update(1, 0, false)).await;
+        srv.recv_frame(frames::go_away(0).protocol_error()).await;
+    };
+
+    let h2 = async move {
+        let (_client, h2) = client::handshake(io).await.unwrap();
+
+        let err = h2.await.unwrap_err();
+        assert!(err.is_go_away());
+        assert_eq!(err.reason(), Some(Reason::PROTOCOL_ERROR));
+    };
+
+    join(srv, h2).await;
+}
+
+/// Accepts `n` requests, then responds to them in the order they were
+/// received, each with a body of one or more DATA frames.
+async fn respond_to_all<T>(mut srv: server::Connection<T, Bytes>, n: usize, frames: usize)
+where
+    T: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let mut streams = Vec::new();
+    for _ in 0..n {
+        let (req, stream) = srv.accept().await.unwrap().unwrap();
+        streams.push((req.uri().path().to_owned(), stream));
+    }
+
+    // Queue all the responses before the connection writes any of them.
+    for (path, mut stream) in streams {
+        let rsp = http::Response::builder().status(200).body(()).unwrap();
+        let mut stream = stream.send_response(rsp, false).unwrap();
+        for i in 0..frames {
+            let body = Bytes::copy_from_slice(path.as_bytes());
+            stream.send_data(body, i + 1 == frames).unwrap();
+        }
+    }
+
+    assert!(srv.accept().await.is_none());
+}
+
+#[tokio::test]
+async fn server_ignores_rfc7540_priorities_by_default() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        for id in [1, 3] {
+            client
+                .send_frame(
+                    frames::headers(id)
+                        .request("GET", format!("https://example.com/{}", id))
+                        .eos(),
+                )
+                .await;
+        }
+        client.send_frame(frames::priority(1, 3, 15, false)).await;
+
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.recv_frame(frames::headers(3).response(200)).await;
+        client.recv_frame(frames::data(1, "/1").eos()).await;
+        client.recv_frame(frames::data(3, "/3").eos()).await;
+    };
+
+    let srv = async move {
+        let srv = server::handshake(io).await.expect("handshake");
+        respond_to_all(srv, 2, 1).await;
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn server_sends_dependencies_first() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        for id in [1, 3, 5] {
+            client
+                .send_frame(
+                    frames::headers(id)
+                        .request("GET", format!("https://example.com/{}", id))
+                        .eos(),
+                )
+                .await;
+        }
+        // 5 <- 1 <- 3
+        client.send_frame(frames::priority(1, 5, 15, false)).await;
+        client.send_frame(frames::priority(3, 1, 15, false)).await;
+
+        for id in [5, 1, 3] {
+            let body = format!("/{}", id);
+            client.recv_frame(frames::headers(id).response(200)).await;
+            client.recv_frame(frames::data(id, body.clone())).await;
+            client.recv_frame(frames::data(id, body).eos()).await;
+        }
+    };
+
+    let srv = async move {
+        let srv = server::Builder::new()
+            .enable_rfc7540_priorities()
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+        respond_to_all(srv, 3, 2).await;
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn server_recv_exclusive_headers_priority() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        for id in [1, 3] {
+            client
+                .send_frame(
+                    frames::headers(id)
+                        .request("GET", format!("https://example.com/{}", id))
+                        .eos(),
+                )
+                .await;
+        }
+        // Stream 5 becomes the parent of streams 1 and 3.
+        client
+            .send_frame(
+                frames::headers(5)
+                    .request("GET", "https://example.com/5")
+                    .priority(0, 15, true)
+                    .eos(),
+            )
+            .await;
+
+        client.recv_frame(frames::headers(5).response(200)).await;
+        client.recv_frame(frames::data(5, "/5").eos()).await;
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.recv_frame(frames::headers(3).response(200)).await;
+        client.recv_frame(frames::data(1, "/1").eos()).await;
+        client.recv_frame(frames::data(3, "/3").eos()).await;
+    };
+
+    let srv = async move {
+        let srv = server::Builder::new()
+            .enable_rfc7540_priorities()
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+        respond_to_all(srv, 3, 1).await;
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn server_shares_connection_by_weight() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        // Weights of 256 and 1.
+        for (id, weight) in [(1, 255), (3, 0)] {
+            client
+                .send_frame(
+                    frames::headers(id)
+                        .request("GET", format!("https://example.com/{}", id))
+                        .priority(0, weight, false)
+                        .eos(),
+                )
+                .await;
+        }
+
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.recv_frame(frames::headers(3).response(200)).await;
+        client.recv_frame(frames::data(1, "/1")).await;
+        client.recv_frame(frames::data(1, "/1")).await;
+        client.recv_frame(frames::data(1, "/1").eos()).await;
+        client.recv_frame(frames::data(3, "/3")).await;
+        client.recv_frame(frames::data(3, "/3")).await;
+        client.recv_frame(frames::data(3, "/3").eos()).await;
+    };
+
+    let srv = async move {
+        let srv = server::Builder::new()
+            .enable_rfc7540_priorities()
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+        respond_to_all(srv, 2, 3).await;
+    };
+
+    join(client, srv).await;
+}
+
+async fn respond_with_body<T>(mut srv: server::Connection<T, Bytes>, n: usize, len: usize)
+where
+    T: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let mut streams = Vec::new();
+    for _ in 0..n {
+        let (_, stream) = srv.accept().await.unwrap().unwrap();
+        streams.push(stream);
+    }
+
+    for mut stream in streams {
+        let rsp = http::Response::builder().status(200).body(()).unwrap();
+        let mut stream = stream.send_response(rsp, false).unwrap();
+        stream.send_data(vec![b'a'; len].into(), true).unwrap();
+    }
+
+    assert!(srv.accept().await.is_none());
+}
+
+#[tokio::test]
+async fn server_round_robin_splits_frames_by_quantum() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        for id in [1, 3] {
+            client
+                .send_frame(
+                    frames::headers(id)
+                        .request("GET", format!("https://example.com/{}", id))
+                        .eos(),
+                )
+                .await;
+        }
+
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.recv_frame(frames::headers(3).response(200)).await;
+        client.recv_frame(frames::data(1, [b'a'; 10])).await;
+        client.recv_frame(frames::data(3, [b'a'; 10])).await;
+        client.recv_frame(frames::data(1, [b'a'; 10])).await;
+        client.recv_frame(frames::data(3, [b'a'; 10])).await;
+        client.recv_frame(frames::data(1, [b'a'; 10]).eos()).await;
+        client.recv_frame(frames::data(3, [b'a'; 10]).eos()).await;
+    };
+
+    let srv = async move {
+        let srv = server::Builder::new()
+            .scheduling_strategy(SchedulingStrategy::RoundRobin { quantum: 10 })
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+        respond_with_body(srv, 2, 30).await;
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn server_round_robin_turn_spans_frames() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        for id in [1, 3] {
+            client
+                .send_frame(
+                    frames::headers(id)
+                        .request("GET", format!("https://example.com/{}", id))
+                        .eos(),
+                )
+                .await;
+        }
+
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.recv_frame(frames::headers(3).response(200)).await;
+        // Each body is 4 frames of 2 bytes, within a single turn.
+        for id in [1, 3] {
+            for _ in 0..3 {
+                client
+                    .recv_frame(frames::data(id, format!("/{}", id)))
+                    .await;
+            }
+            client
+                .recv_frame(frames::data(id, format!("/{}", id)).eos())
+                .await;
+        }
+    };
+
+    let srv = async move {
+        let srv = server::Builder::new()
+            .scheduling_strategy(SchedulingStrategy::RoundRobin { quantum: 20 })
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+        respond_to_all(srv, 2, 4).await;
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn server_weighted_round_robin_uses_stream_weights() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        // A weight of 32, twice the default.
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/1")
+                    .priority(0, 31, false)
+                    .eos(),
+            )
+            .await;
+        client
+            .send_frame(
+                frames::headers(3)
+                    .request("GET", "https://example.com/3")
+                    .eos(),
+            )
+            .await;
+
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.recv_frame(frames::headers(3).response(200)).await;
+        client.recv_frame(frames::data(1, [b'a'; 20])).await;
+        client.recv_frame(frames::data(3, [b'a'; 10])).await;
+        client.recv_frame(frames::data(1, [b'a'; 20]).eos()).await;
+        client.recv_frame(frames::data(3, [b'a'; 10])).await;
+        client.recv_frame(frames::data(3, [b'a'; 10])).await;
+        client.recv_frame(frames::data(3, [b'a'; 10]).eos()).await;
+    };
+
+    let srv = async move {
+        let srv = server::Builder::new()
+            .scheduling_strategy(SchedulingStrategy::WeightedRoundRobin { quantum: 10 })
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+        respond_with_body(srv, 2, 40).await;
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn client_round_robin_interleaves_request_bodies() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        for id in [1, 3] {
+            srv.recv_frame(frames::headers(id).request("POST", "https://example.com/"))
+                .await;
+        }
+        for _ in 0..2 {
+            srv.recv_frame(frames::data(1, [b'a'; 10])).await;
+            srv.recv_frame(frames::data(3, [b'a'; 10])).await;
+        }
+        srv.recv_frame(frames::data(1, [b'a'; 10]).eos()).await;
+        srv.recv_frame(frames::data(3, [b'a'; 10]).eos()).await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+        srv.send_frame(frames::headers(3).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut conn) = client::Builder::new()
+            .scheduling_strategy(SchedulingStrategy::RoundRobin { quantum: 10 })
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+
+        let mut responses = Vec::new();
+        for _ in 0..2 {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri("https://example.com/")
+                .body(())
+                .unwrap();
+            let (response, mut stream) = client.send_request(request, false).unwrap();
+            stream.send_data(vec![b'a'; 30].into(), true).unwrap();
+            responses.push(response);
+        }
+
+        for response in responses {
+            let response = conn.drive(response).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        conn.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn client_round_robin_turn_restarts_after_running_dry() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        for id in [1, 3] {
+            srv.recv_frame(frames::headers(id).request("POST", "https://example.com/"))
+                .await;
+        }
+        srv.recv_frame(frames::data(1, [b'a'; 4])).await;
+        // Stream 1 ran out of data in the middle of its last turn, and gets
+        // a whole turn when it is scheduled again.
+        srv.recv_frame(frames::data(3, [b'b'; 10])).await;
+        srv.recv_frame(frames::data(1, [b'a'; 10])).await;
+        srv.recv_frame(frames::data(3, [b'b'; 10]).eos()).await;
+        srv.recv_frame(frames::data(1, [b'a'; 10]).eos()).await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+        srv.send_frame(frames::headers(3).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut conn) = client::Builder::new()
+            .scheduling_strategy(SchedulingStrategy::RoundRobin { quantum: 10 })
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+
+        let mut requests = Vec::new();
+        for _ in 0..2 {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri("https://example.com/")
+                .body(())
+                .unwrap();
+            requests.push(client.send_request(request, false).unwrap());
+        }
+
+        requests[0]
+            .1
+            .send_data(vec![b'a'; 4].into(), false)
+            .unwrap();
+        conn.drive(util::yield_once()).await;
+
+        requests[1]
+            .1
+            .send_data(vec![b'b'; 20].into(), true)
+            .unwrap();
+        requests[0]
+            .1
+            .send_data(vec![b'a'; 20].into(), true)
+            .unwrap();
+
+        for (response, _) in requests {
+            let response = conn.drive(response).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        conn.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[test]
+#[should_panic(expected = "scheduling quantum must be at least 1")]
+fn scheduling_quantum_must_not_be_zero() {
+    server::Builder::new().scheduling_strategy(SchedulingStrategy::RoundRobin { quantum: 0 });
+}
+
+#[tokio::test]
+async fn client_set_priority_assigns_capacity_first() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        for id in [1, 3, 5] {
+            srv.recv_frame(frames::headers(id).request("POST", "https://example.com/"))
+                .await;
+        }
+        // Stream 1 uses up the connection window.
+        for _ in 0..3 {
+            srv.recv_frame(frames::data(1, vec![b'a'; 16_384])).await;
+        }
+        srv.recv_frame(frames::data(1, vec![b'a'; 16_383]).eos())
+            .await;
+
+        // Stream 5 asked for capacity last, but has the larger weight.
+        srv.send_frame(frames::window_update(0, 10)).await;
+        srv.recv_frame(frames::data(5, [b'a'; 10]).eos()).await;
+        srv.send_frame(frames::window_update(0, 10)).await;
+        srv.recv_frame(frames::data(3, [b'a'; 10]).eos()).await;
+
+        for id in [1, 3, 5] {
+            srv.send_frame(frames::headers(id).response(200).eos())
+                .await;
+        }
+    };
+
+    let h2 = async move {
+        let (mut client, mut conn) = client::handshake(io).await.unwrap();
+
+        let mut responses = Vec::new();
+        for len in [65_535, 10, 10] {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri("https://example.com/")
+                .body(())
+                .unwrap();
+            let (response, mut stream) = client.send_request(request, false).unwrap();
+            stream.send_data(vec![b'a'; len].into(), true).unwrap();
+            responses.push((response, stream));
+        }
+
+        let weight = StreamDependency::new(StreamId::zero(), 255, false);
+        responses[2].1.set_priority(weight).unwrap();
+
+        for (response, _) in responses {
+            let response = conn.drive(response).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        conn.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn client_send_priority() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(frames::headers(1).request("POST", "https://example.com/"))
+            .await;
+        srv.recv_frame(frames::priority(1, 0, 255, true)).await;
+        srv.recv_frame(frames::data(1, "hello").eos()).await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut conn) = client::handshake(io).await.unwrap();
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("https://example.com/")
+            .body(())
+            .unwrap();
+        let (response, mut stream) = client.send_request(request, false).unwrap();
+        stream.send_data("hello".into(), true).unwrap();
+
+        let err = stream
+            .send_priority(StreamDependency::new(1.into(), 0, false))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "user error: invalid stream dependency");
+
+        // Sent ahead of the queued body, but after the request headers.
+        stream
+            .send_priority(StreamDependency::new(StreamId::zero(), 255, true))
+            .unwrap();
+
+        let response = conn.drive(response).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        conn.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn server_set_priority_scales_weighted_turns() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        for id in [1, 3] {
+            client
+                .send_frame(
+                    frames::headers(id)
+                        .request("GET", format!("https://example.com/{}", id))
+                        .eos(),
+                )
+                .await;
+        }
+
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.recv_frame(frames::headers(3).response(200)).await;
+        client.recv_frame(frames::data(1, [b'a'; 10])).await;
+        client.recv_frame(frames::data(3, [b'a'; 20])).await;
+        client.recv_frame(frames::data(1, [b'a'; 10])).await;
+        client.recv_frame(frames::data(3, [b'a'; 20]).eos()).await;
+        client.recv_frame(frames::data(1, [b'a'; 10])).await;
+        client.recv_frame(frames::data(1, [b'a'; 10]).eos()).await;
+    };
+
+    let srv = async move {
+        let mut srv = server::Builder::new()
+            .scheduling_strategy(SchedulingStrategy::WeightedRoundRobin { quantum: 10 })
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+
+        let (_, mut stream1) = srv.accept().await.unwrap().unwrap();
+        let (_, mut stream3) = srv.accept().await.unwrap().unwrap();
+
+        // Twice the default weight.
+        stream3
+            .set_priority(StreamDependency::new(StreamId::zero(), 31, false))
+            .unwrap();
+
+        for stream in [&mut stream1, &mut stream3] {
+            let rsp = http::Response::builder().status(200).body(()).unwrap();
+            let mut stream = stream.send_response(rsp, false).unwrap();
+
+            let err = stream
+                .send_priority(StreamDependency::new(StreamId::zero(), 0, false))
+                .unwrap_err();
+            assert_eq!(err.to_string(), "user error: unexpected frame type");
+
+            stream.send_data(vec![b'a'; 40].into(), true).unwrap();
+        }
+
+        assert!(srv.accept().await.is_none());
+    };
+
+    join(client, srv).await;
+}
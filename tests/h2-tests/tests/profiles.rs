@@ -0,0 +1,421 @@
+use h2_support::prelude::*;
+use tokio::io::AsyncReadExt;
+
+/// Sends a `GET https://example.com/` request using `profile` and asserts
+/// that everything written after the connection preface magic matches
+/// `expected` byte for byte.
+async fn assert_profile_wire(profile: client::Profile, expected: &[&[u8]]) {
+    let mut builder = client::Builder::new();
+    builder.profile(profile);
+    assert_wire(builder, 1, expected).await;
+}
+
+/// Like `assert_profile_wire`, but for an arbitrary client configuration that
+/// sends the request on `stream_id`.
+async fn assert_wire(builder: client::Builder, stream_id: u32, expected: &[&[u8]]) {
+    h2_support::trace_init!();
+
+    let expected = expected.concat();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        srv.read_preface().await.unwrap();
+
+        let mut actual = vec![0; expected.len()];
+        srv.read_exact(&mut actual).await.unwrap();
+        assert_eq!(actual, expected);
+
+        srv.send_frame(frames::settings()).await;
+        srv.recv_frame(frames::settings_ack()).await;
+        srv.send_frame(frames::settings_ack()).await;
+        srv.send_frame(frames::headers(stream_id).response(200).eos())
+            .await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = builder.handshake::<_, Bytes>(io).await.unwrap();
+
+        // The fields are deliberately inserted in an order no profile uses.
+        let request = Request::get("https://example.com/")
+            .header("accept-encoding", "gzip, deflate, br")
+            .header("accept", "*/*")
+            .header("user-agent", "h2")
+            .body(())
+            .unwrap();
+
+        let (response, _) = client.send_request(request, true).unwrap();
+        let response = h2.drive(response).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    };
+
+    join(srv, h2).await;
+}
+
+// :method GET
+const METHOD_GET: &[u8] = &[0x82];
+// :scheme https
+const SCHEME_HTTPS: &[u8] = &[0x87];
+// :path /
+const PATH_ROOT: &[u8] = &[0x84];
+// :authority example.com, incremental indexing, huffman encoded
+const AUTHORITY: &[u8] = &[0x41, 0x88, 0x2f, 0x91, 0xd3, 0x5d, 0x05, 0x5c, 0x87, 0xa7];
+// user-agent: h2
+const USER_AGENT: &[u8] = &[0x7a, 0x82, 0x9c, 0x5f];
+// accept: */*
+const ACCEPT: &[u8] = &[0x53, 0x83, 0xf9, 0x63, 0xe7];
+// accept-encoding: gzip, deflate, br
+const ACCEPT_ENCODING: &[u8] = &[
+    0x50, 0x8d, 0x9b, 0xd9, 0xab, 0xfa, 0x52, 0x42, 0xcb, 0x40, 0xd2, 0x5f, 0xa5, 0x23, 0xb3,
+];
+
+#[tokio::test]
+async fn chrome_profile_wire() {
+    assert_profile_wire(
+        client::Profile::chrome(),
+        &[
+            // SETTINGS: 1:65536;2:0;4:6291456;6:262144
+            &[
+                0, 0, 24, 4, 0, 0, 0, 0, 0, //
+                0, 1, 0, 1, 0, 0, //
+                0, 2, 0, 0, 0, 0, //
+                0, 4, 0, 0x60, 0, 0, //
+                0, 6, 0, 4, 0, 0,
+            ],
+            // WINDOW_UPDATE: 15663105
+            &[0, 0, 4, 8, 0, 0, 0, 0, 0, 0, 0xef, 0, 1],
+            // HEADERS: END_STREAM | END_HEADERS | PRIORITY, exclusive on 0, weight 256
+            &[0, 0, 42, 1, 0x25, 0, 0, 0, 1, 0x80, 0, 0, 0, 255],
+            METHOD_GET,
+            AUTHORITY,
+            SCHEME_HTTPS,
+            PATH_ROOT,
+            USER_AGENT,
+            ACCEPT,
+            ACCEPT_ENCODING,
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn firefox_profile_wire() {
+    assert_profile_wire(
+        client::Profile::firefox(),
+        &[
+            // SETTINGS: 1:65536;2:0;4:131072;5:16384
+            &[
+                0, 0, 24, 4, 0, 0, 0, 0, 0, //
+                0, 1, 0, 1, 0, 0, //
+                0, 2, 0, 0, 0, 0, //
+                0, 4, 0, 2, 0, 0, //
+                0, 5, 0, 0, 0x40, 0,
+            ],
+            // WINDOW_UPDATE: 12517377
+            &[0, 0, 4, 8, 0, 0, 0, 0, 0, 0, 0xbf, 0, 1],
+            // HEADERS: END_STREAM | END_HEADERS | PRIORITY, on 0, weight 42
+            &[0, 0, 42, 1, 0x25, 0, 0, 0, 1, 0, 0, 0, 0, 41],
+            METHOD_GET,
+            PATH_ROOT,
+            AUTHORITY,
+            SCHEME_HTTPS,
+            USER_AGENT,
+            ACCEPT,
+            ACCEPT_ENCODING,
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn safari_profile_wire() {
+    assert_profile_wire(
+        client::Profile::safari(),
+        &[
+            // SETTINGS: 2:0;3:100;4:2097152;9:1
+            &[
+                0, 0, 24, 4, 0, 0, 0, 0, 0, //
+                0, 2, 0, 0, 0, 0, //
+                0, 3, 0, 0, 0, 100, //
+                0, 4, 0, 0x20, 0, 0, //
+                0, 9, 0, 0, 0, 1,
+            ],
+            // WINDOW_UPDATE: 10420225
+            &[0, 0, 4, 8, 0, 0, 0, 0, 0, 0, 0x9f, 0, 1],
+            // HEADERS: END_STREAM | END_HEADERS
+            &[0, 0, 37, 1, 0x5, 0, 0, 0, 1],
+            METHOD_GET,
+            SCHEME_HTTPS,
+            AUTHORITY,
+            PATH_ROOT,
+            ACCEPT,
+            USER_AGENT,
+            ACCEPT_ENCODING,
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn safari_profile_clears_stream_dependency() {
+    use h2::ext::StreamDependency;
+
+    // Safari disables RFC 7540 priorities, so the dependency set before is
+    // dropped.
+    let mut builder = client::Builder::new();
+    builder
+        .headers_stream_dependency(StreamDependency::new(StreamId::ZERO, 41, false))
+        .profile(client::Profile::safari());
+
+    assert_wire(
+        builder,
+        1,
+        &[
+            // SETTINGS: 2:0;3:100;4:2097152;9:1
+            &[
+                0, 0, 24, 4, 0, 0, 0, 0, 0, //
+                0, 2, 0, 0, 0, 0, //
+                0, 3, 0, 0, 0, 100, //
+                0, 4, 0, 0x20, 0, 0, //
+                0, 9, 0, 0, 0, 1,
+            ],
+            // WINDOW_UPDATE: 10420225
+            &[0, 0, 4, 8, 0, 0, 0, 0, 0, 0, 0x9f, 0, 1],
+            // HEADERS: END_STREAM | END_HEADERS
+            &[0, 0, 37, 1, 0x5, 0, 0, 0, 1],
+            METHOD_GET,
+            SCHEME_HTTPS,
+            AUTHORITY,
+            PATH_ROOT,
+            ACCEPT,
+            USER_AGENT,
+            ACCEPT_ENCODING,
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn fingerprint_wire() {
+    let fingerprint: Fingerprint =
+        "1:65536;4:131072;5:16384;10:1|12517377|3:0:0:201,5:0:7:101|m,p,a,s"
+            .parse()
+            .unwrap();
+    let mut builder = client::Builder::new();
+    builder.apply_fingerprint(&fingerprint).unwrap();
+
+    assert_wire(
+        builder,
+        7,
+        &[
+            // SETTINGS: 1:65536;4:131072;5:16384;10:1
+            &[
+                0, 0, 24, 4, 0, 0, 0, 0, 0, //
+                0, 1, 0, 1, 0, 0, //
+                0, 4, 0, 2, 0, 0, //
+                0, 5, 0, 0, 0x40, 0, //
+                0, 10, 0, 0, 0, 1,
+            ],
+            // WINDOW_UPDATE: 12517377
+            &[0, 0, 4, 8, 0, 0, 0, 0, 0, 0, 0xbf, 0, 1],
+            // PRIORITY: stream 3 on 0, weight 201
+            &[0, 0, 5, 2, 0, 0, 0, 0, 3, 0, 0, 0, 0, 200],
+            // PRIORITY: stream 5 on 7, weight 101
+            &[0, 0, 5, 2, 0, 0, 0, 0, 5, 0, 0, 0, 7, 100],
+            // HEADERS: END_STREAM | END_HEADERS, on the first stream after
+            // the PRIORITY frames
+            &[0, 0, 37, 1, 0x5, 0, 0, 0, 7],
+            METHOD_GET,
+            PATH_ROOT,
+            AUTHORITY,
+            SCHEME_HTTPS,
+            ACCEPT_ENCODING,
+            ACCEPT,
+            USER_AGENT,
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn fingerprint_without_priorities_resets_first_stream() {
+    let mut builder = client::Builder::new();
+    for fingerprint in [
+        "1:65536;4:131072;5:16384;10:1|12517377|3:0:0:201,5:0:7:101|m,p,a,s",
+        "1:65536;4:131072;5:16384;10:1|12517377|0|m,p,a,s",
+    ] {
+        let fingerprint: Fingerprint = fingerprint.parse().unwrap();
+        builder.apply_fingerprint(&fingerprint).unwrap();
+    }
+
+    assert_wire(
+        builder,
+        1,
+        &[
+            // SETTINGS: 1:65536;4:131072;5:16384;10:1
+            &[
+                0, 0, 24, 4, 0, 0, 0, 0, 0, //
+                0, 1, 0, 1, 0, 0, //
+                0, 4, 0, 2, 0, 0, //
+                0, 5, 0, 0, 0x40, 0, //
+                0, 10, 0, 0, 0, 1,
+            ],
+            // WINDOW_UPDATE: 12517377
+            &[0, 0, 4, 8, 0, 0, 0, 0, 0, 0, 0xbf, 0, 1],
+            // HEADERS: END_STREAM | END_HEADERS, on the first stream
+            &[0, 0, 37, 1, 0x5, 0, 0, 0, 1],
+            METHOD_GET,
+            PATH_ROOT,
+            AUTHORITY,
+            SCHEME_HTTPS,
+            ACCEPT_ENCODING,
+            ACCEPT,
+            USER_AGENT,
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn preface_script_wire() {
+    use h2::ext::{Priority, StreamDependency};
+
+    let script = client::PrefaceScript::builder()
+        .ping([1, 2, 3, 4, 5, 6, 7, 8])
+        .window_update(12_517_377)
+        .flush()
+        .priority(Priority::new(
+            StreamId::from(3),
+            StreamDependency::new(StreamId::ZERO, 200, false),
+        ))
+        .build();
+    let mut builder = client::Builder::new();
+    builder.max_concurrent_streams(100).preface(script);
+
+    assert_wire(
+        builder,
+        5,
+        &[
+            // SETTINGS: 3:100
+            &[0, 0, 6, 4, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 100],
+            // PING
+            &[0, 0, 8, 6, 0, 0, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8],
+            // WINDOW_UPDATE: 12517377
+            &[0, 0, 4, 8, 0, 0, 0, 0, 0, 0, 0xbf, 0, 1],
+            // PRIORITY: stream 3 on 0, weight 201
+            &[0, 0, 5, 2, 0, 0, 0, 0, 3, 0, 0, 0, 0, 200],
+            // HEADERS: END_STREAM | END_HEADERS, on the first stream after
+            // the PRIORITY frame
+            &[0, 0, 37, 1, 0x5, 0, 0, 0, 5],
+            METHOD_GET,
+            SCHEME_HTTPS,
+            AUTHORITY,
+            PATH_ROOT,
+            ACCEPT_ENCODING,
+            ACCEPT,
+            USER_AGENT,
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn preface_script_replaces_window_update() {
+    use h2::ext::{Priority, StreamDependency};
+
+    let script = client::PrefaceScript::builder()
+        .ping([0; 8])
+        .ping([1; 8])
+        .build();
+    let mut builder = client::Builder::new();
+    builder
+        .max_concurrent_streams(100)
+        .initial_connection_window_size(1_000_000)
+        // Replaced below: its PRIORITY frame no longer moves the first stream.
+        .preface(
+            client::PrefaceScript::builder()
+                .priority(Priority::new(
+                    StreamId::from(3),
+                    StreamDependency::new(StreamId::ZERO, 200, false),
+                ))
+                .build(),
+        )
+        .preface(script);
+
+    assert_wire(
+        builder,
+        1,
+        &[
+            // SETTINGS: 3:100
+            &[0, 0, 6, 4, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 100],
+            // PING
+            &[0, 0, 8, 6, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            // PING
+            &[0, 0, 8, 6, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1],
+            // HEADERS: END_STREAM | END_HEADERS, no WINDOW_UPDATE before it
+            &[0, 0, 37, 1, 0x5, 0, 0, 0, 1],
+            METHOD_GET,
+            SCHEME_HTTPS,
+            AUTHORITY,
+            PATH_ROOT,
+            ACCEPT_ENCODING,
+            ACCEPT,
+            USER_AGENT,
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn preface_script_ignores_connection_priorities() {
+    use h2::ext::{Priorities, Priority, StreamDependency};
+
+    let priorities = Priorities::builder()
+        .push(Priority::new(
+            StreamId::from(3),
+            StreamDependency::new(StreamId::ZERO, 100, true),
+        ))
+        .build();
+    let script = client::PrefaceScript::builder()
+        .priority(Priority::new(
+            StreamId::from(3),
+            StreamDependency::new(StreamId::ZERO, 200, false),
+        ))
+        .build();
+    let mut builder = client::Builder::new();
+    builder
+        .max_concurrent_streams(100)
+        .priorities(priorities)
+        .preface(script);
+
+    assert_wire(
+        builder,
+        5,
+        &[
+            // SETTINGS: 3:100
+            &[0, 0, 6, 4, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 100],
+            // PRIORITY: stream 3 on 0, weight 201, from the script only
+            &[0, 0, 5, 2, 0, 0, 0, 0, 3, 0, 0, 0, 0, 200],
+            // HEADERS: END_STREAM | END_HEADERS
+            &[0, 0, 37, 1, 0x5, 0, 0, 0, 5],
+            METHOD_GET,
+            SCHEME_HTTPS,
+            AUTHORITY,
+            PATH_ROOT,
+            ACCEPT_ENCODING,
+            ACCEPT,
+            USER_AGENT,
+        ],
+    )
+    .await;
+}
+
+#[test]
+fn fingerprint_invalid_for_client() {
+    let fingerprint: Fingerprint = "2:2|00|0|m,a,s,p".parse().unwrap();
+    let mut builder = client::Builder::new();
+    let err = builder.apply_fingerprint(&fingerprint).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid HTTP/2 fingerprint: invalid value for SETTINGS parameter 2"
+    );
+}
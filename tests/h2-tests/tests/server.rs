@@ -69,6 +69,153 @@ async fn server_builder_set_max_concurrent_streams() {
     join(client, h2).await;
 }
 
+#[tokio::test]
+async fn server_builder_settings_order() {
+    use h2::frame::{ExperimentalSettings, Setting, SettingId, SettingsOrder};
+    use tokio::io::AsyncReadExt;
+
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        client.write_preface().await;
+        client.send_frame(frames::settings()).await;
+
+        // SETTINGS: 9:1;10:1;3:100;1:65536
+        let expected = [
+            0, 0, 24, 4, 0, 0, 0, 0, 0, //
+            0, 9, 0, 0, 0, 1, //
+            0, 10, 0, 0, 0, 1, //
+            0, 3, 0, 0, 0, 100, //
+            0, 1, 0, 1, 0, 0,
+        ];
+        let mut actual = [0; 33];
+        client.read_exact(&mut actual).await.unwrap();
+        assert_eq!(actual, expected);
+
+        client.send_frame(frames::settings_ack()).await;
+        client.recv_frame(frames::settings_ack()).await;
+    };
+
+    let mut builder = server::Builder::new();
+    builder
+        .max_concurrent_streams(100)
+        .header_table_size(65_536)
+        .no_rfc7540_priorities(true)
+        .experimental_settings(
+            ExperimentalSettings::builder()
+                .push(Setting::from_id(10, 1))
+                .build(),
+        )
+        .settings_order(
+            SettingsOrder::builder()
+                .push(SettingId::NoRfc7540Priorities)
+                .push(SettingId::Unknown(10))
+                .push(SettingId::MaxConcurrentStreams)
+                .push(SettingId::HeaderTableSize)
+                .build(),
+        );
+
+    let srv = async move {
+        let mut srv = builder.handshake::<_, Bytes>(io).await.expect("handshake");
+        assert!(srv.next().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn server_builder_headers_order() {
+    use h2::ext::{HeaderOrder, PseudoId, PseudoOrder};
+    use http::header;
+
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        client.assert_server_handshake().await;
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/")
+                    .eos(),
+            )
+            .await;
+
+        let frame = client.next().await.unwrap().unwrap();
+        let (pseudo, fields) = match frame {
+            frame::Frame::PushPromise(frame) => frame.into_parts(),
+            frame => panic!("expected PUSH_PROMISE; actual={:?}", frame),
+        };
+        let order: Vec<_> = pseudo.order.into_iter().copied().take(4).collect();
+        assert_eq!(
+            order,
+            [
+                PseudoId::Method,
+                PseudoId::Path,
+                PseudoId::Authority,
+                PseudoId::Scheme,
+            ]
+        );
+        let names: Vec<_> = fields.keys().cloned().collect();
+        assert_eq!(names, [header::USER_AGENT, header::ACCEPT]);
+
+        let headers = assert_headers!(client.next().await.unwrap().unwrap());
+        assert_eq!(headers.stream_id(), 1);
+        let names: Vec<_> = headers.fields().keys().cloned().collect();
+        assert_eq!(
+            names,
+            [header::CONTENT_TYPE, header::CONTENT_LENGTH, header::SERVER]
+        );
+    };
+
+    let mut builder = server::Builder::new();
+    builder
+        .headers_pseudo_order(
+            PseudoOrder::builder()
+                .extend([
+                    PseudoId::Method,
+                    PseudoId::Path,
+                    PseudoId::Authority,
+                    PseudoId::Scheme,
+                ])
+                .build(),
+        )
+        .headers_order(
+            HeaderOrder::builder()
+                .push(header::CONTENT_TYPE)
+                .push(header::CONTENT_LENGTH)
+                .push(header::USER_AGENT)
+                .build(),
+        );
+
+    let srv = async move {
+        let mut srv = builder.handshake::<_, Bytes>(io).await.expect("handshake");
+        let (_, mut stream) = srv.next().await.unwrap().unwrap();
+
+        let req = http::Request::get("https://example.com/style.css")
+            .header(header::ACCEPT, "text/css")
+            .header(header::USER_AGENT, "h2")
+            .body(())
+            .unwrap();
+        stream.push_request(req).unwrap();
+
+        // The fields are deliberately inserted in a different order.
+        let rsp = http::Response::builder()
+            .status(200)
+            .header(header::SERVER, "h2")
+            .header(header::CONTENT_LENGTH, "0")
+            .header(header::CONTENT_TYPE, "text/plain")
+            .body(())
+            .unwrap();
+        stream.send_response(rsp, true).unwrap();
+
+        assert!(srv.next().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
 #[tokio::test]
 async fn serve_request() {
     h2_support::trace_init!();
@@ -104,6 +251,67 @@ async fn serve_request() {
     join(client, srv).await;
 }
 
+#[tokio::test]
+async fn recv_request_pseudo_order() {
+    use h2::ext::{PseudoId, PseudoOrder};
+
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let order = PseudoOrder::builder()
+        .extend([
+            PseudoId::Method,
+            PseudoId::Path,
+            PseudoId::Authority,
+            PseudoId::Scheme,
+        ])
+        .build();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        let mut pseudo =
+            frame::Pseudo::request(Method::GET, "https://example.com/".parse().unwrap(), None);
+        pseudo.set_pseudo_order(order);
+        client
+            .send_frame(frames::headers(1).pseudo(pseudo).eos())
+            .await;
+        client
+            .recv_frame(frames::headers(1).response(200).eos())
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+        let (req, mut stream) = srv.next().await.unwrap().unwrap();
+
+        // Only the fields that were received are listed.
+        let order: Vec<_> = req
+            .extensions()
+            .get::<PseudoOrder>()
+            .unwrap()
+            .into_iter()
+            .copied()
+            .collect();
+        assert_eq!(
+            order,
+            [
+                PseudoId::Method,
+                PseudoId::Path,
+                PseudoId::Authority,
+                PseudoId::Scheme,
+            ]
+        );
+
+        let rsp = http::Response::builder().status(200).body(()).unwrap();
+        stream.send_response(rsp, true).unwrap();
+
+        assert!(srv.next().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
 #[tokio::test]
 async fn serve_connect() {
     h2_support::trace_init!();
@@ -1552,3 +1760,70 @@ async fn init_window_size_smaller_than_default_should_use_default_before_ack() {
 
     join(client, h2).await;
 }
+
+#[tokio::test]
+async fn peer_fingerprint() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        client.write_preface().await;
+        // SETTINGS: 4:6291456;2:0;2570:7, the last one being unknown
+        client
+            .send_bytes(&[
+                0, 0, 18, 4, 0, 0, 0, 0, 0, //
+                0, 4, 0, 0x60, 0, 0, //
+                0, 2, 0, 0, 0, 0, //
+                0x0a, 0x0a, 0, 0, 0, 7,
+            ])
+            .await;
+        let settings = assert_settings!(client.next().await.unwrap().unwrap());
+        assert!(!settings.is_ack());
+        client.send_frame(frames::settings_ack()).await;
+        client.recv_frame(frames::settings_ack()).await;
+
+        client.send_frame(frames::window_update(0, 15663105)).await;
+        // PRIORITY: stream 3 on 0, weight 201
+        client
+            .send_bytes(&[0, 0, 5, 2, 0, 0, 0, 0, 3, 0, 0, 0, 0, 200])
+            .await;
+        // HEADERS: END_STREAM | END_HEADERS, pseudo fields in m,p,a,s order
+        client
+            .send_bytes(&[
+                0, 0, 13, 1, 0x5, 0, 0, 0, 1,    //
+                0x82, //
+                0x84, //
+                0x41, 0x88, 0x2f, 0x91, 0xd3, 0x5d, 0x05, 0x5c, 0x87, 0xa7, //
+                0x87,
+            ])
+            .await;
+        // Neither of these is part of the fingerprint.
+        client.send_frame(frames::window_update(0, 1024)).await;
+        client
+            .send_bytes(&[0, 0, 5, 2, 0, 0, 0, 0, 5, 0, 0, 0, 0, 16])
+            .await;
+        client
+            .recv_frame(frames::headers(1).response(200).eos())
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+        let (req, mut stream) = srv.next().await.unwrap().unwrap();
+        assert_eq!(req.uri(), "https://example.com/");
+
+        let rsp = http::Response::builder().status(200).body(()).unwrap();
+        stream.send_response(rsp, true).unwrap();
+
+        assert!(srv.next().await.is_none());
+
+        let fingerprint = srv.peer_fingerprint();
+        assert_eq!(fingerprint.window_update(), Some(15663105));
+        assert_eq!(
+            fingerprint.to_string(),
+            "4:6291456;2:0;2570:7|15663105|3:0:0:201|m,p,a,s"
+        );
+    };
+
+    join(client, srv).await;
+}
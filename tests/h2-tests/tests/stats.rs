@@ -0,0 +1,268 @@
+use futures::future::join;
+use h2::ext::{FrameKind, Initiator};
+use h2_support::prelude::*;
+
+#[tokio::test]
+async fn client_connection_stats() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200)).await;
+        srv.send_frame(frames::data(1, "hello").eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut conn) = client::handshake(io).await.unwrap();
+
+        let request = Request::builder()
+            .uri("https://example.com/")
+            .body(())
+            .unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+        let response = conn.drive(response).await.unwrap();
+        let body = conn.drive(util::concat(response.into_body())).await;
+        assert_eq!(body.unwrap(), "hello");
+
+        let stats = conn.stats();
+
+        let sent = stats.frames_sent();
+        assert_eq!(sent.frames(FrameKind::Headers), 1);
+        // The preface SETTINGS and the ACK of the server SETTINGS.
+        assert_eq!(sent.frames(FrameKind::Settings), 2);
+        assert_eq!(sent.frames(FrameKind::Data), 0);
+        assert_eq!(sent.total_frames(), 3);
+
+        let received = stats.frames_received();
+        assert_eq!(received.frames(FrameKind::Data), 1);
+        assert_eq!(received.bytes(FrameKind::Data), 9 + 5);
+        assert_eq!(received.frames(FrameKind::Headers), 1);
+        assert_eq!(
+            received.iter().map(|(kind, ..)| kind).collect::<Vec<_>>(),
+            [FrameKind::Data, FrameKind::Headers, FrameKind::Settings]
+        );
+
+        assert_eq!(stats.streams().local_opened(), 1);
+        assert_eq!(stats.streams().local_closed(), 1);
+        assert_eq!(stats.streams().remote_opened(), 0);
+        assert_eq!(stats.streams().total_reset(), 0);
+
+        assert_eq!(stats.send_window(), 65_535);
+        assert_eq!(stats.recv_window(), 65_535 - 5);
+
+        // Only `:authority` is added to the dynamic table.
+        assert_eq!(stats.encoder_table().entries(), 1);
+        assert_eq!(stats.encoder_table().size(), 32 + 10 + 11);
+        assert_eq!(stats.decoder_table().entries(), 0);
+        assert_eq!(stats.decoder_table().max_size(), 4096);
+
+        conn.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn client_stats_count_resets() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(frames::headers(1).request("POST", "https://example.com/"))
+            .await;
+        srv.recv_frame(frames::reset(1).cancel()).await;
+        srv.recv_frame(frames::headers(3).request("POST", "https://example.com/"))
+            .await;
+        srv.send_frame(frames::reset(3).refused()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut conn) = client::handshake(io).await.unwrap();
+
+        let request = || {
+            Request::builder()
+                .method(Method::POST)
+                .uri("https://example.com/")
+                .body(())
+                .unwrap()
+        };
+
+        let (_response, mut stream) = client.send_request(request(), false).unwrap();
+        conn.drive(util::yield_once()).await;
+        stream.send_reset(Reason::CANCEL);
+        conn.drive(util::yield_once()).await;
+
+        let (response, _stream) = client.send_request(request(), false).unwrap();
+        let err = conn.drive(response).await.unwrap_err();
+        assert_eq!(err.reason(), Some(Reason::REFUSED_STREAM));
+
+        let stats = conn.stats();
+        let streams = stats.streams();
+        assert_eq!(streams.local_opened(), 2);
+        assert_eq!(streams.local_closed(), 2);
+        assert_eq!(streams.reset(Initiator::User, Reason::CANCEL), 1);
+        assert_eq!(streams.reset(Initiator::Remote, Reason::REFUSED_STREAM), 1);
+        assert_eq!(streams.reset(Initiator::Remote, Reason::CANCEL), 0);
+        assert_eq!(streams.total_reset(), 2);
+
+        assert_eq!(stats.frames_sent().frames(FrameKind::Reset), 1);
+        assert_eq!(stats.frames_sent().bytes(FrameKind::Reset), 9 + 4);
+        assert_eq!(stats.frames_received().frames(FrameKind::Reset), 1);
+
+        drop(client);
+        conn.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn server_connection_stats() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(frames::headers(1).request("POST", "https://example.com/"))
+            .await;
+        client
+            .send_frame(frames::data(1, "hello world").eos())
+            .await;
+        client
+            .recv_frame(frames::headers(1).response(200).eos())
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+        let (req, mut stream) = srv.accept().await.unwrap().unwrap();
+        let body = util::concat(req.into_body()).await.unwrap();
+        assert_eq!(body, "hello world");
+
+        let rsp = http::Response::builder().status(200).body(()).unwrap();
+        stream.send_response(rsp, true).unwrap();
+        assert!(srv.accept().await.is_none());
+
+        let stats = srv.stats();
+        assert_eq!(stats.streams().remote_opened(), 1);
+        assert_eq!(stats.streams().remote_closed(), 1);
+        assert_eq!(stats.streams().local_opened(), 0);
+        assert_eq!(stats.frames_received().frames(FrameKind::Data), 1);
+        assert_eq!(stats.frames_received().bytes(FrameKind::Data), 9 + 11);
+        assert_eq!(stats.frames_sent().frames(FrameKind::Headers), 1);
+        assert_eq!(stats.recv_window(), 65_535 - 11);
+        assert_eq!(stats.decoder_table().entries(), 1);
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn client_stream_stats() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(frames::headers(1).request("POST", "https://example.com/"))
+            .await;
+        srv.recv_frame(frames::data(1, "hello").eos()).await;
+        srv.send_frame(frames::headers(1).response(200)).await;
+        srv.send_frame(frames::data(1, "world")).await;
+        srv.send_frame(frames::data(1, "!").eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut conn) = client::handshake(io).await.unwrap();
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("https://example.com/")
+            .body(())
+            .unwrap();
+        let (mut response, mut stream) = client.send_request(request, false).unwrap();
+
+        let stats = response.stats();
+        assert!(stats.headers_queued_at().is_some());
+        assert!(stats.headers_flushed_at().is_none());
+
+        stream.send_data("hello".into(), true).unwrap();
+        let resp = conn.drive(&mut response).await.unwrap();
+
+        let stats = stream.stats();
+        assert!(stats.headers_flushed_at() >= stats.headers_queued_at());
+        assert!(stats.headers_received_at() >= stats.headers_flushed_at());
+        assert_eq!(stats.data_frames_sent(), 1);
+        assert_eq!(stats.bytes_sent(), 5);
+        assert_eq!(stats.flow_control_blocked(), Duration::ZERO);
+
+        let mut body = resp.into_body();
+        assert_eq!(conn.drive(body.data()).await.unwrap().unwrap(), "world");
+        assert_eq!(conn.drive(body.data()).await.unwrap().unwrap(), "!");
+
+        let stats = body.stats();
+        assert!(stats.data_received_at() >= stats.headers_received_at());
+        assert_eq!(stats.data_frames_received(), 2);
+        assert_eq!(stats.bytes_received(), 6);
+
+        drop((response, stream, body));
+        conn.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn stream_stats_flow_control_blocked() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client
+            .assert_server_handshake_with_settings(frames::settings().initial_window_size(0))
+            .await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/")
+                    .eos(),
+            )
+            .await;
+        client.recv_frame(frames::headers(1).response(200)).await;
+        idle_ms(20).await;
+        client.send_frame(frames::window_update(1, 5)).await;
+        client.recv_frame(frames::data(1, "hello").eos()).await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+        let (_, mut stream) = srv.accept().await.unwrap().unwrap();
+
+        let rsp = http::Response::builder().status(200).body(()).unwrap();
+        let mut send = stream.send_response(rsp, false).unwrap();
+        send.send_data("hello".into(), true).unwrap();
+        assert!(srv.accept().await.is_none());
+
+        let stats = stream.stats();
+        assert!(stats.headers_flushed_at() >= stats.headers_queued_at());
+        assert!(stats.headers_received_at() <= stats.headers_queued_at());
+        assert_eq!(stats.bytes_sent(), 5);
+        assert!(stats.flow_control_blocked() >= Duration::from_millis(20));
+    };
+
+    join(client, srv).await;
+}